@@ -0,0 +1,41 @@
+//! Compare building day 12's regions and day 16's graph with their scratch
+//! space in a bump arena to building them with plain vectors. Run them with
+//! `cargo bench --features arena --bench arena`.
+use omalley_aoc2024 as aoc_lib;
+use aoc_lib::y2024::{day12, day16};
+use bumpalo::Bump;
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+
+/// Read the day's input, or None if it isn't available.
+fn input(day: &str) -> Option<String> {
+  aoc_lib::utils::read_inputs("input", &[day], &[true]).ok().map(|mut inputs| inputs.remove(0))
+}
+
+fn arena_benchmark(c: &mut Criterion) {
+  let mut arena = Bump::new();
+  if let Some(data) = input("day12") {
+    let grid: day12::Grid = data.parse().expect("can't parse input");
+    c.bench_function("day12 regions vec", |b| {
+      b.iter_batched(|| grid.clone(), day12::Input::new, BatchSize::SmallInput)
+    });
+    c.bench_function("day12 regions arena", |b| {
+      b.iter_batched(|| grid.clone(), |grid| {
+        let input = day12::Input::new_in(grid, &arena);
+        arena.reset();
+        input
+      }, BatchSize::SmallInput)
+    });
+  }
+  if let Some(data) = input("day16") {
+    let grid: day16::Grid = data.parse().expect("can't parse input");
+    c.bench_function("day16 graph vec", |b| b.iter(|| day16::Graph::from_grid(&grid)));
+    c.bench_function("day16 graph arena", |b| b.iter(|| {
+      let graph = day16::Graph::from_grid_in(&grid, &arena);
+      arena.reset();
+      graph
+    }));
+  }
+}
+
+criterion_group!(arena, arena_benchmark);
+criterion_main!(arena);
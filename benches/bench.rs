@@ -1,11 +1,13 @@
 use omalley_aoc2024 as aoc_lib;
+use aoc_lib::testgen::XorShift;
 use criterion::{criterion_group, criterion_main, Criterion};
+use itertools::Itertools;
 
 macro_rules! benchmarks_internal {
-  ( $($day:ident),* ) => {
+  ( $($day:ident),* ; $($extra:ident),* ) => {
     paste::paste!{
       $(fn [<$day _benchmark>](c: &mut Criterion) {
-          use aoc_lib::$day;
+          use aoc_lib::y2024::$day;
           let input_data = aoc_lib::utils::read_inputs("input", &vec![stringify!($day)], &vec![true])
             .expect("can't read input");
           let input = $day::generator(&input_data[0]);
@@ -22,16 +24,176 @@ macro_rules! benchmarks_internal {
         criterion_group!($day, [<$day _benchmark>]);
       )*
 
-      criterion_main!($($day),*);
+      criterion_main!($($day,)* $($extra),*);
     }
   };
 }
 
 #[macro_export]
 macro_rules! benchmarks {
-  ( $($day:literal),* ) => {
-    paste::paste!{ benchmarks_internal!{$( [<day $day>] ),*} }
+  ( $($day:literal),* $(; $($extra:ident),*)? ) => {
+    paste::paste!{ benchmarks_internal!{$( [<day $day>] ),* ; $($($extra),*)?} }
   }
 }
 
-benchmarks!(11);
+/// Generate a random square maze with some of the walls knocked out so that
+/// there are loops. The start is in the bottom left and the exit is in the top right.
+fn generate_maze(size: usize, seed: u64) -> String {
+  let mut rng = XorShift::new(seed);
+  let mut grid = vec![vec!['#'; size]; size];
+  let mut pending = vec![(1, 1)];
+  grid[1][1] = '.';
+  while let Some(&(y, x)) = pending.last() {
+    let options: Vec<(usize, usize)> = [(-2, 0), (2, 0), (0, -2), (0, 2)].iter()
+        .map(|&(dy, dx)| (y as isize + dy, x as isize + dx))
+        .filter(|&(ny, nx)| ny > 0 && nx > 0 && ny < size as isize - 1 &&
+            nx < size as isize - 1)
+        .map(|(ny, nx)| (ny as usize, nx as usize))
+        .filter(|&(ny, nx)| grid[ny][nx] == '#')
+        .collect();
+    if options.is_empty() {
+      pending.pop();
+    } else {
+      let (ny, nx) = options[rng.below(options.len())];
+      grid[(y + ny) / 2][(x + nx) / 2] = '.';
+      grid[ny][nx] = '.';
+      pending.push((ny, nx));
+    }
+  }
+  for row in grid.iter_mut().take(size - 1).skip(1) {
+    for cell in row.iter_mut().take(size - 1).skip(1) {
+      if *cell == '#' && rng.next_u64().is_multiple_of(20) {
+        *cell = '.';
+      }
+    }
+  }
+  grid[size - 2][1] = 'S';
+  grid[1][size - 2] = 'E';
+  grid.iter().map(|row| row.iter().collect::<String>()).join("\n")
+}
+
+fn maze_benchmark(c: &mut Criterion) {
+  use aoc_lib::y2024::day16::{generator, Costs};
+  let costs = Costs::default();
+  for size in [141, 501, 1001] {
+    let graph = generator(&generate_maze(size, 0x2024));
+    c.bench_function(&format!("day16 forward search {size}"), |b| {
+      b.iter(|| graph.minimum_cost(&costs))
+    });
+    c.bench_function(&format!("day16 bidirectional search {size}"), |b| {
+      b.iter(|| graph.bidirectional_cost(&costs))
+    });
+  }
+}
+
+criterion_group!(mazes, maze_benchmark);
+
+fn vm_benchmark(c: &mut Criterion) {
+  use aoc_lib::y2024::day17::{compile, generator, run_with_a};
+  let input_data = aoc_lib::utils::read_inputs("input", &["day17"], &[true])
+    .expect("can't read input");
+  let (state, program, _) = generator(&input_data[0]);
+  let compiled = compile(&state, &program).expect("can't compile");
+  c.bench_function("day17 interpreted 4096", |b| {
+    b.iter(|| (0..4096).map(|a| run_with_a(&state, &program, a << 30).len()).sum::<usize>())
+  });
+  c.bench_function("day17 compiled 4096", |b| {
+    b.iter(|| (0..4096).map(|a| compiled(a << 30).len()).sum::<usize>())
+  });
+}
+
+criterion_group!(vm, vm_benchmark);
+
+/// Generate a square memory space with a fifth of the cells blocked, leaving
+/// the corners and their neighbors open.
+fn generate_bytes(size: usize, seed: u64) -> String {
+  let mut rng = XorShift::new(seed);
+  let mut lines = vec![format!("size={size}")];
+  for y in 0..size {
+    for x in 0..size {
+      if rng.next_u64().is_multiple_of(5) && x + y > 1 && x + y < 2 * size - 3 {
+        lines.push(format!("{x},{y}"));
+      }
+    }
+  }
+  lines.join("\n")
+}
+
+fn bytes_benchmark(c: &mut Criterion) {
+  use aoc_lib::y2024::day18::{generator, run_part1, run_part1_astar};
+  let input_data = aoc_lib::utils::read_inputs("input", &["day18"], &[true])
+    .expect("can't read input");
+  let mut real = generator(&input_data[0]);
+  real.bytes.truncate(real.fallen);
+  let inputs = [("71", real), ("501", generator(&generate_bytes(501, 0x2024))),
+    ("1001", generator(&generate_bytes(1001, 0x2024)))];
+  for (name, memory) in &inputs {
+    c.bench_function(&format!("day18 bfs {name}"), |b| {
+      b.iter(|| run_part1(&memory.bytes, memory.bounds()))
+    });
+    c.bench_function(&format!("day18 astar {name}"), |b| {
+      b.iter(|| run_part1_astar(&memory.bytes, memory.bounds()))
+    });
+  }
+}
+
+criterion_group!(bytes, bytes_benchmark);
+
+/// Generate towels of up to 8 stripes and designs that are mostly made
+/// out of them.
+fn generate_towels(designs: usize, seed: u64) -> String {
+  let mut rng = XorShift::new(seed);
+  const COLORS: [char; 5] = ['w', 'u', 'b', 'r', 'g'];
+  let towels = (0..400).map(|_| (0..1 + rng.below(8))
+      .map(|_| COLORS[rng.below(COLORS.len())]).collect::<String>())
+      .collect_vec();
+  let lines = (0..designs).map(|_| {
+    let mut design = (0..2 + rng.below(6))
+        .map(|_| towels[rng.below(towels.len())].as_str()).collect::<String>();
+    if rng.next_u64().is_multiple_of(2) {
+      design.push(COLORS[rng.below(COLORS.len())]);
+    }
+    design
+  }).join("\n");
+  format!("{}\n\n{lines}", towels.join(", "))
+}
+
+fn towel_benchmark(c: &mut Criterion) {
+  use aoc_lib::y2024::day19::{generator, part1, part1_parallel, part2, part2_aho, part2_parallel};
+  let input_data = aoc_lib::utils::read_inputs("input", &["day19"], &[true])
+    .expect("can't read input");
+  let input = generator(&input_data[0]);
+  c.bench_function("day19 trie", |b| b.iter(|| part2(&input)));
+  c.bench_function("day19 aho-corasick", |b| b.iter(|| part2_aho(&input)));
+  let input = generator(&generate_towels(100_000, 0x2024));
+  let mut group = c.benchmark_group("day19 100k designs");
+  group.sample_size(10);
+  group.bench_function("part 1 serial", |b| b.iter(|| part1(&input)));
+  group.bench_function("part 1 parallel", |b| b.iter(|| part1_parallel(&input)));
+  group.bench_function("part 2 serial", |b| b.iter(|| part2(&input)));
+  group.bench_function("part 2 parallel", |b| b.iter(|| part2_parallel(&input)));
+  group.finish();
+}
+
+criterion_group!(towels, towel_benchmark);
+
+fn cheat_benchmark(c: &mut Criterion) {
+  use aoc_lib::y2024::day20::{do_part2, do_part2_parallel, do_part2_track, generator, DEFAULT_JUMP,
+                       DEFAULT_LIMIT};
+  let input_data = aoc_lib::utils::read_inputs("input", &["day20"], &[true])
+    .expect("can't read input");
+  let input = generator(&input_data[0]);
+  c.bench_function("day20 serial", |b| {
+    b.iter(|| do_part2(&input, DEFAULT_LIMIT, DEFAULT_JUMP))
+  });
+  c.bench_function("day20 parallel", |b| {
+    b.iter(|| do_part2_parallel(&input, DEFAULT_LIMIT, DEFAULT_JUMP))
+  });
+  c.bench_function("day20 track order", |b| {
+    b.iter(|| do_part2_track(&input, DEFAULT_LIMIT, DEFAULT_JUMP))
+  });
+}
+
+criterion_group!(cheats, cheat_benchmark);
+
+benchmarks!(11; mazes, vm, bytes, towels, cheats);
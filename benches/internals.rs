@@ -0,0 +1,46 @@
+//! Micro-benchmarks of the hot loops inside the days, so that optimizing
+//! them can be measured without the rest of the part around them. Run them
+//! with `cargo bench --features bench-internals --bench internals`.
+use omalley_aoc2024 as aoc_lib;
+use aoc_lib::params;
+use aoc_lib::y2024::{day16, day18, day19, day20, day9};
+use criterion::{criterion_group, criterion_main, Criterion};
+
+/// Read the day's input, or None if it isn't available.
+fn input(day: &str) -> Option<String> {
+  aoc_lib::utils::read_inputs("input", &[day], &[true]).ok().map(|mut inputs| inputs.remove(0))
+}
+
+fn internals_benchmark(c: &mut Criterion) {
+  if let Some(data) = input("day9") {
+    let files = day9::generator(&data);
+    c.bench_function("day9 next_file", |b| b.iter(|| day9::internals::next_files(&files)));
+  }
+  if let Some(data) = input("day16") {
+    let graph = day16::generator(&data);
+    let costs = day16::Costs::default();
+    c.bench_function("day16 minimum_cost", |b| b.iter(|| graph.minimum_cost(&costs)));
+  }
+  if let Some(data) = input("day18") {
+    let memory = day18::generator(&data);
+    let fallen = &memory.bytes[..memory.fallen.min(memory.bytes.len())];
+    c.bench_function("day18 dijkstra", |b| {
+      b.iter(|| day18::internals::run_part1_dijkstra(fallen, memory.bounds()))
+    });
+  }
+  if let Some(data) = input("day19") {
+    let towels = day19::generator(&data);
+    c.bench_function("day19 count_patterns",
+                     |b| b.iter(|| day19::internals::count_patterns(&towels)));
+  }
+  if let Some(data) = input("day20") {
+    let track = day20::generator(&data);
+    let params = params::current();
+    c.bench_function("day20 do_part2", |b| {
+      b.iter(|| day20::do_part2(&track, params.cheat_limit, params.cheat_length))
+    });
+  }
+}
+
+criterion_group!(internals, internals_benchmark);
+criterion_main!(internals);
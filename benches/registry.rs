@@ -0,0 +1,25 @@
+//! Benchmarks for every part of every day, driven by the day registry and
+//! the real inputs in the input directory. Run them with
+//! `cargo bench --features benchmarks --bench registry -- day20`.
+use omalley_aoc2024 as aoc_lib;
+use criterion::{criterion_group, criterion_main, Criterion};
+
+fn registry_benchmark(c: &mut Criterion) {
+  for solver in aoc_lib::y2024::SOLVERS {
+    let name = solver.name();
+    // skip the days whose inputs aren't available
+    let Ok(inputs) = aoc_lib::utils::read_inputs("input", &[name], &[true]) else {
+      continue;
+    };
+    let data = &inputs[0];
+    let input = solver.generator(data);
+    let mut group = c.benchmark_group(name);
+    group.bench_function("gen", |b| b.iter(|| solver.generator(data)));
+    group.bench_function("part 1", |b| b.iter(|| solver.part1(&input)));
+    group.bench_function("part 2", |b| b.iter(|| solver.part2(&input)));
+    group.finish();
+  }
+}
+
+criterion_group!(registry, registry_benchmark);
+criterion_main!(registry);
@@ -0,0 +1,8 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+  if let Ok(input) = std::str::from_utf8(data) {
+    let _ = omalley_aoc2024::y2024::day11::try_generator(input);
+  }
+});
@@ -0,0 +1,34 @@
+/* The C interface to Owen's Advent of Code 2024 solutions, from the
+ * library built with the ffi feature. See src/ffi.rs. */
+#ifndef AOC2024_H
+#define AOC2024_H
+
+#include <stddef.h>
+#include <stdint.h>
+#include <sys/types.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* The day or part isn't implemented, the input isn't valid, or the part
+ * can't answer it. */
+#define AOC2024_ERROR (-1)
+/* The input or output buffer is null. */
+#define AOC2024_NULL_POINTER (-2)
+/* The input isn't UTF-8. */
+#define AOC2024_BAD_UTF8 (-3)
+
+/* Solve one part of a day from an input of len bytes. The answer is
+ * written to out_buf as a NUL terminated string and its length is
+ * returned, like snprintf, so an answer that is as long as the capacity
+ * or longer was cut off. Errors return a negative code, and for
+ * AOC2024_ERROR the message is written to out_buf instead. */
+ssize_t aoc2024_solve(uint32_t day, uint32_t part, const uint8_t *input_ptr, size_t len,
+                      char *out_buf, size_t capacity);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
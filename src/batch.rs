@@ -0,0 +1,128 @@
+//! Running one day against every input in a directory, such as friends'
+//! inputs or generated stress inputs, and comparing the answers and times
+//! of each file.
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::utils::{DayResult, Solver};
+
+/// The .txt files in the directory in the order of their names.
+pub fn input_files(directory: &Path) -> Result<Vec<PathBuf>, String> {
+  let entries = fs::read_dir(directory)
+      .map_err(|e| format!("Error reading {}: {e}", directory.display()))?;
+  let mut files = Vec::new();
+  for entry in entries {
+    let path = entry.map_err(|e| format!("Error reading {}: {e}", directory.display()))?.path();
+    if path.is_file() && path.extension().is_some_and(|ext| ext == "txt") {
+      files.push(path);
+    }
+  }
+  files.sort();
+  Ok(files)
+}
+
+/// The result of the day on one of the files, which is an error if the
+/// file couldn't be read or parsed, or if a part couldn't answer it.
+pub struct BatchRun {
+  pub file: PathBuf,
+  pub result: Result<DayResult, String>,
+}
+
+/// Run the day on each of the files.
+pub fn run(solver: &dyn Solver, files: &[PathBuf]) -> Vec<BatchRun> {
+  files.iter()
+      .map(|file| {
+        let result = fs::read_to_string(file)
+            .map_err(|e| format!("Error reading {}: {e}", file.display()))
+            .and_then(|input| solver.try_run(&input));
+        BatchRun{file: file.clone(), result}
+      }).collect()
+}
+
+/// Draw a table with a row for each file's answers and times. The files
+/// that failed have their error instead.
+pub fn table(runs: &[BatchRun]) -> String {
+  let header = ["File", "Part 1", "Part 2", "Parse", "Part 1 time", "Part 2 time"]
+      .map(String::from);
+  let rows: Vec<Result<[String; 6], (String, &String)>> = runs.iter()
+      .map(|run| {
+        let name = run.file.file_name().unwrap_or_default().to_string_lossy().to_string();
+        match &run.result {
+          Ok(result) => Ok([name, result.part1.1.to_string(), result.part2.1.to_string(),
+                            format!("{:.2?}", result.generate_time),
+                            format!("{:.2?}", result.part1.0), format!("{:.2?}", result.part2.0)]),
+          Err(e) => Err((name, e)),
+        }
+      }).collect();
+  let mut widths = header.clone().map(|cell| cell.chars().count());
+  for row in rows.iter().flatten() {
+    for (width, cell) in widths.iter_mut().zip(row) {
+      *width = (*width).max(cell.chars().count());
+    }
+  }
+  for (name, _) in rows.iter().filter_map(|row| row.as_ref().err()) {
+    widths[0] = widths[0].max(name.chars().count());
+  }
+  let mut result = String::new();
+  for row in std::iter::once(Ok(&header)).chain(rows.iter().map(Result::as_ref)) {
+    let line = match row {
+      Ok(row) => row.iter().zip(widths).enumerate()
+          .map(|(column, (cell, width))| if column == 0 {
+            format!("{cell:<width$}")
+          } else {
+            format!("{cell:>width$}")
+          }).collect::<Vec<_>>().join("  "),
+      Err((name, e)) => format!("{name:<width$}  error: {e}", width = widths[0]),
+    };
+    writeln!(result, "{}", line.trim_end()).unwrap();
+  }
+  result
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{env, fs};
+  use crate::y2024::{day17, SOLVERS};
+  use super::{input_files, run, table};
+
+  #[test]
+  fn test_batch() {
+    let directory = env::temp_dir().join(format!("aoc2024-batch-{}", std::process::id()));
+    fs::create_dir_all(&directory).unwrap();
+    fs::write(directory.join("b.txt"), "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n").unwrap();
+    fs::write(directory.join("a.txt"), "1   x\n").unwrap();
+    fs::write(directory.join("notes.md"), "not an input").unwrap();
+    let files = input_files(&directory).unwrap();
+    assert_eq!(vec!["a.txt", "b.txt"], files.iter()
+        .map(|file| file.file_name().unwrap().to_str().unwrap()).collect::<Vec<_>>());
+    let runs = run(SOLVERS[0], &files);
+    assert!(runs[0].result.is_err());
+    let text = table(&runs);
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(3, lines.len());
+    assert!(lines[0].starts_with("File   Part 1"), "{text}");
+    assert!(lines[1].starts_with("a.txt  error: "), "{text}");
+    assert!(lines[2].starts_with("b.txt"), "{text}");
+    assert_eq!(vec!["b.txt", "11", "31"], lines[2].split_whitespace().take(3).collect::<Vec<_>>());
+    fs::remove_dir_all(directory).unwrap();
+  }
+
+  #[test]
+  fn test_batch_unanswerable() {
+    let directory = env::temp_dir().join(format!("aoc2024-batch-quine-{}", std::process::id()));
+    fs::create_dir_all(&directory).unwrap();
+    // part 2 can't find an A that makes this program print itself
+    fs::write(directory.join("a.txt"),
+              "Register A: 5\nRegister B: 0\nRegister C: 0\n\nProgram: 0,1,5,4,3,0\n").unwrap();
+    fs::write(directory.join("b.txt"),
+              "Register A: 2024\nRegister B: 0\nRegister C: 0\n\nProgram: 0,3,5,4,3,0\n").unwrap();
+    let solver = SOLVERS[16];
+    assert_eq!("day17", solver.name());
+    let runs = run(solver, &input_files(&directory).unwrap());
+    assert_eq!(Some(day17::NO_QUINE.to_string()), runs[0].result.as_ref().err().cloned());
+    // while the next file still runs
+    assert_eq!("117440", runs[1].result.as_ref().unwrap().part2.1.to_string());
+    assert!(table(&runs).contains(&format!("a.txt  error: {}", day17::NO_QUINE)));
+    fs::remove_dir_all(directory).unwrap();
+  }
+}
@@ -0,0 +1,223 @@
+//! Start a new day: `cargo run --bin new-day -- 22` writes
+//! src/y2024/day22.rs with stubs for the four functions, adds the day to
+//! the day_list! in src/y2024.rs, and sets up an empty example in
+//! tests/data and a fuzz target.
+use std::fs;
+use std::path::{Path, PathBuf};
+use argh::FromArgs;
+
+#[derive(FromArgs)]
+/** Create the files for a new day and register it. */
+struct Args {
+  /// the repository to change (defaults to the current directory)
+  #[argh(option, default="String::from(\".\")")]
+  root: String,
+
+  /// the year of the day, which must already have its module (defaults to 2024)
+  #[argh(option, default="2024")]
+  year: usize,
+
+  /// the puzzle's title (defaults to "Day N")
+  #[argh(option)]
+  title: Option<String>,
+
+  /// the day to create
+  #[argh(positional)]
+  day: usize,
+}
+
+/// The new day's source with a generator that splits the lines and parts
+/// that don't do anything yet.
+fn template(year: usize, day: usize, title: &str) -> String {
+  format!(r#"use std::io::BufRead;
+use crate::utils::parse::parse_lines;
+
+pub const TITLE: &str = "{title}";
+
+pub fn try_generator(input: &str) -> Result<Vec<String>, String> {{
+  Ok(input.lines().map(|line| line.to_string()).collect())
+}}
+
+pub fn generator(input: &str) -> Vec<String> {{
+  try_generator(input).expect("Can't parse input")
+}}
+
+pub fn generator_from_reader(reader: impl BufRead) -> Result<Vec<String>, String> {{
+  parse_lines(reader, |line| Ok(line.to_string()))
+}}
+
+pub fn part1(input: &[String]) -> usize {{
+  input.len()
+}}
+
+pub fn part2(_input: &[String]) -> usize {{
+  0
+}}
+
+#[cfg(test)]
+mod tests {{
+  use super::{{generator, part1}};
+
+  // the example from https://adventofcode.com/{year}/day/{day}
+  const INPUT: &str = "";
+
+  #[test]
+  fn test_part1() {{
+    assert_eq!(0, part1(&generator(INPUT)));
+  }}
+}}
+"#)
+}
+
+/// Add the day to the year's day_list! invocation, keeping the days in
+/// order.
+fn register(module: &str, day: usize) -> Result<String, String> {
+  let list = module.find("day_list!(").ok_or("Can't find day_list!")?;
+  let start = list + module[list..].find("=>").ok_or("Can't find the year in day_list!")?
+      + "=>".len();
+  let end = start + module[start..].find(')').ok_or("Can't find the end of day_list!")?;
+  let mut days = module[start..end].split(',')
+      .map(|day| day.trim().parse::<usize>().map_err(|_| format!("Bad day '{day}' in day_list!")))
+      .collect::<Result<Vec<_>, _>>()?;
+  if days.contains(&day) {
+    return Err(format!("Day {day} is already registered"));
+  }
+  days.push(day);
+  days.sort();
+  let list = days.iter().map(|day| day.to_string()).collect::<Vec<_>>().join(",");
+  Ok(format!("{} {list}{}", &module[..start], &module[end..]))
+}
+
+/// The manifest entry for the empty example, which doesn't check any
+/// answers until they are added.
+fn example_entry(day: usize) -> String {
+  format!("day{day}:\n  - file: example.txt\n")
+}
+
+fn fuzz_target(year: usize, day: usize) -> String {
+  format!(r#"#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {{
+  if let Ok(input) = std::str::from_utf8(data) {{
+    let _ = omalley_aoc2024::y{year}::day{day}::try_generator(input);
+  }}
+}});
+"#)
+}
+
+/// The fuzz targets for 2024 are just named after the day.
+fn fuzz_name(year: usize, day: usize) -> String {
+  if year == 2024 {
+    format!("day{day}")
+  } else {
+    format!("y{year}_day{day}")
+  }
+}
+
+fn fuzz_entry(year: usize, day: usize) -> String {
+  let name = fuzz_name(year, day);
+  format!("\n[[bin]]\nname = \"{name}\"\npath = \"fuzz_targets/{name}.rs\"\n\
+           test = false\ndoc = false\nbench = false\n")
+}
+
+fn read(path: &Path) -> Result<String, String> {
+  fs::read_to_string(path).map_err(|e| format!("Error reading {}: {e}", path.display()))
+}
+
+fn write(path: &Path, contents: &str) -> Result<(), String> {
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent).map_err(|e| format!("Can't create {}: {e}", parent.display()))?;
+  }
+  fs::write(path, contents).map_err(|e| format!("Error writing {}: {e}", path.display()))?;
+  println!("Wrote {}", path.display());
+  Ok(())
+}
+
+fn append(path: &Path, contents: &str) -> Result<(), String> {
+  let mut text = read(path)?;
+  if !text.ends_with('\n') {
+    text.push('\n');
+  }
+  text.push_str(contents);
+  write(path, &text)
+}
+
+fn create(root: &Path, year: usize, day: usize, title: &str) -> Result<(), String> {
+  let source = root.join(format!("src/y{year}/day{day}.rs"));
+  if source.exists() {
+    return Err(format!("{} already exists", source.display()));
+  }
+  // check the registration first, so nothing is written if it fails
+  let module = root.join(format!("src/y{year}.rs"));
+  if !module.exists() {
+    return Err(format!("Add {} with a day_list! for {year} first", module.display()));
+  }
+  let registered = register(&read(&module)?, day)?;
+  write(&source, &template(year, day, title))?;
+  write(&module, &registered)?;
+  // the examples in tests/data are the ones from 2024
+  if year == 2024 {
+    write(&root.join(format!("tests/data/day{day}/example.txt")), "")?;
+    append(&root.join("tests/data/examples.yml"), &example_entry(day))?;
+  }
+  write(&root.join(format!("fuzz/fuzz_targets/{}.rs", fuzz_name(year, day))),
+        &fuzz_target(year, day))?;
+  append(&root.join("fuzz/Cargo.toml"), &fuzz_entry(year, day))
+}
+
+fn main() {
+  let args: Args = argh::from_env();
+  let title = args.title.unwrap_or_else(|| format!("Day {}", args.day));
+  let root = PathBuf::from(&args.root);
+  if let Err(e) = create(&root, args.year, args.day, &title) {
+    eprintln!("{e}");
+    std::process::exit(1);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{create, register, template};
+
+  #[test]
+  fn test_register() {
+    let module = "//! 2024\ncrate::day_list!(2024 => 1,2,3,5);";
+    assert_eq!("//! 2024\ncrate::day_list!(2024 => 1,2,3,4,5);", register(module, 4).unwrap());
+    assert_eq!("day_list!(2023 => 1,2,3,5,22);",
+               register("day_list!(2023 => 1,2,3,5);", 22).unwrap());
+    assert_eq!("Day 3 is already registered", register(module, 3).unwrap_err());
+    assert!(register("mod day1;", 2).is_err());
+    assert!(register("day_list!(1,2);", 3).is_err());
+  }
+
+  #[test]
+  fn test_create() {
+    let root = std::env::temp_dir().join(format!("new-day-{}", std::process::id()));
+    for (path, contents) in [("src/y2024.rs", "crate::day_list!(2024 => 1);"),
+                             ("src/y2023.rs", "crate::day_list!(2023 => 1);"),
+                             ("tests/data/examples.yml", "day1:\n"),
+                             ("fuzz/Cargo.toml", "[package]")] {
+      std::fs::create_dir_all(root.join(path).parent().unwrap()).unwrap();
+      std::fs::write(root.join(path), contents).unwrap();
+    }
+    create(&root, 2024, 2, "Red-Nosed Reports").unwrap();
+    let read = |path: &str| std::fs::read_to_string(root.join(path)).unwrap();
+    assert_eq!("crate::day_list!(2024 => 1,2);", read("src/y2024.rs"));
+    assert_eq!(template(2024, 2, "Red-Nosed Reports"), read("src/y2024/day2.rs"));
+    assert_eq!("day1:\nday2:\n  - file: example.txt\n", read("tests/data/examples.yml"));
+    assert!(read("fuzz/Cargo.toml").ends_with("path = \"fuzz_targets/day2.rs\"\n\
+                                               test = false\ndoc = false\nbench = false\n"));
+    assert!(read("fuzz/fuzz_targets/day2.rs")
+        .contains("omalley_aoc2024::y2024::day2::try_generator"));
+    // other years get their own fuzz target names and no example
+    create(&root, 2023, 5, "If You Give A Seed A Fertilizer").unwrap();
+    assert_eq!("crate::day_list!(2023 => 1,5);", read("src/y2023.rs"));
+    assert!(read("fuzz/fuzz_targets/y2023_day5.rs").contains("y2023::day5::try_generator"));
+    assert!(!root.join("tests/data/day5").exists());
+    // the day can't be created twice or in a year without a module
+    assert!(create(&root, 2024, 2, "Again").is_err());
+    assert!(create(&root, 2022, 1, "Calorie Counting").is_err());
+    std::fs::remove_dir_all(&root).unwrap();
+  }
+}
@@ -0,0 +1,136 @@
+//! Time budgets for the parts, so that a refactoring that accidentally makes
+//! a day quadratic is noticed. The runner warns about the parts that took
+//! longer than the budget, and a Watchdog can stop a part that is still
+//! running once it is over.
+use std::cell::RefCell;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::utils::DayResult;
+
+/// The budget for each part when one isn't given.
+pub const DEFAULT_BUDGET: Duration = Duration::from_secs(1);
+
+/// Describe each part of the result that took longer than the budget.
+pub fn over_budget(result: &DayResult, budget: Duration) -> Vec<String> {
+  [("part 1", result.part1.0), ("part 2", result.part2.0)].into_iter()
+      .filter(|(_, time)| *time > budget)
+      .map(|(part, time)| format!("{} {part} took {time:.2?}, over its {budget:.2?} budget",
+                                  result.pretty_day()))
+      .collect()
+}
+
+/// A thread that calls its action when a part runs for longer than the
+/// budget.
+pub struct Watchdog {
+  budget: Duration,
+  /// The part that is running and when it started.
+  current: Mutex<Option<(String, Instant)>>,
+  changed: Condvar,
+  on_timeout: Box<dyn Fn(&str) + Send + Sync>,
+}
+
+impl Watchdog {
+  /// Start the watchdog's thread, which runs until the process exits.
+  pub fn spawn(budget: Duration, on_timeout: impl Fn(&str) + Send + Sync + 'static)
+      -> Arc<Self> {
+    let watchdog = Arc::new(Watchdog{budget, current: Mutex::new(None),
+      changed: Condvar::new(), on_timeout: Box::new(on_timeout)});
+    let watcher = watchdog.clone();
+    thread::spawn(move || watcher.watch());
+    watchdog
+  }
+
+  fn watch(&self) {
+    let mut current = self.current.lock().unwrap();
+    loop {
+      let remaining = current.as_ref()
+          .map(|(label, start)| (label.clone(), self.budget.saturating_sub(start.elapsed())));
+      current = match remaining {
+        None => self.changed.wait(current).unwrap(),
+        Some((label, Duration::ZERO)) => {
+          (self.on_timeout)(&label);
+          *current = None;
+          current
+        }
+        Some((_, remaining)) => self.changed.wait_timeout(current, remaining).unwrap().0,
+      }
+    }
+  }
+
+  fn start(&self, label: String) {
+    *self.current.lock().unwrap() = Some((label, Instant::now()));
+    self.changed.notify_one();
+  }
+
+  fn finish(&self) {
+    *self.current.lock().unwrap() = None;
+    self.changed.notify_one();
+  }
+}
+
+thread_local! {
+  /// The watchdog for the parts that run on this thread, if there is one.
+  static WATCHDOG: RefCell<Option<Arc<Watchdog>>> = const { RefCell::new(None) };
+}
+
+/// Restores the previous watchdog when it is dropped.
+pub struct Installed {
+  previous: Option<Arc<Watchdog>>,
+}
+
+impl Drop for Installed {
+  fn drop(&mut self) {
+    WATCHDOG.set(self.previous.take());
+  }
+}
+
+/// Watch the parts that run on this thread until the result is dropped.
+pub fn install(watchdog: Arc<Watchdog>) -> Installed {
+  let previous = WATCHDOG.replace(Some(watchdog));
+  Installed { previous }
+}
+
+/// Run the part while the installed watchdog, if any, watches it.
+pub fn watch<T>(day: &str, part: &str, run: impl FnOnce() -> T) -> T {
+  let watchdog = WATCHDOG.with_borrow(|watchdog| watchdog.clone());
+  if let Some(watchdog) = &watchdog {
+    watchdog.start(format!("{} {part}", day.replace("day", "Day ")));
+  }
+  let result = run();
+  if let Some(watchdog) = &watchdog {
+    watchdog.finish();
+  }
+  result
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::{Arc, Mutex};
+  use std::thread;
+  use std::time::Duration;
+  use crate::utils::{Answer, DayResult};
+  use super::{install, over_budget, watch, Watchdog};
+
+  #[test]
+  fn test_over_budget() {
+    let result = DayResult{day: "day6".to_string(), generate_time: Duration::from_secs(5),
+      part1: (Duration::from_millis(10), Answer::from(1u64)),
+      part2: (Duration::from_millis(1500), Answer::from(2u64)), peak_memory: None};
+    assert_eq!(vec!["Day 6 part 2 took 1.50s, over its 1.00s budget"],
+               over_budget(&result, Duration::from_secs(1)));
+    assert!(over_budget(&result, Duration::from_secs(2)).is_empty());
+  }
+
+  #[test]
+  fn test_watchdog() {
+    let timeouts = Arc::new(Mutex::new(Vec::new()));
+    let seen = timeouts.clone();
+    let watchdog = Watchdog::spawn(Duration::from_millis(50),
+                                   move |label| seen.lock().unwrap().push(label.to_string()));
+    let _installed = install(watchdog);
+    assert_eq!(1, watch("day6", "part 1", || 1));
+    watch("day20", "part 2", || thread::sleep(Duration::from_millis(200)));
+    assert_eq!(vec!["Day 20 part 2"], *timeouts.lock().unwrap());
+  }
+}
@@ -0,0 +1,123 @@
+//! Checking a day against both its examples and the real input at once. The
+//! examples and their answers are listed in tests/data/examples.yml, while
+//! the real input's answers are the ones that the runner recorded.
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::{self, File};
+use std::path::Path;
+use colored::Colorize;
+use serde::Deserialize;
+use crate::utils::Solver;
+
+/// One example input and the answers that the puzzle gives for it.
+#[derive(Clone,Debug,Deserialize)]
+pub struct Example {
+  pub file: String,
+  pub part1: Option<String>,
+  pub part2: Option<String>,
+}
+
+/// The examples for each day, keyed by the day's name.
+pub type Manifest = BTreeMap<String, Vec<Example>>;
+
+/// Read examples.yml from the directory with the examples.
+pub fn read_manifest(data: &Path) -> Result<Manifest, String> {
+  let filename = data.join("examples.yml");
+  let file = File::open(&filename)
+      .map_err(|e| format!("Error reading {}: {e}", filename.display()))?;
+  serde_yaml::from_reader(file).map_err(|e| format!("Error in {}: {e}", filename.display()))
+}
+
+/// The result of one part on one input.
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub struct PartCheck {
+  /// The example's file or "input" for the real input.
+  pub input: String,
+  pub part: usize,
+  pub expected: Option<String>,
+  pub actual: String,
+}
+
+impl PartCheck {
+  /// Did the part give the expected answer? A part without one passes.
+  pub fn passed(&self) -> bool {
+    self.expected.as_ref().is_none_or(|expected| *expected == self.actual)
+  }
+}
+
+impl fmt::Display for PartCheck {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let label = format!("{} part {}", self.input, self.part);
+    match &self.expected {
+      None => write!(f, "{} {label}: {} {}", "?".yellow(), self.actual,
+                     "(no recorded answer)".dimmed()),
+      Some(_) if self.passed() => write!(f, "{} {label}: {}", "✓".green(), self.actual),
+      Some(expected) => write!(f, "{} {label}: expected {expected}, got {}", "✗".red(),
+                               self.actual),
+    }
+  }
+}
+
+/// Run both parts on the input and compare them to the expected answers.
+/// The parts without an expected answer are skipped unless keep_unknown is
+/// set.
+fn check_input(solver: &dyn Solver, name: &str, input: &str, expected: [Option<String>; 2],
+               keep_unknown: bool) -> Result<Vec<PartCheck>, String> {
+  let parsed = solver.try_generator(input)?;
+  Ok(expected.into_iter().enumerate()
+      .filter(|(_, expected)| keep_unknown || expected.is_some())
+      .map(|(part, expected)| {
+        let actual = if part == 0 { solver.part1(&parsed) } else { solver.part2(&parsed) };
+        PartCheck{input: name.to_string(), part: part + 1, expected,
+          actual: actual.map_or_else(|e| format!("error: {e}"), |answer| answer.to_string())}
+      }).collect())
+}
+
+/// Run the day's examples from the manifest, skipping the parts that the
+/// puzzle doesn't give an answer for.
+pub fn check_examples(solver: &dyn Solver, data: &Path, manifest: &Manifest)
+    -> Result<Vec<PartCheck>, String> {
+  let mut result = Vec::new();
+  for example in manifest.get(solver.name()).map(Vec::as_slice).unwrap_or_default() {
+    let filename = data.join(solver.name()).join(&example.file);
+    let input = fs::read_to_string(&filename)
+        .map_err(|e| format!("Error reading {}: {e}", filename.display()))?;
+    result.extend(check_input(solver, &example.file, &input,
+                              [example.part1.clone(), example.part2.clone()], false)?);
+  }
+  Ok(result)
+}
+
+/// Run the real input and compare it to the recorded answers, if there are
+/// any.
+pub fn check_real(solver: &dyn Solver, input: &str, recorded: Option<&[String]>)
+    -> Result<Vec<PartCheck>, String> {
+  let expected = |part: usize| recorded.and_then(|answers| answers.get(part).cloned());
+  check_input(solver, "input", input, [expected(0), expected(1)], true)
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::Path;
+  use crate::y2024::SOLVERS;
+  use super::{check_examples, check_real, read_manifest, PartCheck};
+
+  #[test]
+  fn test_check() {
+    let data = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/data");
+    let manifest = read_manifest(&data).unwrap();
+    let checks = check_examples(SOLVERS[2], &data, &manifest).unwrap();
+    assert_eq!(vec![("example.txt", 1), ("example2.txt", 2)],
+               checks.iter().map(|check| (check.input.as_str(), check.part)).collect::<Vec<_>>());
+    assert!(checks.iter().all(PartCheck::passed));
+
+    let input = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n";
+    let checks = check_real(SOLVERS[0], input, Some(&["11".to_string(), "30".to_string()]))
+        .unwrap();
+    assert!(checks[0].passed());
+    assert!(!checks[1].passed());
+    assert!(checks[1].to_string().contains("expected 30, got 31"));
+    let checks = check_real(SOLVERS[0], input, None).unwrap();
+    assert!(checks.iter().all(|check| check.passed() && check.expected.is_none()));
+  }
+}
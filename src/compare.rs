@@ -0,0 +1,102 @@
+//! Running every implementation of a day's parts on the same parsed input,
+//! checking that they give the same answers, and comparing their times.
+use std::fmt;
+use std::time::{Duration, Instant};
+use colored::Colorize;
+use crate::utils::{Answer, Parsed, Solver};
+
+/// The answer and time of one implementation.
+pub struct Run {
+  pub name: &'static str,
+  pub answer: Answer,
+  pub time: Duration,
+}
+
+/// Every implementation of one part, with the registered one first.
+pub struct Comparison {
+  pub day: String,
+  pub part: usize,
+  pub runs: Vec<Run>,
+}
+
+impl Comparison {
+  /// Do all of the implementations give the registered one's answer?
+  pub fn agrees(&self) -> bool {
+    self.runs.iter().all(|run| run.answer == self.runs[0].answer)
+  }
+}
+
+impl fmt::Display for Comparison {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    writeln!(f, "{} part {}", self.day.replace("day", "Day ").bold(), self.part)?;
+    let registered = self.runs[0].time.as_secs_f64();
+    for run in &self.runs {
+      let relative = if registered > 0.0 { run.time.as_secs_f64() / registered } else { 1.0 };
+      let answer = if run.answer == self.runs[0].answer {
+        run.answer.to_string().normal()
+      } else {
+        run.answer.to_string().red()
+      };
+      writeln!(f, " · {:<14} {:>10} {:>7} {answer}", run.name,
+               format!("{:.2?}", run.time), format!("{relative:.2}x"))?;
+    }
+    Ok(())
+  }
+}
+
+fn time(name: &'static str, solve: impl FnOnce() -> Result<Answer, String>)
+    -> Result<Run, String> {
+  let start = Instant::now();
+  let answer = solve().map_err(|e| format!("{name}: {e}"))?;
+  Ok(Run{name, answer, time: start.elapsed()})
+}
+
+/// Compare the parts of the day that have other implementations. The days
+/// without any give an empty list, and an implementation that can't answer
+/// the input is an error.
+pub fn compare(solver: &dyn Solver, input: &str) -> Result<Vec<Comparison>, String> {
+  let algorithms = solver.algorithms();
+  if algorithms.is_empty() {
+    return Ok(Vec::new());
+  }
+  let parsed: Parsed = solver.try_generator(input)?;
+  [1, 2].into_iter()
+      .filter(|&part| algorithms.iter().any(|algorithm| algorithm.part == part))
+      .map(|part| {
+        let registered = time("registered", || if part == 1 {
+          solver.part1(&parsed)
+        } else {
+          solver.part2(&parsed)
+        });
+        let runs = std::iter::once(registered)
+            .chain(algorithms.iter().filter(|algorithm| algorithm.part == part)
+                .map(|algorithm| time(algorithm.name, || (algorithm.solve)(&parsed))))
+            .collect::<Result<_, String>>()?;
+        Ok(Comparison{day: solver.name().to_string(), part, runs})
+      }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::y2024::SOLVERS;
+  use super::compare;
+
+  #[test]
+  fn test_compare() {
+    assert!(compare(SOLVERS[0], "3   4\n").unwrap().is_empty());
+    let input = "190: 10 19\n3267: 81 40 27\n83: 17 5\n156: 15 6\n7290: 6 8 6 15\n\
+                 161011: 16 10 13\n192: 17 8 14\n21037: 9 7 18 13\n292: 11 6 16 20\n";
+    let comparisons = compare(SOLVERS[6], input).unwrap();
+    assert_eq!(vec![1, 2], comparisons.iter().map(|c| c.part).collect::<Vec<_>>());
+    assert!(comparisons.iter().all(|c| c.agrees() && c.runs.len() == 2));
+    assert_eq!("11387", comparisons[1].runs[1].answer.to_string());
+
+    let bytes = "5,4\n4,2\n4,5\n3,0\n2,1\n6,3\n2,4\n1,5\n0,6\n3,3\n2,6\n5,1\n1,2\n\
+                 5,5\n2,5\n6,5\n1,4\n0,4\n6,4\n1,1\n6,1\n1,0\n0,5\n1,6\n2,0\n";
+    let comparisons = compare(SOLVERS[17], &format!("size=7 bytes=12\n{bytes}")).unwrap();
+    assert_eq!(vec!["registered", "astar"],
+               comparisons[0].runs.iter().map(|run| run.name).collect::<Vec<_>>());
+    assert!(comparisons.iter().all(|c| c.agrees()), "{}", comparisons[1]);
+    assert_eq!("6,1", comparisons[1].runs[1].answer.to_string());
+  }
+}
@@ -0,0 +1,105 @@
+//! The runner's settings from aoc.toml, so that the session cookie and the
+//! directories don't have to be passed each time. A flag on the command line
+//! wins over the file.
+//!
+//! ```toml
+//! session = "53616c7465645f5f..."
+//! cache_dir = "/home/me/.cache/aoc2024"
+//! input_dir = "input"
+//! budget_ms = 500
+//! threads = 4
+//!
+//! [days.day20]
+//! cheat_limit = 50
+//! cheat_length = 2
+//! ```
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use serde::Deserialize;
+use crate::params::Params;
+
+/// The file that is read when --config isn't given.
+pub const DEFAULT_FILE: &str = "aoc.toml";
+
+#[derive(Clone,Debug,Default,Deserialize,PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+  /// The adventofcode.com session cookie for downloading the inputs.
+  pub session: Option<String>,
+  /// Where the downloaded inputs are kept.
+  pub cache_dir: Option<PathBuf>,
+  /// The directory with the inputs, which has a directory for each year
+  /// other than 2024.
+  pub input_dir: Option<PathBuf>,
+  /// The time budget for each part in milliseconds.
+  pub budget_ms: Option<u64>,
+  /// The number of threads for the parallel days.
+  pub threads: Option<usize>,
+  /// The parameters for each day by its name, which are the ones in
+  /// params::PARAMETERS.
+  pub days: BTreeMap<String, BTreeMap<String, usize>>,
+}
+
+impl Config {
+  /// Parse the configuration and check the days' parameters.
+  pub fn parse(text: &str) -> Result<Self, String> {
+    let config: Config = toml::from_str(text).map_err(|e| e.to_string())?;
+    config.params()?;
+    Ok(config)
+  }
+
+  pub fn read(path: &Path) -> Result<Self, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("Error reading {}: {e}", path.display()))?;
+    Self::parse(&text).map_err(|e| format!("Error in {}: {e}", path.display()))
+  }
+
+  /// Read the given file or aoc.toml in the current directory. Only the
+  /// default file may be missing, which gives an empty configuration.
+  pub fn load(path: Option<&str>) -> Result<Self, String> {
+    match path {
+      Some(path) => Self::read(Path::new(path)),
+      None if Path::new(DEFAULT_FILE).exists() => Self::read(Path::new(DEFAULT_FILE)),
+      None => Ok(Self::default()),
+    }
+  }
+
+  /// The default parameters with the file's changes.
+  pub fn params(&self) -> Result<Params, String> {
+    let mut params = Params::default();
+    for (day, values) in &self.days {
+      for (name, value) in values {
+        params.set(day, name, *value)?;
+      }
+    }
+    Ok(params)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::PathBuf;
+  use crate::params::Params;
+  use super::Config;
+
+  #[test]
+  fn test_parse() {
+    let config = Config::parse("session = \"abc\"\ninput_dir = \"puzzles\"\n\n\
+                                [days.day20]\ncheat_limit = 50\n").unwrap();
+    assert_eq!(Some("abc".to_string()), config.session);
+    assert_eq!(Some(PathBuf::from("puzzles")), config.input_dir);
+    assert_eq!(None, config.cache_dir);
+    assert_eq!(Some(500), Config::parse("budget_ms = 500").unwrap().budget_ms);
+    assert_eq!(Some(4), Config::parse("threads = 4").unwrap().threads);
+    let params = config.params().unwrap();
+    assert_eq!(50, params.cheat_limit);
+    assert_eq!(Params::default().cheat_length, params.cheat_length);
+    assert_eq!(Config::default(), Config::parse("").unwrap());
+    // typos are errors instead of being ignored
+    assert!(Config::parse("sesion = \"abc\"").is_err());
+    assert_eq!("day1 doesn't have any parameters",
+               Config::parse("[days.day1]\nlimit = 3").unwrap_err());
+    assert!(Config::parse("[days.day20]\nlimit = 3").unwrap_err().contains("cheat_limit"));
+    assert!(Config::load(Some("no-such-file.toml")).is_err());
+  }
+}
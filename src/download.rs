@@ -0,0 +1,357 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The environment variable with the adventofcode.com session cookie.
+pub const SESSION_VARIABLE: &str = "AOC_SESSION";
+
+/// Inputs are never empty and are well under this size, so anything outside
+/// of that is an error page or a truncated download.
+const MAX_INPUT_SIZE: u64 = 1 << 20;
+
+/// Check that the input's size is plausible.
+fn check_size(size: u64) -> Result<(), String> {
+  if size == 0 || size > MAX_INPUT_SIZE {
+    Err(format!("Input size {size} is outside of 1..={MAX_INPUT_SIZE}"))
+  } else {
+    Ok(())
+  }
+}
+
+/// Download the input for a day using the session cookie.
+pub fn fetch_input(year: usize, day: usize, session: &str) -> Result<String, String> {
+  let url = format!("https://adventofcode.com/{year}/day/{day}/input");
+  let mut response = ureq::get(&url)
+      .header("Cookie", &format!("session={session}"))
+      .call()
+      .map_err(|e| format!("Error fetching {url}: {e}"))?;
+  response.body_mut().read_to_string()
+      .map_err(|e| format!("Error reading {url}: {e}"))
+}
+
+/// Download the page with the day's puzzle, which only has part two once
+/// part one is solved with the session.
+pub fn fetch_puzzle(year: usize, day: usize, session: &str) -> Result<String, String> {
+  let url = format!("https://adventofcode.com/{year}/day/{day}");
+  let mut response = ureq::get(&url)
+      .header("Cookie", &format!("session={session}"))
+      .call()
+      .map_err(|e| format!("Error fetching {url}: {e}"))?;
+  response.body_mut().read_to_string()
+      .map_err(|e| format!("Error reading {url}: {e}"))
+}
+
+/// The columns that the puzzle's paragraphs are wrapped to.
+const PUZZLE_WIDTH: usize = 80;
+
+/// Replace the entities that the puzzle pages use with their characters.
+fn decode_entities(text: &str) -> String {
+  let mut result = String::new();
+  let mut rest = text;
+  while let Some(start) = rest.find('&') {
+    result.push_str(&rest[..start]);
+    rest = &rest[start..];
+    let entity = rest.find(';').map(|end| &rest[1..end]);
+    let decoded = match entity {
+      Some("lt") => Some('<'),
+      Some("gt") => Some('>'),
+      Some("amp") => Some('&'),
+      Some("quot") => Some('"'),
+      Some("apos") => Some('\''),
+      Some("nbsp") => Some(' '),
+      Some(code) if code.starts_with('#') => code[1..].parse().ok().and_then(char::from_u32),
+      _ => None,
+    };
+    match (entity, decoded) {
+      (Some(entity), Some(ch)) => {
+        result.push(ch);
+        rest = &rest[entity.len() + 2..];
+      }
+      _ => {
+        result.push('&');
+        rest = &rest[1..];
+      }
+    }
+  }
+  result.push_str(rest);
+  result
+}
+
+/// Wrap each line of the text at the width, indenting the rest of a list
+/// item under its text.
+fn wrap(text: &str, width: usize) -> String {
+  let mut lines = Vec::new();
+  for line in text.lines() {
+    let (first, indent, line) = match line.strip_prefix("  - ") {
+      Some(item) => ("  - ", "    ", item),
+      None => ("", "", line),
+    };
+    let mut current = first.to_string();
+    for (position, word) in line.split(' ').filter(|word| !word.is_empty()).enumerate() {
+      if position > 0 {
+        if current.chars().count() + 1 + word.chars().count() > width {
+          lines.push(std::mem::replace(&mut current, indent.to_string()));
+        } else {
+          current.push(' ');
+        }
+      }
+      current.push_str(word);
+    }
+    lines.push(current);
+  }
+  lines.join("\n")
+}
+
+/// Convert the puzzle's page to text, keeping the description of each part
+/// and dropping the rest of the site. The paragraphs are wrapped, while the
+/// examples keep their layout.
+pub fn puzzle_text(html: &str) -> String {
+  // each block is its text and whether it is preformatted
+  let mut blocks: Vec<(String, bool)> = Vec::new();
+  let mut current = String::new();
+  let mut pre = false;
+  let mut flush = |current: &mut String, pre: bool| {
+    let text = if pre { current.trim_matches('\n') } else { current.trim_end() };
+    if !text.is_empty() {
+      blocks.push((text.to_string(), pre));
+    }
+    current.clear();
+  };
+  for article in html.split("<article").skip(1) {
+    let article = article.split("</article>").next().unwrap_or_default();
+    let body = article.split_once('>').map_or("", |(_, body)| body);
+    for (index, piece) in body.split('<').enumerate() {
+      let (tag, text) = if index == 0 {
+        ("", piece)
+      } else {
+        piece.split_once('>').unwrap_or((piece, ""))
+      };
+      let closing = tag.starts_with('/');
+      let name = tag.trim_start_matches('/').split([' ', '/']).next().unwrap_or_default();
+      match name {
+        "h2" | "p" | "ul" => flush(&mut current, pre),
+        "pre" => {
+          flush(&mut current, pre);
+          pre = !closing;
+        }
+        "li" if !closing => {
+          if !current.is_empty() {
+            current.push('\n');
+          }
+          current.push_str("  - ");
+        }
+        _ => {}
+      }
+      let text = decode_entities(text);
+      if pre {
+        current.push_str(&text);
+      } else {
+        for (position, word) in text.split(char::is_whitespace).enumerate() {
+          if position > 0 && !current.is_empty() && !current.ends_with([' ', '\n']) {
+            current.push(' ');
+          }
+          current.push_str(word);
+        }
+      }
+    }
+    flush(&mut current, pre);
+  }
+  blocks.iter()
+      .map(|(text, pre)| if *pre { text.clone() } else { wrap(text, PUZZLE_WIDTH) })
+      .collect::<Vec<_>>().join("\n\n") + "\n"
+}
+
+/// The session cookie from the environment, which wins over the configured
+/// one.
+pub fn session(configured: Option<&str>) -> Result<String, String> {
+  let session = env::var(SESSION_VARIABLE).ok().or(configured.map(String::from))
+      .ok_or(format!("Set {SESSION_VARIABLE} or the session in aoc.toml to use adventofcode.com"))?;
+  Ok(session.trim().to_string())
+}
+
+/// Download the input using the session from the environment or the
+/// configured one.
+fn fetch_with_session(year: usize, day: usize, session: Option<&str>) -> Result<String, String> {
+  fetch_input(year, day, &self::session(session)?)
+}
+
+/// A local cache of the puzzle inputs, so that each one is only downloaded
+/// once.
+pub struct InputCache {
+  directory: PathBuf,
+  session: Option<String>,
+}
+
+impl InputCache {
+  pub fn new(directory: impl Into<PathBuf>) -> Self {
+    InputCache { directory: directory.into(), session: None }
+  }
+
+  /// Use this session cookie when AOC_SESSION isn't set.
+  pub fn with_session(self, session: Option<String>) -> Self {
+    InputCache { session, ..self }
+  }
+
+  /// The default location is aoc2024 under $XDG_CACHE_HOME or ~/.cache.
+  pub fn default_directory() -> PathBuf {
+    let base = env::var_os("XDG_CACHE_HOME").map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))
+        .unwrap_or_else(env::temp_dir);
+    base.join("aoc2024")
+  }
+
+  /// Where the files for a given year, such as the solve log, are kept.
+  pub fn year_directory(&self, year: usize) -> PathBuf {
+    self.directory.join(year.to_string())
+  }
+
+  /// Where the input for a given day is kept.
+  pub fn path(&self, year: usize, day: usize) -> PathBuf {
+    self.year_directory(year).join(format!("day{day}.txt"))
+  }
+
+  /// Read the cached input if it exists and has a plausible size.
+  fn read(&self, year: usize, day: usize) -> Option<String> {
+    let path = self.path(year, day);
+    check_size(fs::metadata(&path).ok()?.len()).ok()?;
+    fs::read_to_string(path).ok()
+  }
+
+  /// Where the text of the puzzle for a given day is kept.
+  pub fn puzzle_path(&self, year: usize, day: usize) -> PathBuf {
+    self.year_directory(year).join(format!("day{day}.puzzle.txt"))
+  }
+
+  /// Save the file, writing it to a temporary file first so that a partial
+  /// write never looks like a cached one.
+  fn write(path: &Path, contents: &str) -> Result<(), String> {
+    let parent = path.parent().expect("cache path has a parent");
+    fs::create_dir_all(parent)
+        .map_err(|e| format!("Error creating {}: {e}", parent.display()))?;
+    let temp = path.with_extension("tmp");
+    fs::write(&temp, contents).map_err(|e| format!("Error writing {}: {e}", temp.display()))?;
+    fs::rename(&temp, path).map_err(|e| format!("Error renaming {}: {e}", temp.display()))
+  }
+
+  /// Get the input, only calling fetch if it isn't cached or refetch is set.
+  pub fn get_or_fetch(&self, year: usize, day: usize, refetch: bool,
+                      fetch: impl FnOnce() -> Result<String, String>)
+      -> Result<String, String> {
+    if !refetch {
+      if let Some(input) = self.read(year, day) {
+        return Ok(input);
+      }
+    }
+    let input = fetch()?;
+    check_size(input.len() as u64)
+        .map_err(|e| format!("Downloaded input for {year} day {day}: {e}"))?;
+    Self::write(&self.path(year, day), &input)?;
+    Ok(input)
+  }
+
+  /// Get the input, downloading it with the session in AOC_SESSION or the
+  /// cache's session if needed.
+  pub fn get(&self, year: usize, day: usize, refetch: bool) -> Result<String, String> {
+    self.get_or_fetch(year, day, refetch,
+                      || fetch_with_session(year, day, self.session.as_deref()))
+  }
+
+  /// Get the text of the puzzle, only calling fetch for its page if it isn't
+  /// cached, refetch is set, or the cached one doesn't have part two yet.
+  pub fn get_puzzle_or_fetch(&self, year: usize, day: usize, refetch: bool,
+                             fetch: impl FnOnce() -> Result<String, String>)
+      -> Result<String, String> {
+    let path = self.puzzle_path(year, day);
+    if !refetch {
+      if let Ok(text) = fs::read_to_string(&path) {
+        if text.contains("--- Part Two ---") {
+          return Ok(text);
+        }
+      }
+    }
+    let text = puzzle_text(&fetch()?);
+    if text.trim().is_empty() {
+      return Err(format!("The page for {year} day {day} doesn't have a puzzle"));
+    }
+    Self::write(&path, &text)?;
+    Ok(text)
+  }
+
+  /// Get the text of the puzzle, downloading it with the session in
+  /// AOC_SESSION or the cache's session if needed.
+  pub fn get_puzzle(&self, year: usize, day: usize, refetch: bool) -> Result<String, String> {
+    self.get_puzzle_or_fetch(year, day, refetch,
+                             || fetch_puzzle(year, day, &session(self.session.as_deref())?))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::cell::Cell;
+  use std::{env, fs};
+  use super::{puzzle_text, InputCache};
+
+  #[test]
+  fn test_cache() {
+    let directory = env::temp_dir().join(format!("aoc2024-cache-{}", std::process::id()));
+    let cache = InputCache::new(&directory);
+    let calls = Cell::new(0);
+    let fetch = || { calls.set(calls.get() + 1); Ok("1 2\n".to_string()) };
+    assert_eq!("1 2\n", cache.get_or_fetch(2024, 1, false, fetch).unwrap());
+    assert_eq!("1 2\n", cache.get_or_fetch(2024, 1, false, fetch).unwrap());
+    assert_eq!(1, calls.get());
+    assert!(cache.path(2024, 1).ends_with("2024/day1.txt"));
+
+    // refetch ignores the cache and an empty file is downloaded again
+    cache.get_or_fetch(2024, 1, true, fetch).unwrap();
+    fs::write(cache.path(2024, 1), "").unwrap();
+    cache.get_or_fetch(2024, 1, false, fetch).unwrap();
+    assert_eq!(3, calls.get());
+
+    // bad downloads are not cached
+    assert!(cache.get_or_fetch(2024, 2, false, || Ok(String::new())).is_err());
+    assert!(cache.get_or_fetch(2024, 2, false, || Ok("x".repeat(2 << 20))).is_err());
+    assert!(!cache.path(2024, 2).exists());
+    fs::remove_dir_all(directory).unwrap();
+  }
+
+  const PAGE: &str = r#"<html><body><header>Advent of Code</header><main>
+<article class="day-desc"><h2>--- Day 1: Historian Hysteria ---</h2><p>The <em>Chief
+Historian</em> is always present for the big Christmas sleigh launch, but nobody has seen him in
+months!</p>
+<ul><li>Pair up the <code>3</code> &amp; the <code>4</code>.</li><li>Then the rest.</li></ul>
+<pre><code>3   4
+4   3
+</code></pre>
+</article><p>Your puzzle answer was <code>11</code>.</p></main></body></html>"#;
+
+  #[test]
+  fn test_puzzle_text() {
+    assert_eq!("--- Day 1: Historian Hysteria ---\n\n\
+                The Chief Historian is always present for the big Christmas sleigh launch, but\n\
+                nobody has seen him in months!\n\n\
+                \x20 - Pair up the 3 & the 4.\n\
+                \x20 - Then the rest.\n\n\
+                3   4\n4   3\n", puzzle_text(PAGE));
+    assert_eq!("a < b &c; \u{2014}", super::decode_entities("a &lt; b &c; &#8212;"));
+  }
+
+  #[test]
+  fn test_puzzle_cache() {
+    let directory = env::temp_dir().join(format!("aoc2024-puzzle-{}", std::process::id()));
+    let cache = InputCache::new(&directory);
+    let calls = Cell::new(0);
+    let page = |html: &'static str| || { calls.set(calls.get() + 1); Ok(html.to_string()) };
+    cache.get_puzzle_or_fetch(2024, 1, false, page(PAGE)).unwrap();
+    assert!(cache.puzzle_path(2024, 1).ends_with("2024/day1.puzzle.txt"));
+    // without part two the page is downloaded again
+    let both = "<article><h2>--- Day 1 ---</h2></article><article><h2>--- Part Two ---</h2>\
+                </article>";
+    assert_eq!("--- Day 1 ---\n\n--- Part Two ---\n",
+               cache.get_puzzle_or_fetch(2024, 1, false, page(both)).unwrap());
+    cache.get_puzzle_or_fetch(2024, 1, false, page(PAGE)).unwrap();
+    assert_eq!(2, calls.get());
+    assert!(cache.get_puzzle_or_fetch(2024, 2, false, page("<p>404</p>")).is_err());
+    fs::remove_dir_all(directory).unwrap();
+  }
+}
@@ -0,0 +1,46 @@
+//! Narrations of the intermediate results that lead to some days' answers,
+//! so that they can be checked without adding println! to the days.
+use std::io::Write;
+use crate::utils::{downcast, Parsed};
+use crate::y2024::{self, day13, day16, day5};
+
+/// The days of 2024 that can explain their answers.
+pub const DAYS: &[usize] = &[5, 13, 16];
+
+/// Write the explanation of the day from its parsed input.
+pub fn for_day(year: usize, day: usize, input: &Parsed,
+               out: &mut dyn Write) -> Result<(), String> {
+  let result = match day {
+    5 if year == y2024::YEAR => day5::explain(downcast(day5::generator, input), out),
+    13 if year == y2024::YEAR => day13::explain(downcast(day13::generator, input), out),
+    16 if year == y2024::YEAR => day16::explain(downcast(day16::generator, input), out),
+    _ => return Err(format!("Day {day} of {year} can't be explained, try one of {DAYS:?}")),
+  };
+  result.map_err(|e| format!("Error writing the explanation: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::find_solver;
+  use super::DAYS;
+
+  fn explain(day: usize, input: &str) -> Result<String, String> {
+    let solver = find_solver(2024, day)?;
+    let mut out = Vec::new();
+    solver.explain(&solver.generator(input), &mut out)?;
+    Ok(String::from_utf8(out).unwrap())
+  }
+
+  #[test]
+  fn test_explain() {
+    assert_eq!("Machine 1: part 1 A 80 times and B 40 times for 280 tokens, part 2 can't be won\n",
+               explain(13, "Button A: X+94, Y+34\nButton B: X+22, Y+67\nPrize: X=8400, Y=5400")
+                   .unwrap());
+    assert_eq!("Printing 1: 1,2 is in order, part 1 adds 2\n\
+                Printing 2: 2,1 was fixed to 1,2, part 2 adds 2\n",
+               explain(5, "1|2\n\n1,2\n2,1").unwrap());
+    assert_eq!("Node 0 (start): 0\nNode 1 (end): 2\n", explain(16, "#####\n#S.E#\n#####").unwrap());
+    assert_eq!(format!("Day 1 of 2024 can't be explained, try one of {DAYS:?}"),
+               explain(1, "1 2").unwrap_err());
+  }
+}
@@ -0,0 +1,99 @@
+//! A C interface to the solutions, so that harnesses and benchmark rigs in
+//! other languages can embed them. Build the shared library with
+//! `cargo build --release --lib --features ffi` and include/aoc2024.h for
+//! the declarations.
+use std::os::raw::c_char;
+
+/// The day or part isn't implemented, the input isn't valid, or the part
+/// can't answer it.
+pub const AOC2024_ERROR: isize = -1;
+/// The input or output buffer is null.
+pub const AOC2024_NULL_POINTER: isize = -2;
+/// The input isn't UTF-8.
+pub const AOC2024_BAD_UTF8: isize = -3;
+
+/// Copy as much of the text as fits into the buffer, followed by a NUL.
+///
+/// # Safety
+/// The buffer must have room for capacity bytes.
+unsafe fn copy_out(text: &str, out_buf: *mut c_char, capacity: usize) {
+  if capacity == 0 {
+    return;
+  }
+  let length = text.len().min(capacity - 1);
+  std::ptr::copy_nonoverlapping(text.as_ptr() as *const c_char, out_buf, length);
+  *out_buf.add(length) = 0;
+}
+
+/// Solve one part of a day from an input of len bytes. The answer is
+/// written to out_buf as a NUL terminated string and its length is
+/// returned, like snprintf, so an answer that is as long as the capacity
+/// or longer was cut off. Errors return a negative code, and for
+/// AOC2024_ERROR the message is written to out_buf instead of the answer.
+///
+/// # Safety
+/// input_ptr must point at len readable bytes and out_buf at capacity
+/// writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn aoc2024_solve(day: u32, part: u32, input_ptr: *const u8, len: usize,
+                                       out_buf: *mut c_char, capacity: usize) -> isize {
+  if out_buf.is_null() || (input_ptr.is_null() && len > 0) {
+    return AOC2024_NULL_POINTER;
+  }
+  let bytes = if len == 0 { &[] } else { std::slice::from_raw_parts(input_ptr, len) };
+  let Ok(input) = std::str::from_utf8(bytes) else {
+    return AOC2024_BAD_UTF8;
+  };
+  match crate::solve(crate::DEFAULT_YEAR, day as usize, part as usize, input) {
+    Ok(answer) => {
+      let answer = answer.to_string();
+      copy_out(&answer, out_buf, capacity);
+      answer.len() as isize
+    }
+    Err(message) => {
+      copy_out(&message, out_buf, capacity);
+      AOC2024_ERROR
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::ffi::CStr;
+  use std::os::raw::c_char;
+  use crate::y2024::day17;
+  use super::{aoc2024_solve, AOC2024_BAD_UTF8, AOC2024_ERROR, AOC2024_NULL_POINTER};
+
+  fn solve(day: u32, part: u32, input: &[u8], capacity: usize) -> (isize, String) {
+    let mut buffer = vec![0x7f as c_char; capacity.max(1)];
+    let result = unsafe {
+      aoc2024_solve(day, part, input.as_ptr(), input.len(), buffer.as_mut_ptr(), capacity)
+    };
+    let text = if capacity == 0 {
+      String::new()
+    } else {
+      unsafe { CStr::from_ptr(buffer.as_ptr()) }.to_string_lossy().to_string()
+    };
+    (result, text)
+  }
+
+  #[test]
+  fn test_solve() {
+    let input = b"3   4\n4   3\n2   5\n1   3\n3   9\n3   3";
+    assert_eq!((2, "11".to_string()), solve(1, 1, input, 16));
+    assert_eq!((2, "31".to_string()), solve(1, 2, input, 16));
+    // the answer is cut off to fit, but the full length comes back
+    assert_eq!((2, "3".to_string()), solve(1, 2, input, 2));
+    assert_eq!((2, String::new()), solve(1, 2, input, 0));
+    assert_eq!((AOC2024_ERROR, "Part 3 doesn't exist".to_string()), solve(1, 3, input, 64));
+    assert_eq!(AOC2024_ERROR, solve(30, 1, input, 64).0);
+    // a part that can't answer the input is an error rather than a panic
+    let quine = b"Register A: 5\nRegister B: 0\nRegister C: 0\n\nProgram: 0,1,5,4,3,0";
+    assert_eq!((AOC2024_ERROR, day17::NO_QUINE.to_string()), solve(17, 2, quine, 64));
+    assert_eq!(AOC2024_BAD_UTF8, solve(1, 1, &[0xff, 0xfe], 64).0);
+    let result = unsafe {
+      aoc2024_solve(1, 1, input.as_ptr(), input.len(), std::ptr::null_mut(), 0)
+    };
+    assert_eq!(AOC2024_NULL_POINTER, result);
+  }
+}
@@ -0,0 +1,155 @@
+//! A history of the --time runs, so that the days' speed can be followed
+//! across optimization passes. Each run adds the median times of its days
+//! to timings.yml next to the recorded answers, and the history can be
+//! exported as JSON or CSV or drawn as a bar chart.
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use crate::timing::DayTiming;
+use crate::utils::render::svg::{self, Series};
+
+/// The median time of each step of a day in nanoseconds.
+#[derive(Clone,Debug,Deserialize,Eq,PartialEq,Serialize)]
+pub struct Entry {
+  pub day: String,
+  pub generator: u64,
+  pub part1: u64,
+  pub part2: u64,
+}
+
+impl Entry {
+  pub fn from_timing(timing: &DayTiming) -> Self {
+    let nanos = |duration: Duration| duration.as_nanos() as u64;
+    Entry{day: timing.day.clone(), generator: nanos(timing.generator.median),
+      part1: nanos(timing.part1.median), part2: nanos(timing.part2.median)}
+  }
+
+  /// The day's number for sorting, since "day10" comes before "day2".
+  fn number(&self) -> usize {
+    self.day.strip_prefix("day").and_then(|number| number.parse().ok()).unwrap_or(usize::MAX)
+  }
+}
+
+/// The days that were timed together and when, in seconds since the epoch.
+#[derive(Clone,Debug,Deserialize,Eq,PartialEq,Serialize)]
+pub struct Run {
+  pub time: i64,
+  pub days: Vec<Entry>,
+}
+
+#[derive(Clone,Debug,Default,Deserialize,Eq,PartialEq,Serialize)]
+pub struct History {
+  pub runs: Vec<Run>,
+}
+
+impl History {
+  pub fn filename(directory: &str) -> PathBuf {
+    Path::new(directory).join("timings.yml")
+  }
+
+  /// Read the history from the directory, which is empty if there isn't one.
+  pub fn read(directory: &str) -> Result<Self, String> {
+    let filename = Self::filename(directory);
+    match fs::read_to_string(&filename) {
+      Ok(text) => serde_yaml::from_str(&text)
+          .map_err(|e| format!("Error in {}: {e}", filename.display())),
+      Err(_) => Ok(Self::default()),
+    }
+  }
+
+  pub fn write(&self, directory: &str) -> Result<(), String> {
+    let filename = Self::filename(directory);
+    let text = serde_yaml::to_string(self).map_err(|e| e.to_string())?;
+    fs::write(&filename, text).map_err(|e| format!("Error writing {}: {e}", filename.display()))
+  }
+
+  /// Add a run of the timings at the time.
+  pub fn record(&mut self, timings: &[DayTiming], time: i64) {
+    if !timings.is_empty() {
+      self.runs.push(Run{time, days: timings.iter().map(Entry::from_timing).collect()});
+    }
+  }
+
+  pub fn to_json(&self) -> String {
+    serde_json::to_string_pretty(self).expect("history is serializable")
+  }
+
+  /// Build a CSV table with a row for each day of each run.
+  pub fn to_csv(&self) -> String {
+    let mut result = String::from("time,day,generator_ns,part1_ns,part2_ns\n");
+    for run in &self.runs {
+      for entry in &run.days {
+        writeln!(result, "{},{},{},{},{}", run.time, entry.day, entry.generator, entry.part1,
+                 entry.part2).unwrap();
+      }
+    }
+    result
+  }
+
+  /// The most recent timing of each day in the order of the days.
+  pub fn latest(&self) -> Vec<&Entry> {
+    let mut result: Vec<&Entry> = Vec::new();
+    for entry in self.runs.iter().rev().flat_map(|run| &run.days) {
+      if result.iter().all(|other| other.day != entry.day) {
+        result.push(entry);
+      }
+    }
+    result.sort_by_key(|entry| entry.number());
+    result
+  }
+
+  /// Draw the most recent parse and solve times of each day as an SVG bar
+  /// chart, where the solve time is both parts together.
+  pub fn to_svg(&self) -> String {
+    let latest = self.latest();
+    let labels: Vec<String> = latest.iter()
+        .map(|entry| entry.number().to_string()).collect();
+    let parse = Series{name: "parse".to_string(), color: "#3060c0".to_string(),
+      values: latest.iter().map(|entry| entry.generator as f64).collect()};
+    let solve = Series{name: "solve".to_string(), color: "#e08030".to_string(),
+      values: latest.iter().map(|entry| (entry.part1 + entry.part2) as f64).collect()};
+    svg::bar_chart(&labels, &[parse, solve],
+                   |nanos| format!("{:?}", Duration::from_nanos(nanos as u64)))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::env;
+  use std::time::Duration;
+  use crate::timing::{DayTiming, Stats};
+  use super::History;
+
+  fn timing(day: &str, micros: u64) -> DayTiming {
+    let stats = Stats{median: Duration::from_micros(micros), ..Stats::default()};
+    DayTiming{day: day.to_string(), generator: stats, part1: stats, part2: stats}
+  }
+
+  #[test]
+  fn test_history() {
+    let mut history = History::default();
+    history.record(&[timing("day10", 20), timing("day2", 5)], 100);
+    history.record(&[], 150);
+    history.record(&[timing("day10", 10)], 200);
+    assert_eq!(2, history.runs.len());
+    assert_eq!(vec![("day2", 5000), ("day10", 10000)], history.latest().iter()
+        .map(|entry| (entry.day.as_str(), entry.part1)).collect::<Vec<_>>());
+    assert_eq!("time,day,generator_ns,part1_ns,part2_ns\n\
+                100,day10,20000,20000,20000\n100,day2,5000,5000,5000\n\
+                200,day10,10000,10000,10000\n", history.to_csv());
+    assert!(history.to_json().contains("\"generator\": 10000"));
+    let svg = history.to_svg();
+    assert!(svg.contains(">2</text>") && svg.contains(">10</text>"), "{svg}");
+    assert!(svg.contains(">1µs</text>") && svg.contains(">100µs</text>"), "{svg}");
+
+    let directory = env::temp_dir().join(format!("aoc2024-history-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    let directory = directory.to_string_lossy().to_string();
+    assert_eq!(History::default(), History::read(&directory).unwrap());
+    history.write(&directory).unwrap();
+    assert_eq!(history, History::read(&directory).unwrap());
+    std::fs::remove_dir_all(directory).unwrap();
+  }
+}
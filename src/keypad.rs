@@ -0,0 +1,386 @@
+use std::cmp::Ordering;
+use std::iter;
+use std::str::FromStr;
+use ahash::{AHashMap, AHashSet};
+use itertools::Itertools;
+
+pub use crate::utils::grid::Position;
+
+#[derive(Clone,Copy,Debug,Eq,Hash,PartialEq)]
+pub struct Coordinate {
+  pub x: Position,
+  pub y: Position,
+}
+
+/// A key pad with a fixed layout. The coordinates start from the bottom
+/// right corner, with x increasing to the left and y increasing upwards.
+pub trait KeyPad: Sized {
+  /// Find the button at the given coordinate.
+  fn from_position(coordinate: Coordinate) -> Option<Self>;
+
+  /// Find the position of the given button.
+  fn position(&self) -> Coordinate;
+}
+
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
+pub enum TenKey {
+  Seven, Eight, Nine,
+  Four, Five, Six,
+  One, Two, Three,
+  Zero, Activate,
+}
+
+impl TenKey {
+  pub fn from_char(ch: char) -> Result<TenKey, String> {
+    match ch {
+      '7' => Ok(TenKey::Seven),
+      '8' => Ok(TenKey::Eight),
+      '9' => Ok(TenKey::Nine),
+      '4' => Ok(TenKey::Four),
+      '5' => Ok(TenKey::Five),
+      '6' => Ok(TenKey::Six),
+      '1' => Ok(TenKey::One),
+      '2' => Ok(TenKey::Two),
+      '3' => Ok(TenKey::Three),
+      '0' => Ok(TenKey::Zero),
+      'A' => Ok(TenKey::Activate),
+      _ => Err(format!("Invalid TenKey char '{}'", ch)),
+    }
+  }
+
+  pub fn digit(&self) -> Option<usize> {
+    match self {
+      TenKey::Seven => Some(7),
+      TenKey::Eight => Some(8),
+      TenKey::Nine => Some(9),
+      TenKey::Four => Some(4),
+      TenKey::Five => Some(5),
+      TenKey::Six => Some(6),
+      TenKey::One => Some(1),
+      TenKey::Two => Some(2),
+      TenKey::Three => Some(3),
+      TenKey::Zero => Some(0),
+      _ => None,
+    }
+  }
+
+  pub fn to_char(self) -> char {
+    match self {
+      TenKey::Seven => '7',
+      TenKey::Eight => '8',
+      TenKey::Nine => '9',
+      TenKey::Four => '4',
+      TenKey::Five => '5',
+      TenKey::Six => '6',
+      TenKey::One => '1',
+      TenKey::Two => '2',
+      TenKey::Three => '3',
+      TenKey::Zero => '0',
+      TenKey::Activate => 'A',
+    }
+  }
+}
+
+impl KeyPad for TenKey {
+  fn from_position(coordinate: Coordinate) -> Option<TenKey> {
+    match coordinate.y {
+      0 => match coordinate.x {
+        0 => Some(TenKey::Activate),
+        1 => Some(TenKey::Zero),
+        _ => None,
+      },
+      1 => match coordinate.x {
+        0 => Some(TenKey::Three),
+        1 => Some(TenKey::Two),
+        2 => Some(TenKey::One),
+        _ => None,
+      },
+      2 => match coordinate.x {
+        0 => Some(TenKey::Six),
+        1 => Some(TenKey::Five),
+        2 => Some(TenKey::Four),
+        _ => None,
+      },
+      3 => match coordinate.x {
+        0 => Some(TenKey::Nine),
+        1 => Some(TenKey::Eight),
+        2 => Some(TenKey::Seven),
+        _ => None,
+      },
+      _ => None,
+    }
+  }
+
+  fn position(&self) -> Coordinate {
+    match self {
+      TenKey::Seven => Coordinate{x: 2, y: 3},
+      TenKey::Eight => Coordinate{x: 1, y: 3},
+      TenKey::Nine => Coordinate{x: 0, y: 3},
+      TenKey::Four => Coordinate{x: 2, y: 2},
+      TenKey::Five => Coordinate{x: 1, y: 2},
+      TenKey::Six => Coordinate{x: 0, y: 2},
+      TenKey::One => Coordinate{x: 2, y: 1},
+      TenKey::Two => Coordinate{x: 1, y: 1},
+      TenKey::Three => Coordinate{x: 0, y: 1},
+      TenKey::Zero => Coordinate{x: 1, y: 0},
+      TenKey::Activate => Coordinate{x: 0, y: 0},
+    }
+  }
+}
+
+#[derive(Clone,Copy,Debug,Eq,Hash,PartialEq)]
+pub enum ArrowKey {
+  Up, Activate,
+  Left, Down, Right,
+}
+
+impl ArrowKey {
+  pub const ALL: [ArrowKey; 5] = [ArrowKey::Up, ArrowKey::Activate, ArrowKey::Left,
+                                  ArrowKey::Down, ArrowKey::Right];
+
+  pub fn from_char(ch: char) -> Result<ArrowKey, String> {
+    match ch {
+      '^' => Ok(ArrowKey::Up),
+      'A' => Ok(ArrowKey::Activate),
+      'v' => Ok(ArrowKey::Down),
+      '<' => Ok(ArrowKey::Left),
+      '>' => Ok(ArrowKey::Right),
+      _ => Err(format!("Invalid ArrowKey char '{}'", ch)),
+    }
+  }
+
+  pub fn to_char(self) -> char {
+    match self {
+      ArrowKey::Up => '^',
+      ArrowKey::Activate => 'A',
+      ArrowKey::Down => 'v',
+      ArrowKey::Left => '<',
+      ArrowKey::Right => '>',
+    }
+  }
+
+  /// Move the coordinate one step in this direction. Activate doesn't move.
+  pub fn step(self, coordinate: Coordinate) -> Coordinate {
+    let Coordinate{x, y} = coordinate;
+    match self {
+      ArrowKey::Up => Coordinate{x, y: y + 1},
+      ArrowKey::Down => Coordinate{x, y: y - 1},
+      ArrowKey::Left => Coordinate{x: x + 1, y},
+      ArrowKey::Right => Coordinate{x: x - 1, y},
+      ArrowKey::Activate => coordinate,
+    }
+  }
+
+  /// Which vertical direction do we move to get from current to goal?
+  fn vertical_move(current: Position, goal: Position) -> Option<Self> {
+    match current.cmp(&goal) {
+      Ordering::Less => Some(ArrowKey::Up),
+      Ordering::Equal => None,
+      Ordering::Greater => Some(ArrowKey::Down),
+    }
+  }
+
+  /// Which horizontal direction do we move to get from current to goal?
+  fn horizontal_move(current: Position, goal: Position) -> Option<Self> {
+    match current.cmp(&goal) {
+      Ordering::Less => Some(ArrowKey::Left),
+      Ordering::Equal => None,
+      Ordering::Greater => Some(ArrowKey::Right),
+    }
+  }
+}
+
+
+impl KeyPad for ArrowKey {
+  fn from_position(coordinate: Coordinate) -> Option<Self> {
+    match coordinate.y {
+      0 => match coordinate.x {
+        0 => Some(ArrowKey::Right),
+        1 => Some(ArrowKey::Down),
+        2 => Some(ArrowKey::Left),
+        _ => None,
+      },
+      1 => match coordinate.x {
+        0 => Some(ArrowKey::Activate),
+        1 => Some(ArrowKey::Up),
+        _ => None,
+      },
+      _ => None,
+    }
+  }
+
+  fn position(&self) -> Coordinate {
+    match self {
+      ArrowKey::Up => Coordinate { x: 1, y: 1 },
+      ArrowKey::Activate => Coordinate { x: 0, y: 1 },
+      ArrowKey::Left => Coordinate { x: 2, y: 0 },
+      ArrowKey::Down => Coordinate { x: 1, y: 0 },
+      ArrowKey::Right => Coordinate { x: 0, y: 0 },
+    }
+  }
+}
+
+/// Return the possibilities for pressing the given key.
+/// Only the paths along direct routes are included.
+pub fn plan_paths<T: KeyPad>(curr_key: T, goal_key: T) -> Vec<Vec<ArrowKey>> {
+  plan_moves(curr_key.position(), goal_key.position(),
+             |coordinate| T::from_position(coordinate).is_some())
+}
+
+/// Return the direct routes from current to goal followed by Activate,
+/// skipping any route whose corner isn't a button.
+fn plan_moves(current: Coordinate, goal: Coordinate,
+              is_button: impl Fn(Coordinate) -> bool) -> Vec<Vec<ArrowKey>> {
+  if current == goal {
+    return vec![vec![ArrowKey::Activate]];
+  }
+  let mut result = Vec::new();
+  // Go horizontal and then vertical.
+  if let Some(dir) = ArrowKey::horizontal_move(current.x, goal.x) {
+    // Only include the path if it avoids the missing key.
+    if current.y == goal.y ||
+        is_button(Coordinate{x: goal.x, y: current.y}) {
+      let mut path = vec![dir; current.x.abs_diff(goal.x) as usize];
+      if let Some(dir) = ArrowKey::vertical_move(current.y, goal.y) {
+        path.extend(iter::once(dir).cycle().take(current.y.abs_diff(goal.y) as usize));
+      }
+      path.push(ArrowKey::Activate);
+      result.push(path);
+    }
+  }
+  // Go vertical first and then horizontal.
+  if let Some(dir) = ArrowKey::vertical_move(current.y, goal.y) {
+    // Only include the path if it avoids the missing key.
+    if current.x == goal.x ||
+        is_button(Coordinate{x: current.x, y: goal.y}) {
+      let mut path = vec![dir; current.y.abs_diff(goal.y) as usize];
+      if let Some(dir) = ArrowKey::horizontal_move(current.x, goal.x) {
+        path.extend(iter::once(dir).cycle().take(current.x.abs_diff(goal.x) as usize));
+      }
+      path.push(ArrowKey::Activate);
+      result.push(path);
+    }
+  }
+  result
+}
+
+/// A key pad with a layout given at runtime, such as
+/// "789\n456\n123\n 0A", where spaces are the gaps in the pad.
+#[derive(Clone,Debug)]
+pub struct Layout {
+  buttons: AHashMap<char, Coordinate>,
+  gaps: AHashSet<Coordinate>,
+}
+
+impl Layout {
+  /// Find the position of the given button.
+  pub fn position(&self, button: char) -> Option<Coordinate> {
+    self.buttons.get(&button).copied()
+  }
+
+  /// Is there a gap at the given coordinate?
+  pub fn is_gap(&self, coordinate: Coordinate) -> bool {
+    self.gaps.contains(&coordinate)
+  }
+
+  /// Return the possibilities for pressing the goal button when starting
+  /// from the current button. Only the paths along direct routes that
+  /// avoid the gaps are included.
+  pub fn plan_paths(&self, current: char, goal: char) -> Result<Vec<Vec<ArrowKey>>, String> {
+    let find = |button| self.position(button)
+        .ok_or_else(|| format!("Unknown button '{}'", button));
+    Ok(plan_moves(find(current)?, find(goal)?, |coordinate| !self.is_gap(coordinate)))
+  }
+}
+
+impl FromStr for Layout {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let rows = s.lines().collect_vec();
+    let width = rows.iter().map(|row| row.chars().count()).max().unwrap_or(0);
+    if rows.len() > Position::MAX as usize || width > Position::MAX as usize {
+      return Err(format!("Layout is too large at {}x{}", width, rows.len()));
+    }
+    let mut buttons = AHashMap::new();
+    let mut gaps = AHashSet::new();
+    for (row, line) in rows.iter().enumerate() {
+      let chars = line.chars().collect_vec();
+      for column in 0..width {
+        let coordinate = Coordinate{x: (width - 1 - column) as Position,
+                                    y: (rows.len() - 1 - row) as Position};
+        match chars.get(column) {
+          None | Some(' ') => { gaps.insert(coordinate); },
+          Some(&ch) => if buttons.insert(ch, coordinate).is_some() {
+            return Err(format!("Duplicate button '{}'", ch));
+          },
+        }
+      }
+    }
+    Ok(Layout { buttons, gaps })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::str::FromStr;
+  use itertools::Itertools;
+  use super::{plan_paths, ArrowKey, KeyPad, Layout, TenKey};
+
+  fn render(paths: &[Vec<ArrowKey>]) -> Vec<String> {
+    paths.iter().map(|path| path.iter().map(|key| key.to_char()).collect()).collect()
+  }
+
+  #[test]
+  fn test_ten_key() {
+    for key in "0123456789A".chars() {
+      let key = TenKey::from_char(key).unwrap();
+      assert_eq!(Some(key), TenKey::from_position(key.position()));
+    }
+    assert!(TenKey::from_char('B').is_err());
+  }
+
+  #[test]
+  fn test_arrow_key() {
+    for key in "^v<>A".chars() {
+      assert_eq!(key, ArrowKey::from_char(key).unwrap().to_char());
+    }
+    assert!(ArrowKey::from_char('V').is_err());
+    let up = ArrowKey::Up.position();
+    assert_eq!(up, ArrowKey::Up.step(ArrowKey::Down.position()));
+    assert_eq!(ArrowKey::Activate.position(), ArrowKey::Right.step(up));
+    assert_eq!(None, ArrowKey::from_position(ArrowKey::Left.step(up)));
+  }
+
+  #[test]
+  fn test_plan_paths() {
+    let ten = |ch| TenKey::from_char(ch).unwrap();
+    // these have to avoid the gap in the corner of the ten key
+    assert_eq!(vec![">vvvA"], render(&plan_paths(ten('7'), ten('0'))));
+    assert_eq!(vec!["^<<A"], render(&plan_paths(ten('A'), ten('1'))));
+    assert_eq!(vec!["<^A", "^<A"], render(&plan_paths(ten('A'), ten('2'))));
+    // and the gap in the corner of the arrow pad
+    assert_eq!(vec![">^A"], render(&plan_paths(ArrowKey::Left, ArrowKey::Up)));
+    assert_eq!(vec!["v<<A"], render(&plan_paths(ArrowKey::Activate, ArrowKey::Left)));
+    assert_eq!(vec!["A"], render(&plan_paths(ArrowKey::Down, ArrowKey::Down)));
+  }
+
+  #[test]
+  fn test_layout() {
+    let layout = Layout::from_str("789\n456\n123\n 0A").unwrap();
+    for (from, to) in "0123456789A".chars().tuple_combinations() {
+      let expected = plan_paths(TenKey::from_char(from).unwrap(), TenKey::from_char(to).unwrap());
+      assert_eq!(expected, layout.plan_paths(from, to).unwrap());
+    }
+    assert_eq!(vec![">>vvvA"], render(&layout.plan_paths('7', 'A').unwrap()));
+    assert!(layout.plan_paths('7', 'B').is_err());
+    assert!(Layout::from_str("12\n21").is_err());
+
+    // a pad with a gap in the middle has to go around it
+    let ring = Layout::from_str("abc\nd e\nfgh").unwrap();
+    assert_eq!(vec![">>vvA", "vv>>A"], render(&ring.plan_paths('a', 'h').unwrap()));
+    assert_eq!(vec![">vA"], render(&ring.plan_paths('b', 'e').unwrap()));
+    assert_eq!(vec!["vvA"], render(&ring.plan_paths('b', 'g').unwrap()));
+  }
+}
@@ -0,0 +1,138 @@
+//! A private leaderboard from adventofcode.com, with each member's score
+//! and when they got the stars of each day. The site asks that the JSON
+//! isn't fetched more than once every 15 minutes.
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use colored::Colorize;
+use serde::Deserialize;
+use crate::download;
+use crate::solves::{format_delay, unlock_time};
+
+/// When a member got one of the stars.
+#[derive(Clone,Debug,Deserialize,PartialEq)]
+pub struct Star {
+  pub get_star_ts: i64,
+}
+
+/// A member of the leaderboard. Anonymous members don't have a name.
+#[derive(Clone,Debug,Deserialize,PartialEq)]
+pub struct Member {
+  pub id: u64,
+  pub name: Option<String>,
+  pub local_score: u64,
+  pub stars: u64,
+  /// The stars by the day and then the part, both as strings.
+  #[serde(default)]
+  pub completion_day_level: BTreeMap<String, BTreeMap<String, Star>>,
+}
+
+impl Member {
+  pub fn display_name(&self) -> String {
+    self.name.clone().unwrap_or_else(|| format!("(anonymous user #{})", self.id))
+  }
+
+  /// When the member got the star of the day's part, as seconds since the
+  /// epoch.
+  pub fn star(&self, day: usize, part: usize) -> Option<i64> {
+    self.completion_day_level.get(&day.to_string())?.get(&part.to_string())
+        .map(|star| star.get_star_ts)
+  }
+}
+
+#[derive(Clone,Debug,Deserialize,PartialEq)]
+pub struct Leaderboard {
+  pub event: String,
+  pub owner_id: u64,
+  pub members: BTreeMap<String, Member>,
+}
+
+impl Leaderboard {
+  pub fn parse(json: &str) -> Result<Self, String> {
+    serde_json::from_str(json).map_err(|e| format!("Can't parse the leaderboard: {e}"))
+  }
+
+  /// The members from the highest score down, with ties broken by the
+  /// number of stars and then the name.
+  pub fn standings(&self) -> Vec<&Member> {
+    let mut members: Vec<&Member> = self.members.values().collect();
+    members.sort_by_key(|member| (std::cmp::Reverse((member.local_score, member.stars)),
+                                  member.display_name()));
+    members
+  }
+
+  /// Draw the standings with a column for each day, where a yellow '*' is
+  /// both stars and a gray '*' is only the first. It is followed by how
+  /// long each member took to get each star after the puzzle unlocked.
+  pub fn render(&self) -> String {
+    let year = self.event.parse().unwrap_or(0);
+    let standings = self.standings();
+    let mut result = String::new();
+    let days: String = (1..=25).map(|day| char::from(b'0' + (day % 10) as u8)).collect();
+    writeln!(result, "{:>4} {:>5} {:>5}  {days}", "", "Score", "Stars").unwrap();
+    for (rank, member) in standings.iter().enumerate() {
+      let stars: String = (1..=25).map(|day| match (member.star(day, 1), member.star(day, 2)) {
+        (_, Some(_)) => "*".yellow().to_string(),
+        (Some(_), None) => "*".bright_black().to_string(),
+        _ => " ".to_string(),
+      }).collect();
+      writeln!(result, "{:>3}) {:>5} {:>5}  {stars}  {}", rank + 1, member.local_score,
+               member.stars, member.display_name()).unwrap();
+    }
+    for day in 1..=25 {
+      let Some(unlock) = unlock_time(year, day) else { continue };
+      let times: Vec<String> = standings.iter()
+          .filter_map(|member| {
+            let first = member.star(day, 1)?;
+            let second = member.star(day, 2)
+                .map_or("-".to_string(), |second| format_delay(second - unlock));
+            Some(format!("{} {}/{second}", member.display_name(), format_delay(first - unlock)))
+          }).collect();
+      if !times.is_empty() {
+        writeln!(result, "{} {}", format!("Day {day}:").bold(), times.join(", ")).unwrap();
+      }
+    }
+    result
+  }
+}
+
+/// Download the leaderboard's JSON using the session cookie.
+pub fn fetch(year: usize, id: u64, session: Option<&str>) -> Result<Leaderboard, String> {
+  let session = download::session(session)?;
+  let url = format!("https://adventofcode.com/{year}/leaderboard/private/view/{id}.json");
+  let mut response = ureq::get(&url)
+      .header("Cookie", &format!("session={session}"))
+      .call()
+      .map_err(|e| format!("Error fetching {url}: {e}"))?;
+  let json = response.body_mut().read_to_string()
+      .map_err(|e| format!("Error reading {url}: {e}"))?;
+  Leaderboard::parse(&json)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::Leaderboard;
+
+  const JSON: &str = r#"{"event": "2024", "owner_id": 7, "members": {
+    "7": {"id": 7, "name": "Owen", "local_score": 10, "stars": 3, "last_star_ts": 0,
+          "global_score": 0, "completion_day_level": {
+            "1": {"1": {"get_star_ts": 1733029500, "star_index": 1},
+                  "2": {"get_star_ts": 1733030100, "star_index": 2}},
+            "2": {"1": {"get_star_ts": 1733115900, "star_index": 3}}}},
+    "9": {"id": 9, "name": null, "local_score": 4, "stars": 1, "completion_day_level": {
+            "1": {"1": {"get_star_ts": 1733032800, "star_index": 1}}}}}}"#;
+
+  #[test]
+  fn test_leaderboard() {
+    let board = Leaderboard::parse(JSON).unwrap();
+    let standings = board.standings();
+    assert_eq!(vec!["Owen", "(anonymous user #9)"],
+               standings.iter().map(|member| member.display_name()).collect::<Vec<_>>());
+    assert_eq!(Some(1733029500), standings[0].star(1, 1));
+    assert_eq!(None, standings[1].star(1, 2));
+    let text = board.render();
+    assert!(text.contains("  1)    10     3  "), "{text}");
+    assert!(text.contains("Owen 0:05:00/0:15:00, (anonymous user #9) 1:00:00/-"), "{text}");
+    assert!(text.contains("Owen 0:05:00/-"), "{text}");
+    assert!(Leaderboard::parse("{}").is_err());
+  }
+}
@@ -1,5 +1,92 @@
 extern crate core;
 
+use utils::{Answer, DayInfo, Solver};
+
+pub mod batch;
+pub mod budget;
+pub mod check;
+pub mod compare;
+pub mod config;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod download;
+pub mod explain;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod history;
+pub mod keypad;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod leaderboard;
+pub mod memory;
+pub mod params;
+#[cfg(all(feature = "profile", not(target_arch = "wasm32")))]
+pub mod profile;
+pub mod progress;
+pub mod report;
+pub mod solves;
+pub mod testgen;
+pub mod threads;
+pub mod timing;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod tui;
 pub mod utils;
+pub mod visualize;
+pub mod y2024;
+
+#[cfg(feature = "memory")]
+#[global_allocator]
+static ALLOCATOR: memory::TrackingAllocator = memory::TrackingAllocator;
+
+/// The years with solutions, each with its registry of days.
+pub const YEARS: &[(usize, &[&dyn Solver])] = &[(y2024::YEAR, y2024::SOLVERS)];
+
+/// The year that the runner and the tools use when one isn't given.
+pub const DEFAULT_YEAR: usize = y2024::YEAR;
+
+/// The registry of days for a year.
+pub fn solvers(year: usize) -> Result<&'static [&'static dyn Solver], String> {
+  YEARS.iter().find(|(y, _)| *y == year).map(|(_, solvers)| *solvers)
+      .ok_or(format!("Year {year} isn't implemented"))
+}
+
+/// Find the solver for a day of a year.
+pub fn find_solver(year: usize, day: usize) -> Result<&'static dyn Solver, String> {
+  let name = format!("day{day}");
+  solvers(year)?.iter().copied().find(|solver| solver.name() == name)
+      .ok_or(format!("Day {day} of {year} isn't implemented"))
+}
+
+/// Describe each of the implemented days of every year.
+pub fn registry() -> Vec<DayInfo> {
+  YEARS.iter().flat_map(|(_, solvers)| solvers.iter().map(|solver| solver.info())).collect()
+}
+
+/// Solve one part of a day from the input, returning an error rather than
+/// panicking if the year, day, part, or input is bad, or if the part can't
+/// answer the input.
+pub fn solve(year: usize, day: usize, part: usize, input: &str) -> Result<Answer, String> {
+  let solver = find_solver(year, day)?;
+  let parsed = solver.try_generator(input)?;
+  match part {
+    1 => solver.part1(&parsed),
+    2 => solver.part2(&parsed),
+    _ => Err(format!("Part {part} doesn't exist")),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::y2024::day17;
+  use super::{solve, DEFAULT_YEAR};
 
-day_list!(1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,16,17,18,19,20,21);
\ No newline at end of file
+  #[test]
+  fn test_solve() {
+    let input = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3";
+    assert_eq!("11", solve(DEFAULT_YEAR, 1, 1, input).unwrap().to_string());
+    assert_eq!(Err("Part 3 doesn't exist".to_string()),
+               solve(DEFAULT_YEAR, 1, 3, input).map(|a| a.to_string()));
+    // there isn't an A that makes this program print itself
+    let input = "Register A: 5\nRegister B: 0\nRegister C: 0\n\nProgram: 0,1,5,4,3,0";
+    assert_eq!(Err(day17::NO_QUINE.to_string()),
+               solve(DEFAULT_YEAR, 17, 2, input).map(|a| a.to_string()));
+  }
+}
\ No newline at end of file
@@ -1,23 +1,253 @@
 use std::time;
-use omalley_aoc2024::{FUNCS, NAMES, utils};
+use omalley_aoc2024::{batch, budget, check, compare, config, download, find_solver, history,
+                      leaderboard, params, progress, registry, report, solvers, solves, threads,
+                      timing, tui, utils, visualize, DEFAULT_YEAR};
+use omalley_aoc2024::utils::Solver;
+use omalley_aoc2024::y2024::{day17, day18};
+use omalley_aoc2024::utils::{anim, render};
 
 use argh::FromArgs;
 use colored::Colorize;
+use indicatif::{ProgressBar, ProgressStyle};
 use serde::{Deserialize,Serialize};
 use std::collections::BTreeMap;
 use std::fs::File;
-use std::path::Path;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use tracing_subscriber::fmt::format::FmtSpan;
+
+/// How the runner prints the results.
+#[derive(Clone,Copy,Debug,Default,Eq,PartialEq)]
+enum OutputFormat {
+  #[default]
+  Text,
+  Json,
+  Markdown,
+  Csv,
+}
+
+impl FromStr for OutputFormat {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "text" => Ok(OutputFormat::Text),
+      "json" => Ok(OutputFormat::Json),
+      "markdown" => Ok(OutputFormat::Markdown),
+      "csv" => Ok(OutputFormat::Csv),
+      _ => Err(format!("Unknown output format '{s}', expected text, json, markdown, or csv")),
+    }
+  }
+}
 
 #[derive(FromArgs)]
 /** Solution for Advent of Code (https://adventofcode.com/)*/
 struct Args {
-  /// the input directory
-  #[argh(option, short='i', default="String::from(\"input\")")]
-  input: String,
+  /// the configuration file (defaults to aoc.toml if it exists)
+  #[argh(option)]
+  config: Option<String>,
+
+  /// the input directory (defaults to input for 2024 and input/<year> for the others)
+  #[argh(option, short='i')]
+  input: Option<String>,
+
+  /// the year of the puzzles to run (defaults to 2024)
+  #[argh(option, default="DEFAULT_YEAR")]
+  year: usize,
+
+  /// the directory for the downloaded inputs (defaults to ~/.cache/aoc2024)
+  #[argh(option)]
+  cache_dir: Option<String>,
+
+  /// download the inputs again even if they are cached
+  #[argh(switch)]
+  refetch: bool,
+
+  /// log the days' spans and events at this level: error, warn, info, debug, or trace
+  #[argh(option)]
+  log_level: Option<tracing::Level>,
+
+  /// parse each input from the file a line at a time instead of reading it into memory first,
+  /// although the grid days and days 3, 9, 11, 17, and 19 still read the whole input
+  #[argh(switch)]
+  stream: bool,
+
+  /// warn about the parts that take longer than this many milliseconds (defaults to 1000)
+  #[argh(option)]
+  budget: Option<u64>,
+
+  /// stop the run when a part goes over its --budget
+  #[argh(switch)]
+  abort_over_budget: bool,
+
+  /// the number of threads for the days that run in parallel (defaults to one per core)
+  #[argh(option)]
+  threads: Option<usize>,
+
+  /// don't show progress bars for the slow parts
+  #[argh(switch)]
+  no_progress: bool,
+
+  /// the output format: text, json, markdown, or csv (defaults to text)
+  #[argh(option, default="OutputFormat::Text")]
+  output: OutputFormat,
+
+  /// time each generator and part over several runs instead of solving once
+  #[argh(switch)]
+  time: bool,
+
+  /// the number of measured runs for --time (defaults to 10)
+  #[argh(option, default="10")]
+  iterations: usize,
+
+  /// the number of warmup runs for --time (defaults to 3)
+  #[argh(option, default="3")]
+  warmup: usize,
+
+  /// run each generator and part this many times and report the mean, median, and 95th
+  /// percentile without the outliers
+  #[argh(option)]
+  repeat: Option<usize>,
+
+  /// write every --repeat run's time to this CSV file
+  #[argh(option)]
+  samples: Option<String>,
+
+  /// write a flamegraph of each step to this directory, running each one --iterations times
+  /// (needs the profile feature)
+  #[argh(option)]
+  profile: Option<String>,
+
+  /// save the --time results to this baseline file
+  #[argh(option)]
+  save_baseline: Option<String>,
+
+  /// write the history of the --time runs from timings.yml in the input directory to this
+  /// .json or .csv file
+  #[argh(option)]
+  export_timings: Option<String>,
+
+  /// draw the latest parse and solve times of each day from the --time history as an SVG
+  /// bar chart in this file
+  #[argh(option)]
+  plot_timings: Option<String>,
+
+  /// compare the --time results to this baseline file
+  #[argh(option)]
+  baseline: Option<String>,
+
+  /// the slowdown in percent that counts as a regression (defaults to 10)
+  #[argh(option, default="10.0")]
+  threshold: f64,
+
+  /// write each day's parsed input as JSON into this directory (needs the serde feature)
+  #[argh(option)]
+  dump_parsed: Option<String>,
+
+  /// run the days from the parsed inputs that --dump-parsed wrote to this directory
+  #[argh(option)]
+  load_parsed: Option<String>,
+
+  /// describe the intermediate results of the days that can explain them (days 5, 13, and 16)
+  #[argh(switch)]
+  explain: bool,
+
+  /// run the day's examples and then its input, checking them against examples.yml and
+  /// answers.yml
+  #[argh(option)]
+  check: Option<usize>,
+
+  /// the directory with the examples and examples.yml for --check (defaults to tests/data)
+  #[argh(option, default="String::from(\"tests/data\")")]
+  examples: String,
+
+  /// run every implementation of the picked days' parts, check that they agree, and
+  /// compare their times
+  #[argh(switch)]
+  compare: bool,
+
+  /// check the answers against the ones recorded in answers.yml and fail if any differ
+  #[argh(switch)]
+  verify: bool,
+
+  /// show the text of the day's puzzle, which is downloaded to the cache directory
+  #[argh(option)]
+  show_puzzle: Option<usize>,
+
+  /// show the standings and star times of the private leaderboard with this id
+  #[argh(option)]
+  leaderboard: Option<u64>,
+
+  /// run the picked day on every .txt file in this directory and print a table of the
+  /// answers and times
+  #[argh(option)]
+  batch: Option<String>,
+
+  /// show when each part was first solved from solves.yml in the input cache
+  #[argh(switch)]
+  solves: bool,
+
+  /// list the implemented days with their titles and answer types
+  #[argh(switch)]
+  list: bool,
 
   /// days to execute (defaults to all)
   #[argh(positional)]
   days: Vec<usize>,
+
+  /// run the day 17 program in an interactive debugger
+  #[argh(switch)]
+  debug17: bool,
+
+  /// animate the falling bytes of day 18
+  #[argh(switch)]
+  animate18: bool,
+
+  /// show the day step by step in the terminal (days 6, 14, 15, and 18)
+  #[argh(option)]
+  visualize: Option<usize>,
+
+  /// walk through the day's steps with commands instead of playing them (days 6, 14, 15, and 18)
+  #[argh(option)]
+  debug_step: Option<usize>,
+
+  /// with --visualize, write the steps to this animated GIF instead
+  #[argh(option)]
+  gif: Option<String>,
+
+  /// the most frames to write to the --gif (defaults to 1000)
+  #[argh(option, default="1000")]
+  frames: usize,
+
+  /// the number of steps to skip before the first frame of the --gif or the --snapshot
+  #[argh(option, default="0")]
+  skip: usize,
+
+  /// write a PNG picture of the day's grid to stdout (days 4, 6, 8, 10, 12, 14, 15, 16, 18, and 20)
+  #[argh(option)]
+  snapshot: Option<usize>,
+
+  /// the size of each cell in pixels for --gif and --snapshot (defaults to 4 and 8)
+  #[argh(option)]
+  cell_size: Option<usize>,
+
+  /// the colors for --gif and --snapshot: dark or light (defaults to dark)
+  #[argh(option, default="String::from(\"dark\")")]
+  palette: String,
+
+  /// print an SVG picture of the day's answer (days 12, 16, and 20)
+  #[argh(option)]
+  svg: Option<usize>,
+
+  /// the frames per second for --visualize, --gif, and --animate18 (defaults to 20)
+  #[argh(option, default="20")]
+  fps: u32,
+
+  /// change a day's parameter, such as day11.blinks2=30 (may be repeated)
+  #[argh(option)]
+  param: Vec<String>,
 }
 
 #[derive(Default,Deserialize,Serialize)]
@@ -39,17 +269,32 @@ impl Answers {
     }
   }
 
-  fn update(&mut self, delta_list: &Vec<utils::DayResult>) {
+  /// Record the new answers and return a message for each one that changed.
+  fn update(&mut self, delta_list: &Vec<utils::DayResult>) -> Vec<String> {
+    let mut changes = Vec::new();
     for delta in delta_list {
       let new_val = delta.get_answers();
       if let Some(prev) =
           self.days.insert(delta.day.to_string(), new_val.clone()) {
         if prev != new_val {
-          println!("{}", format!("Output for {} changed from {:?} to {:?}!",
-                                 delta.pretty_day(), prev, new_val).bold());
+          changes.push(format!("Output for {} changed from {:?} to {:?}!",
+                               delta.pretty_day(), prev, new_val));
         }
       }
     }
+    changes
+  }
+
+  /// Return a message for each result that doesn't match the recorded
+  /// answers. The days without recorded answers are skipped.
+  fn verify(&self, results: &[utils::DayResult]) -> Vec<String> {
+    results.iter()
+        .filter_map(|result| {
+          let expected = self.days.get(&result.day)?;
+          let actual = result.get_answers();
+          (*expected != actual).then(|| format!("{} gave {:?} instead of {:?}",
+                                                result.pretty_day(), actual, expected))
+        }).collect()
   }
 
   fn write(&self, directory: &str) {
@@ -63,37 +308,606 @@ impl Answers {
   }
 }
 
+/// Shows the progress of the slow parts as a bar on stderr, which is cleared
+/// when the part finishes.
+#[derive(Default)]
+struct Bar {
+  bar: Mutex<Option<ProgressBar>>,
+}
+
+impl progress::Progress for Bar {
+  fn start(&self, label: &str, total: usize) {
+    let bar = ProgressBar::new(total as u64)
+        .with_style(ProgressStyle::with_template("{msg} [{bar:40}] {percent:>3}% {elapsed}")
+            .expect("Bad progress template")
+            .progress_chars("=> "))
+        .with_message(label.to_string());
+    *self.bar.lock().unwrap() = Some(bar);
+  }
+
+  fn update(&self, done: usize) {
+    if let Some(bar) = self.bar.lock().unwrap().as_ref() {
+      bar.set_position(done as u64);
+    }
+  }
+
+  fn finish(&self) {
+    if let Some(bar) = self.bar.lock().unwrap().take() {
+      bar.finish_and_clear();
+    }
+  }
+}
+
+/// Print the registry's description of each day in the output format.
+fn print_registry(format: OutputFormat) {
+  let days = registry();
+  match format {
+    OutputFormat::Text => {
+      for info in &days {
+        println!("{} {:>2} {:<24} {} / {}", info.year, info.day, info.title, info.part1,
+                 info.part2.unwrap_or("-"));
+      }
+    }
+    OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&days)
+        .expect("Registry can be serialized")),
+    OutputFormat::Markdown => {
+      println!("| Year | Day | Title | Part 1 | Part 2 |");
+      println!("|-----:|----:|:------|:-------|:-------|");
+      for info in &days {
+        println!("| {} | {} | {} | {} | {} |", info.year, info.day, info.title, info.part1,
+                 info.part2.unwrap_or("-"));
+      }
+    }
+    OutputFormat::Csv => {
+      println!("year,day,title,part1,part2");
+      for info in &days {
+        println!("{},{},{},{},{}", info.year, info.day, info.title, info.part1, info.part2.unwrap_or(""));
+      }
+    }
+  }
+}
+
+/// Dumping and loading the parsed inputs as JSON, which needs the serde
+/// feature.
+#[cfg(feature = "serde")]
+mod parsed {
+  use std::path::{Path, PathBuf};
+  use omalley_aoc2024::utils::{DayResult, Solver};
+
+  fn filename(directory: &str, solver: &dyn Solver) -> PathBuf {
+    Path::new(directory).join(format!("{}.json", solver.name()))
+  }
+
+  /// Parse the input and write it to the directory.
+  pub fn dump(solver: &dyn Solver, input: &str, directory: &str) -> Result<PathBuf, String> {
+    let json = solver.dump(&solver.try_generator(input)?)?;
+    std::fs::create_dir_all(directory).map_err(|e| format!("Can't create {directory}: {e}"))?;
+    let filename = filename(directory, solver);
+    std::fs::write(&filename, json)
+        .map_err(|e| format!("Error writing {}: {e}", filename.display()))?;
+    Ok(filename)
+  }
+
+  /// Run the day from the parsed input in the directory.
+  pub fn run(solver: &dyn Solver, directory: &str) -> Result<DayResult, String> {
+    let filename = filename(directory, solver);
+    let json = std::fs::read_to_string(&filename)
+        .map_err(|e| format!("Error reading {}: {e}", filename.display()))?;
+    solver.run_loaded(&json).map_err(|e| format!("Error loading {}: {e}", filename.display()))
+  }
+}
+
+#[cfg(not(feature = "serde"))]
+mod parsed {
+  use std::path::PathBuf;
+  use omalley_aoc2024::utils::{DayResult, Solver};
+
+  const MISSING: &str = "The parsed inputs need the serde feature (cargo run --features serde)";
+
+  pub fn dump(_solver: &dyn Solver, _input: &str, _directory: &str) -> Result<PathBuf, String> {
+    Err(MISSING.to_string())
+  }
+
+  pub fn run(_solver: &dyn Solver, _directory: &str) -> Result<DayResult, String> {
+    Err(MISSING.to_string())
+  }
+}
+
+/// Writing flamegraphs of each step, which needs the profile feature.
+#[cfg(feature = "profile")]
+mod profiling {
+  pub use omalley_aoc2024::profile::profile_day;
+}
+
+#[cfg(not(feature = "profile"))]
+mod profiling {
+  use std::path::PathBuf;
+  use omalley_aoc2024::utils::Solver;
+
+  pub fn profile_day(_solver: &dyn Solver, _data: &str, _directory: &str,
+                     _iterations: usize) -> Result<Vec<PathBuf>, String> {
+    Err("Profiling needs the profile feature (cargo run --features profile)".to_string())
+  }
+}
+
+/// Read the inputs from the input directory, falling back to the cache
+/// and downloading them if they aren't there. Returns where each input was
+/// read from along with the input.
+fn read_inputs(directory: &str, year: usize, days: &[&dyn Solver], picked: &[bool],
+               cache: &download::InputCache,
+               refetch: bool) -> Result<Vec<(PathBuf, String)>, String> {
+  days.iter().map(|solver| solver.name()).zip(picked)
+      .map(|(name, &picked)| {
+        let filename = Path::new(directory).join(format!("{name}.txt"));
+        if !picked {
+          Ok((filename, String::new()))
+        } else if !refetch && filename.exists() {
+          let input = utils::read_inputs(directory, &[name], &[true])?.remove(0);
+          Ok((filename, input))
+        } else {
+          let day = name.trim_start_matches("day").parse()
+              .map_err(|_| format!("Bad day name {name}"))?;
+          Ok((cache.path(year, day), cache.get(year, day, refetch)?))
+        }
+      }).collect()
+}
+
+/// Find the input files for --stream, downloading any that are missing
+/// from both the input directory and the cache.
+fn input_paths(directory: &str, year: usize, days: &[&dyn Solver], picked: &[bool],
+               cache: &download::InputCache, refetch: bool) -> Result<Vec<PathBuf>, String> {
+  days.iter().map(|solver| solver.name()).zip(picked)
+      .map(|(name, &picked)| {
+        let filename = Path::new(directory).join(format!("{name}.txt"));
+        if !picked || (!refetch && filename.exists()) {
+          Ok(filename)
+        } else {
+          let day = name.trim_start_matches("day").parse()
+              .map_err(|_| format!("Bad day name {name}"))?;
+          cache.get(year, day, refetch)?;
+          Ok(cache.path(year, day))
+        }
+      }).collect()
+}
+
+/// Run the day, parsing the input as it is read from the file.
+fn run_streaming(solver: &dyn Solver, path: &Path) -> Result<utils::DayResult, String> {
+  let file = File::open(path).map_err(|e| format!("Error reading {}: {e}", path.display()))?;
+  solver.run_from_reader(&mut std::io::BufReader::new(file))
+}
+
+/// Change the parameter from a flag such as "day20.cheat_limit=50".
+fn parse_param(params: &mut params::Params, flag: &str) -> Result<(), String> {
+  let (name, value) = flag.split_once('=').ok_or(format!("Can't find '=' in {flag}"))?;
+  let (day, name) = name.split_once('.').ok_or(format!("Can't find the day in {flag}"))?;
+  let value = value.parse().map_err(|_| format!("Can't parse the value of {flag}"))?;
+  params.set(day, name, value)
+}
+
 fn main() {
   let args: Args = argh::from_env();
+  if let Some(level) = args.log_level {
+    tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_span_events(FmtSpan::CLOSE)
+        .with_writer(std::io::stderr)
+        .init();
+  }
+  let config = config::Config::load(args.config.as_deref()).unwrap_or_else(|e| panic!("{e}"));
+  if let Some(threads) = args.threads.or(config.threads) {
+    threads::configure(threads).unwrap_or_else(|e| panic!("{e}"));
+  }
+  // the flags win over the configuration file
+  let input_dir = args.input.clone().unwrap_or_else(|| {
+    let base = config.input_dir.clone().unwrap_or_else(|| PathBuf::from("input"));
+    if args.year == DEFAULT_YEAR {
+      base.to_string_lossy().to_string()
+    } else {
+      base.join(args.year.to_string()).to_string_lossy().to_string()
+    }
+  });
+  let mut params = config.params().unwrap_or_else(|e| panic!("{e}"));
+  for param in &args.param {
+    parse_param(&mut params, param).unwrap_or_else(|e| panic!("{e}"));
+  }
+  // the answers with other parameters aren't the puzzle's answers
+  let custom_params = params != params::Params::default();
+  // the days that run on this thread use the parameters
+  let _params = params::install(params);
+  if args.list {
+    print_registry(args.output);
+    return;
+  }
+  let cache = download::InputCache::new(args.cache_dir.map(PathBuf::from)
+      .or(config.cache_dir.clone())
+      .unwrap_or_else(download::InputCache::default_directory))
+      .with_session(config.session.clone());
+  // the solves are kept out of the checkout along with the downloads
+  let solves_dir = cache.year_directory(args.year);
+  if args.solves {
+    let log = solves::SolveLog::read(&solves_dir).unwrap_or_else(|e| panic!("{e}"));
+    print!("{}", log.report(args.year as i32));
+    return;
+  }
+  if let Some(day) = args.show_puzzle {
+    let text = cache.get_puzzle(args.year, day, args.refetch).unwrap_or_else(|e| panic!("{e}"));
+    print!("{text}");
+    return;
+  }
+  if args.export_timings.is_some() || args.plot_timings.is_some() {
+    let history = history::History::read(&input_dir).unwrap_or_else(|e| panic!("{e}"));
+    if let Some(filename) = &args.export_timings {
+      let text = match Path::new(filename).extension().and_then(|ext| ext.to_str()) {
+        Some("json") => history.to_json(),
+        Some("csv") => history.to_csv(),
+        _ => panic!("--export-timings writes a .json or .csv file, not {filename}"),
+      };
+      std::fs::write(filename, text).unwrap_or_else(|e| panic!("Error writing {filename}: {e}"));
+    }
+    if let Some(filename) = &args.plot_timings {
+      std::fs::write(filename, history.to_svg())
+          .unwrap_or_else(|e| panic!("Error writing {filename}: {e}"));
+    }
+    return;
+  }
+  if let Some(id) = args.leaderboard {
+    let board = leaderboard::fetch(args.year, id, config.session.as_deref())
+        .unwrap_or_else(|e| panic!("{e}"));
+    print!("{}", board.render());
+    return;
+  }
+  if args.debug17 {
+    let input = utils::read_inputs(&input_dir, &["day17"], &[true])
+        .expect("Can't read input");
+    let (state, program, _) = day17::generator(&input[0]);
+    let mut debugger = day17::Debugger::new(&state, &program);
+    day17::debug(&mut debugger, std::io::stdin().lock(), std::io::stdout())
+        .expect("Debugger failed");
+    return;
+  }
+  if args.animate18 {
+    let input = utils::read_inputs(&input_dir, &["day18"], &[true])
+        .expect("Can't read input");
+    let memory = day18::generator(&input[0]);
+    day18::animate(&memory, std::io::stdout().lock(), args.fps)
+        .expect("Animation failed");
+    return;
+  }
+  if let Some(day) = args.visualize {
+    let name = format!("day{day}");
+    let input = utils::read_inputs(&input_dir, &[&name], &[true])
+        .expect("Can't read input");
+    let mut simulation = visualize::for_day(day, &input[0]).unwrap_or_else(|e| panic!("{e}"));
+    if let Some(path) = &args.gif {
+      let defaults = render::gif::GifOptions::default();
+      let options = render::gif::GifOptions{cell_size: args.cell_size.unwrap_or(defaults.cell_size),
+        palette: render::palette_by_name(&args.palette).unwrap_or_else(|e| panic!("{e}")),
+        fps: args.fps, skip: args.skip, max_frames: args.frames};
+      let file = BufWriter::new(File::create(path).expect("Can't create GIF"));
+      let frames = render::gif::record(simulation.as_mut(), file, &options)
+          .unwrap_or_else(|e| panic!("{e}"));
+      println!("Wrote {frames} frames to {path}");
+    } else {
+      tui::play(simulation, args.fps)
+          .expect("Visualization failed");
+    }
+    return;
+  }
+  if let Some(day) = args.debug_step {
+    let name = format!("day{day}");
+    let input = utils::read_inputs(&input_dir, &[&name], &[true])
+        .expect("Can't read input");
+    let mut simulation = visualize::for_day(day, &input[0]).unwrap_or_else(|e| panic!("{e}"));
+    anim::debug_step(simulation.as_mut(), std::io::stdin().lock(), std::io::stdout())
+        .expect("Debugger failed");
+    return;
+  }
+  if let Some(day) = args.svg {
+    let name = format!("day{day}");
+    let input = utils::read_inputs(&input_dir, &[&name], &[true])
+        .expect("Can't read input");
+    print!("{}", visualize::svg_for_day(day, &input[0]).unwrap_or_else(|e| panic!("{e}")));
+    return;
+  }
+  if let Some(day) = args.snapshot {
+    let name = format!("day{day}");
+    let input = utils::read_inputs(&input_dir, &[&name], &[true])
+        .expect("Can't read input");
+    let defaults = render::png::PngOptions::default();
+    let options = render::png::PngOptions{cell_size: args.cell_size.unwrap_or(defaults.cell_size),
+      palette: render::palette_by_name(&args.palette).unwrap_or_else(|e| panic!("{e}"))};
+    let png = visualize::snapshot_for_day(day, &input[0], args.skip)
+        .and_then(|cells| render::png::render(&cells, &options))
+        .unwrap_or_else(|e| panic!("{e}"));
+    std::io::stdout().write_all(&png).expect("Can't write snapshot");
+    return;
+  }
+  if let Some(day) = args.check {
+    let solver = find_solver(args.year, day).unwrap_or_else(|e| panic!("{e}"));
+    let data = Path::new(&args.examples);
+    let manifest = check::read_manifest(data).unwrap_or_else(|e| panic!("{e}"));
+    let input = utils::read_inputs(&input_dir, &[solver.name()], &[true])
+        .expect("Can't read input");
+    let answers = Answers::read(&input_dir);
+    let mut checks = check::check_examples(solver, data, &manifest)
+        .unwrap_or_else(|e| panic!("{e}"));
+    checks.extend(check::check_real(solver, &input[0],
+                                    answers.days.get(solver.name()).map(Vec::as_slice))
+        .unwrap_or_else(|e| panic!("{e}")));
+    println!("{}", solver.name().replace("day", "Day ").bold());
+    for check in &checks {
+      println!(" · {check}");
+    }
+    let mut log = solves::SolveLog::read(&solves_dir).unwrap_or_else(|e| panic!("{e}"));
+    let now = chrono::Utc::now().timestamp();
+    for check in checks.iter().filter(|check| check.input == "input" && check.passed()) {
+      if check.expected.is_some() {
+        log.record(solver.name(), check.part, &check.actual, now);
+      }
+    }
+    log.write(&solves_dir).unwrap_or_else(|e| panic!("{e}"));
+    let failed = checks.iter().filter(|check| !check.passed()).count();
+    if failed > 0 {
+      eprintln!("{}", format!("{failed} of {} checks failed", checks.len()).red());
+      std::process::exit(1);
+    }
+    return;
+  }
   // Which days did the user pick to run?
-  let mut day_filter = [args.days.is_empty(); NAMES.len()];
+  let days = solvers(args.year).unwrap_or_else(|e| panic!("{e}"));
+  let mut day_filter = vec![args.days.is_empty(); days.len()];
   for day in args.days {
     let name = format!("day{day}");
-    if let Some(idx) = NAMES.iter().position(|&n| n == name) {
+    if let Some(idx) = days.iter().position(|solver| solver.name() == name) {
       day_filter[idx] = true;
     } else {
-      panic!("Can't find implementation for {name}.")
+      panic!("Can't find implementation for {name} of {}.", args.year)
+    }
+  }
+  let text = args.output == OutputFormat::Text;
+  if let Some(directory) = &args.batch {
+    let picked = days.iter().zip(&day_filter).filter(|(_, &picked)| picked)
+        .map(|(solver, _)| *solver).collect::<Vec<_>>();
+    let [solver] = picked[..] else {
+      panic!("--batch needs exactly one day, such as --batch inputs 6");
+    };
+    let files = batch::input_files(Path::new(directory)).unwrap_or_else(|e| panic!("{e}"));
+    let runs = batch::run(solver, &files);
+    if text {
+      print!("{}", batch::table(&runs));
+    } else {
+      for run in &runs {
+        if let Err(e) = &run.result {
+          eprintln!("{}: {e}", run.file.display());
+        }
+      }
+      let report = report::Report::new(runs.iter()
+          .filter_map(|run| Some((run.result.as_ref().ok()?, run.file.clone()))));
+      match args.output {
+        OutputFormat::Markdown => print!("{}", report.to_markdown()),
+        OutputFormat::Csv => print!("{}", report.to_csv()),
+        _ => println!("{}", report.to_json()),
+      }
     }
+    return;
   }
   // Read the inputs from the given directory
-  println!("{} {}\n", "Reading from".bold(), &args.input);
-  let inputs = utils::read_inputs(&args.input, NAMES, &day_filter)
+  if text {
+    println!("{} {}\n", "Reading from".bold(), &input_dir);
+  }
+  if args.stream && (args.load_parsed.is_some() || args.dump_parsed.is_some() || args.explain
+                     || args.compare || args.profile.is_some() || args.repeat.is_some()
+                     || args.time) {
+    panic!("--stream only applies to solving the days from their input files");
+  }
+  // the loaded and streamed days don't need their inputs in memory
+  let to_read = day_filter.iter()
+      .map(|&picked| picked && args.load_parsed.is_none() && !args.stream)
+      .collect::<Vec<bool>>();
+  let mut inputs = read_inputs(&input_dir, args.year, days, &to_read, &cache, args.refetch)
       .expect("Can't read input");
+  if args.stream {
+    let paths = input_paths(&input_dir, args.year, days, &day_filter, &cache, args.refetch)
+        .expect("Can't find input");
+    for (input, path) in inputs.iter_mut().zip(paths) {
+      input.0 = path;
+    }
+  }
 
-  let results=
-    FUNCS.iter().enumerate()
+  if let Some(directory) = &args.dump_parsed {
+    for (p, solver) in days.iter().enumerate().filter(|(p, _)| day_filter[*p]) {
+      let filename = parsed::dump(*solver, &inputs[p].1, directory)
+          .unwrap_or_else(|e| panic!("{e}"));
+      println!("Wrote {}", filename.display());
+    }
+    return;
+  }
+
+  if args.compare {
+    let mut disagreements = 0;
+    for (p, solver) in days.iter().enumerate().filter(|(p, _)| day_filter[*p]) {
+      for comparison in compare::compare(*solver, &inputs[p].1)
+          .unwrap_or_else(|e| panic!("{e}")) {
+        print!("{comparison}");
+        disagreements += !comparison.agrees() as usize;
+      }
+    }
+    if disagreements > 0 {
+      eprintln!("{}", format!("{disagreements} parts had implementations that disagree").red());
+      std::process::exit(1);
+    }
+    return;
+  }
+
+  if args.explain {
+    let mut out = std::io::stdout().lock();
+    for (p, solver) in days.iter().enumerate().filter(|(p, _)| day_filter[*p]) {
+      let input = solver.try_generator(&inputs[p].1).unwrap_or_else(|e| panic!("{e}"));
+      writeln!(out, "{}", solver.name().replace("day", "Day ").bold()).expect("Can't write");
+      if let Err(e) = solver.explain(&input, &mut out) {
+        eprintln!("{}", e.yellow());
+      }
+    }
+    return;
+  }
+
+  if let Some(directory) = &args.profile {
+    for (p, solver) in days.iter().enumerate().filter(|(p, _)| day_filter[*p]) {
+      for filename in profiling::profile_day(*solver, &inputs[p].1, directory, args.iterations)
+          .unwrap_or_else(|e| panic!("{e}")) {
+        println!("Wrote {}", filename.display());
+      }
+    }
+    return;
+  }
+
+  if let Some(repeat) = args.repeat {
+    if args.load_parsed.is_some() {
+      panic!("--repeat parses the inputs, so it can't use --load-parsed");
+    }
+    let options = timing::TimingOptions { warmup: args.warmup, iterations: repeat };
+    let mut samples = args.samples.as_ref().map(|filename| {
+      let mut file = BufWriter::new(File::create(filename).expect("Can't create samples"));
+      writeln!(file, "day,step,nanoseconds").expect("Can't write samples");
+      file
+    });
+    for (p, solver) in days.iter().enumerate().filter(|(p, _)| day_filter[*p]) {
+      let repeated = timing::RepeatedDay::measure(*solver, &inputs[p].1, &options);
+      println!("{repeated}");
+      if let Some(file) = samples.as_mut() {
+        repeated.write_samples(file).expect("Can't write samples");
+      }
+    }
+    return;
+  }
+
+  if args.time {
+    if args.load_parsed.is_some() {
+      panic!("--time parses the inputs, so it can't use --load-parsed");
+    }
+    let options = timing::TimingOptions { warmup: args.warmup, iterations: args.iterations };
+    let timings = days.iter().enumerate()
         .filter(|(p, _)| day_filter[*p])
-        .map(|(p, f)| {
-          let result = f(&inputs[p]);
+        .map(|(p, solver)| {
+          let result = timing::DayTiming::measure(*solver, &inputs[p].1, &options);
           println!("{result}");
           result})
+        .collect::<Vec<timing::DayTiming>>();
+    let mut history = history::History::read(&input_dir).unwrap_or_else(|e| panic!("{e}"));
+    history.record(&timings, chrono::Utc::now().timestamp());
+    history.write(&input_dir).unwrap_or_else(|e| panic!("{e}"));
+    let mut regressions = 0;
+    if let Some(filename) = &args.baseline {
+      let baseline = timing::Baseline::read(filename).expect("Can't read baseline");
+      println!("{} {}", "Comparing to".bold(), filename);
+      for comparison in baseline.compare(&timings, args.threshold) {
+        regressions += comparison.regression as usize;
+        println!("{comparison}");
+      }
+    }
+    if let Some(filename) = &args.save_baseline {
+      // keep the thresholds from the old baseline
+      let mut baseline = timing::Baseline::from_timings(&timings);
+      if let Ok(old) = timing::Baseline::read(filename) {
+        baseline.thresholds = old.thresholds;
+      }
+      baseline.write(filename).expect("Can't write baseline");
+    }
+    if regressions > 0 {
+      eprintln!("{}", format!("{regressions} steps were slower than the baseline").red());
+      std::process::exit(1);
+    }
+    return;
+  }
+
+  let budget = args.budget.or(config.budget_ms).map(time::Duration::from_millis)
+      .unwrap_or(budget::DEFAULT_BUDGET);
+  let _watchdog = args.abort_over_budget.then(|| budget::install(
+      budget::Watchdog::spawn(budget, move |part| {
+        eprintln!("{}", format!("{part} is over its {budget:.2?} budget, stopping").red());
+        std::process::exit(2);
+      })));
+  let bar: Arc<dyn progress::Progress> = Arc::new(Bar::default());
+  let results=
+    days.iter().enumerate()
+        .filter(|(p, _)| day_filter[*p])
+        .map(|(p, solver)| {
+          let result = if let Some(directory) = &args.load_parsed {
+            parsed::run(*solver, directory).unwrap_or_else(|e| panic!("{e}"))
+          } else if args.stream {
+            run_streaming(*solver, &inputs[p].0).unwrap_or_else(|e| panic!("{e}"))
+          } else if args.no_progress {
+            solver.run(&inputs[p].1)
+          } else {
+            solver.run_with_progress(&inputs[p].1, bar.clone())
+          };
+          if text {
+            println!("{result}");
+          }
+          for warning in budget::over_budget(&result, budget) {
+            eprintln!("{}", format!("Warning: {warning}").yellow());
+          }
+          result})
         .collect::<Vec<utils::DayResult>>();
-  let elapsed = results.iter()
-      .map(|r| r.generate_time + r.part1.0 + r.part2.0)
-      .sum::<time::Duration>();
-  println!("{} {}", "Overall runtime".bold(), format!("({:.2?})", elapsed).dimmed());
-
-  let mut old_answers = Answers::read(&args.input);
-  old_answers.update(&results);
-  old_answers.write(&args.input);
+  match args.output {
+    OutputFormat::Text => {
+      let elapsed = results.iter()
+          .map(|r| r.generate_time + r.part1.0 + r.part2.0)
+          .sum::<time::Duration>();
+      println!("{} {}", "Overall runtime".bold(), format!("({:.2?})", elapsed).dimmed());
+    }
+    format => {
+      let paths = inputs.into_iter().zip(day_filter)
+          .filter(|(_, picked)| *picked)
+          .map(|((path, _), _)| path);
+      let report = report::Report::new(results.iter().zip(paths));
+      match format {
+        OutputFormat::Markdown => print!("{}", report.to_markdown()),
+        OutputFormat::Csv => print!("{}", report.to_csv()),
+        _ => println!("{}", report.to_json()),
+      }
+    }
+  }
+
+  let mut old_answers = Answers::read(&input_dir);
+  let mut log = solves::SolveLog::read(&solves_dir).unwrap_or_else(|e| panic!("{e}"));
+  let now = chrono::Utc::now().timestamp();
+  if args.verify {
+    // only the parts that match their recorded answers count as solved
+    for result in &results {
+      log.record_result(result, old_answers.days.get(&result.day), now);
+    }
+    log.write(&solves_dir).unwrap_or_else(|e| panic!("{e}"));
+    for result in results.iter().filter(|r| !old_answers.days.contains_key(&r.day)) {
+      eprintln!("{}", format!("{} doesn't have recorded answers", result.pretty_day()).yellow());
+    }
+    let mismatches = old_answers.verify(&results);
+    for mismatch in &mismatches {
+      eprintln!("{}", mismatch.red());
+    }
+    if !mismatches.is_empty() {
+      eprintln!("{}", format!("{} of {} days didn't match their answers",
+                               mismatches.len(), results.len()).red());
+      std::process::exit(1);
+    }
+    return;
+  }
+  if custom_params {
+    eprintln!("{}", "The answers aren't recorded, since the parameters were changed".yellow());
+    return;
+  }
+  // like --verify, a new answer isn't a solve until a later run matches it
+  for result in &results {
+    log.record_result(result, old_answers.days.get(&result.day), now);
+  }
+  log.write(&solves_dir).unwrap_or_else(|e| panic!("{e}"));
+  for change in old_answers.update(&results) {
+    // keep stdout clean for the structured formats
+    if text {
+      println!("{}", change.bold());
+    } else {
+      eprintln!("{change}");
+    }
+  }
+  old_answers.write(&input_dir);
 }
@@ -0,0 +1,114 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Wraps the system allocator to keep track of the current and peak number
+/// of bytes on the heap. It is the global allocator when the memory feature
+/// is enabled.
+pub struct TrackingAllocator;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+fn grow(bytes: usize) {
+  let current = CURRENT.fetch_add(bytes, Ordering::Relaxed) + bytes;
+  PEAK.fetch_max(current, Ordering::Relaxed);
+}
+
+fn shrink(bytes: usize) {
+  CURRENT.fetch_sub(bytes, Ordering::Relaxed);
+}
+
+unsafe impl GlobalAlloc for TrackingAllocator {
+  unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+    let ptr = System.alloc(layout);
+    if !ptr.is_null() {
+      grow(layout.size());
+    }
+    ptr
+  }
+
+  unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+    System.dealloc(ptr, layout);
+    shrink(layout.size());
+  }
+
+  unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+    let ptr = System.alloc_zeroed(layout);
+    if !ptr.is_null() {
+      grow(layout.size());
+    }
+    ptr
+  }
+
+  unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+    let result = System.realloc(ptr, layout, new_size);
+    if !result.is_null() {
+      if new_size > layout.size() {
+        grow(new_size - layout.size());
+      } else {
+        shrink(layout.size() - new_size);
+      }
+    }
+    result
+  }
+}
+
+/// The most heap that each step of a day used.
+#[derive(Clone,Copy,Debug,Default,Eq,PartialEq)]
+pub struct PeakMemory {
+  pub generator: usize,
+  pub part1: usize,
+  pub part2: usize,
+}
+
+/// Run the function and return its result along with the most bytes that
+/// were on the heap at once beyond what was there when it started. The
+/// allocations of other threads are counted too. Returns None for the bytes
+/// unless the memory feature is enabled.
+pub fn peak_during<T>(func: impl FnOnce() -> T) -> (T, Option<usize>) {
+  if !cfg!(feature = "memory") {
+    return (func(), None);
+  }
+  let base = CURRENT.load(Ordering::Relaxed);
+  PEAK.store(base, Ordering::Relaxed);
+  let result = func();
+  (result, Some(PEAK.load(Ordering::Relaxed).saturating_sub(base)))
+}
+
+/// Format a number of bytes with a binary unit, such as "1.50 MiB".
+pub fn format_bytes(bytes: usize) -> String {
+  const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+  if bytes < 1024 {
+    return format!("{bytes} B");
+  }
+  let mut size = bytes as f64 / 1024.0;
+  let mut unit = 0;
+  while size >= 1024.0 && unit + 1 < UNITS.len() {
+    size /= 1024.0;
+    unit += 1;
+  }
+  format!("{size:.2} {}", UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{format_bytes, peak_during};
+
+  #[test]
+  fn test_format_bytes() {
+    assert_eq!("12 B", format_bytes(12));
+    assert_eq!("1.50 KiB", format_bytes(1536));
+    assert_eq!("3.00 MiB", format_bytes(3 << 20));
+  }
+
+  #[test]
+  fn test_peak() {
+    let (len, peak) = peak_during(|| vec![0u8; 1 << 20].len());
+    assert_eq!(1 << 20, len);
+    if cfg!(feature = "memory") {
+      assert!(peak.unwrap() >= 1 << 20);
+    } else {
+      assert_eq!(None, peak);
+    }
+  }
+}
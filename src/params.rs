@@ -0,0 +1,107 @@
+//! The constants that the puzzles give in their text instead of the input,
+//! so that the runner can change them. The runner installs the parameters
+//! like a progress reporter and the days read the current ones, while each
+//! affected day also has an entry point that takes them directly.
+use std::cell::Cell;
+use crate::y2024::day20;
+
+/// The parameters that each day can change.
+pub const PARAMETERS: &[(&str, &[&str])] = &[
+  ("day11", &["blinks1", "blinks2"]),
+  ("day14", &["width", "height"]),
+  ("day18", &["size", "bytes"]),
+  ("day20", &["cheat_limit", "cheat_length"]),
+];
+
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+pub struct Params {
+  /// The number of blinks in each part of day 11.
+  pub blinks1: usize,
+  pub blinks2: usize,
+  /// The size of the restroom in day 14.
+  pub width: usize,
+  pub height: usize,
+  /// The size of the memory space in day 18 and how many bytes fall before
+  /// part 1. A header in the input wins over these.
+  pub size: usize,
+  pub bytes: usize,
+  /// The picoseconds that a cheat must save and the longest cheat in part 2
+  /// of day 20.
+  pub cheat_limit: usize,
+  pub cheat_length: usize,
+}
+
+impl Default for Params {
+  fn default() -> Self {
+    Params{blinks1: 25, blinks2: 75, width: 101, height: 103, size: 71, bytes: 1024,
+      cheat_limit: day20::DEFAULT_LIMIT, cheat_length: day20::DEFAULT_JUMP}
+  }
+}
+
+impl Params {
+  /// Change one of a day's parameters by name.
+  pub fn set(&mut self, day: &str, name: &str, value: usize) -> Result<(), String> {
+    let field = match (day, name) {
+      ("day11", "blinks1") => &mut self.blinks1,
+      ("day11", "blinks2") => &mut self.blinks2,
+      ("day14", "width") => &mut self.width,
+      ("day14", "height") => &mut self.height,
+      ("day18", "size") => &mut self.size,
+      ("day18", "bytes") => &mut self.bytes,
+      ("day20", "cheat_limit") => &mut self.cheat_limit,
+      ("day20", "cheat_length") => &mut self.cheat_length,
+      _ => return Err(match PARAMETERS.iter().find(|(known, _)| *known == day) {
+        Some((_, names)) => format!("{day} doesn't have a parameter {name}, try one of {names:?}"),
+        None => format!("{day} doesn't have any parameters"),
+      }),
+    };
+    *field = value;
+    Ok(())
+  }
+}
+
+thread_local! {
+  /// The parameters for the days running on this thread.
+  static CURRENT: Cell<Params> = Cell::new(Params::default());
+}
+
+/// Restores the previous parameters when it is dropped.
+pub struct Installed {
+  previous: Params,
+}
+
+impl Drop for Installed {
+  fn drop(&mut self) {
+    CURRENT.set(self.previous);
+  }
+}
+
+/// Use the parameters for the days that run on this thread until the result
+/// is dropped.
+pub fn install(params: Params) -> Installed {
+  Installed { previous: CURRENT.replace(params) }
+}
+
+/// The parameters for the days running on this thread.
+pub fn current() -> Params {
+  CURRENT.get()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{current, install, Params};
+
+  #[test]
+  fn test_install() {
+    let mut params = Params::default();
+    params.set("day20", "cheat_limit", 50).unwrap();
+    assert_eq!(50, params.cheat_limit);
+    assert_eq!("day1 doesn't have any parameters", params.set("day1", "x", 1).unwrap_err());
+    assert!(params.set("day14", "depth", 1).unwrap_err().contains("width"));
+    {
+      let _installed = install(params);
+      assert_eq!(50, current().cheat_limit);
+    }
+    assert_eq!(Params::default(), current());
+  }
+}
@@ -0,0 +1,41 @@
+//! Flamegraphs of each step of a day from pprof's sampling profiler, so that
+//! a slow day can be profiled without setting up a harness for it. Build the
+//! runner with `--features profile` and run it with `--profile <directory>`.
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use pprof::ProfilerGuardBuilder;
+use crate::utils::Solver;
+
+/// How many samples pprof takes each second.
+pub const FREQUENCY: i32 = 1000;
+
+/// Sample the function while running it the given number of times, and
+/// write the flamegraph as SVG.
+fn flamegraph<T>(path: &Path, iterations: usize, func: impl Fn() -> T) -> Result<(), String> {
+  let guard = ProfilerGuardBuilder::default()
+      .frequency(FREQUENCY)
+      .blocklist(&["libc", "libgcc", "pthread", "vdso"])
+      .build().map_err(|e| e.to_string())?;
+  for _ in 0..iterations.max(1) {
+    std::hint::black_box(func());
+  }
+  let report = guard.report().build().map_err(|e| e.to_string())?;
+  let file = File::create(path).map_err(|e| format!("Can't create {}: {e}", path.display()))?;
+  report.flamegraph(file).map_err(|e| format!("Error writing {}: {e}", path.display()))
+}
+
+/// Profile the day's generator and each part separately, writing
+/// dayX-generator.svg, dayX-part1.svg, and dayX-part2.svg to the directory.
+/// Each step is run the given number of times, so that the quick ones get
+/// enough samples.
+pub fn profile_day(solver: &dyn Solver, data: &str, directory: &str,
+                   iterations: usize) -> Result<Vec<PathBuf>, String> {
+  std::fs::create_dir_all(directory).map_err(|e| format!("Can't create {directory}: {e}"))?;
+  let path = |step: &str| Path::new(directory).join(format!("{}-{step}.svg", solver.name()));
+  let input = solver.try_generator(data)?;
+  let steps = [path("generator"), path("part1"), path("part2")];
+  flamegraph(&steps[0], iterations, || solver.generator(data))?;
+  flamegraph(&steps[1], iterations, || solver.part1(&input))?;
+  flamegraph(&steps[2], iterations, || solver.part2(&input))?;
+  Ok(steps.to_vec())
+}
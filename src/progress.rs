@@ -0,0 +1,118 @@
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Receives how far along the slow parts are, so that the runner can show
+/// that they are still making progress.
+pub trait Progress: Send + Sync {
+  /// A step with the given number of units of work is starting.
+  fn start(&self, label: &str, total: usize);
+
+  /// The current step has finished done units of work.
+  fn update(&self, done: usize);
+
+  /// The current step is finished.
+  fn finish(&self);
+}
+
+thread_local! {
+  /// The reporter that the days running on this thread send their progress
+  /// to, if there is one.
+  static REPORTER: RefCell<Option<Arc<dyn Progress>>> = const { RefCell::new(None) };
+}
+
+/// Restores the previous reporter when it is dropped.
+pub struct Installed {
+  previous: Option<Arc<dyn Progress>>,
+}
+
+impl Drop for Installed {
+  fn drop(&mut self) {
+    REPORTER.set(self.previous.take());
+  }
+}
+
+/// Send the progress of the days that run on this thread to the reporter
+/// until the result is dropped.
+pub fn install(reporter: Arc<dyn Progress>) -> Installed {
+  let previous = REPORTER.replace(Some(reporter));
+  Installed { previous }
+}
+
+/// Counts the units of work that a slow step has finished. The counter picks up
+/// the reporter of the thread that creates it, but it is safe to tick from
+/// several threads at once. The reporter only hears about each percent
+/// of the work once.
+pub struct Counter {
+  reporter: Option<Arc<dyn Progress>>,
+  done: AtomicUsize,
+  total: usize,
+}
+
+impl Counter {
+  pub fn new(label: &str, total: usize) -> Self {
+    let reporter = REPORTER.with_borrow(|reporter| reporter.clone());
+    if let Some(reporter) = &reporter {
+      reporter.start(label, total);
+    }
+    Counter { reporter, done: AtomicUsize::new(0), total }
+  }
+
+  /// Record that one more unit of work is done.
+  #[inline]
+  pub fn tick(&self) {
+    if let Some(reporter) = &self.reporter {
+      let done = self.done.fetch_add(1, Ordering::Relaxed) + 1;
+      if done * 100 / self.total.max(1) != (done - 1) * 100 / self.total.max(1) {
+        reporter.update(done);
+      }
+    }
+  }
+}
+
+impl Drop for Counter {
+  fn drop(&mut self) {
+    if let Some(reporter) = &self.reporter {
+      reporter.finish();
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::{Arc, Mutex};
+  use super::{install, Counter, Progress};
+
+  #[derive(Default)]
+  struct Recorder(Mutex<Vec<String>>);
+
+  impl Progress for Recorder {
+    fn start(&self, label: &str, total: usize) {
+      self.0.lock().unwrap().push(format!("{label} {total}"));
+    }
+
+    fn update(&self, done: usize) {
+      self.0.lock().unwrap().push(done.to_string());
+    }
+
+    fn finish(&self) {
+      self.0.lock().unwrap().push("done".to_string());
+    }
+  }
+
+  #[test]
+  fn test_counter() {
+    let recorder = Arc::new(Recorder::default());
+    {
+      let _installed = install(recorder.clone());
+      let counter = Counter::new("test", 200);
+      (0..200).for_each(|_| counter.tick());
+    }
+    // without a reporter, the ticks go nowhere
+    Counter::new("ignored", 10).tick();
+    let events = recorder.0.lock().unwrap();
+    assert_eq!(102, events.len());
+    assert_eq!(["test 200", "2", "4"], events[..3]);
+    assert_eq!(["200", "done"], events[100..]);
+  }
+}
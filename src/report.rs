@@ -0,0 +1,151 @@
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+use serde::Serialize;
+use crate::utils::{Answer, DayResult};
+
+/// The result of one part of a day.
+#[derive(Debug,Serialize)]
+pub struct PartReport {
+  pub part: usize,
+  pub answer: Answer,
+  pub solve_nanos: u64,
+}
+
+/// The results of running one day.
+#[derive(Debug,Serialize)]
+pub struct DayReport {
+  pub day: String,
+  pub input: PathBuf,
+  pub parse_nanos: u64,
+  pub parts: Vec<PartReport>,
+}
+
+/// The structured results of a run, so that other tools don't have to
+/// scrape the pretty output.
+#[derive(Debug,Serialize)]
+pub struct Report {
+  pub version: &'static str,
+  pub days: Vec<DayReport>,
+}
+
+impl Report {
+  /// Build the report from each day's results and the input it read.
+  pub fn new<'a>(results: impl IntoIterator<Item=(&'a DayResult, PathBuf)>) -> Self {
+    let days = results.into_iter()
+        .map(|(result, input)| DayReport {
+          day: result.day.clone(),
+          input,
+          parse_nanos: result.generate_time.as_nanos() as u64,
+          parts: [&result.part1, &result.part2].iter().enumerate()
+              .map(|(i, (time, answer))| PartReport {
+                part: i + 1,
+                answer: answer.clone(),
+                solve_nanos: time.as_nanos() as u64,
+              }).collect(),
+        }).collect();
+    Report { version: env!("CARGO_PKG_VERSION"), days }
+  }
+
+  pub fn to_json(&self) -> String {
+    serde_json::to_string_pretty(self).expect("Report can be serialized")
+  }
+
+  /// Build a Markdown table with a row for each day.
+  pub fn to_markdown(&self) -> String {
+    let mut result = String::new();
+    writeln!(result, "| Day | Part 1 | Part 2 | Parse | Part 1 time | Part 2 time |").unwrap();
+    writeln!(result, "|----:|-------:|-------:|------:|------------:|------------:|").unwrap();
+    for day in &self.days {
+      write!(result, "| {} |", day.day.trim_start_matches("day")).unwrap();
+      for part in &day.parts {
+        write!(result, " {} |", part.answer.to_string().replace('|', "\\|")).unwrap();
+      }
+      write!(result, " {:.2?} |", Duration::from_nanos(day.parse_nanos)).unwrap();
+      for part in &day.parts {
+        write!(result, " {:.2?} |", Duration::from_nanos(part.solve_nanos)).unwrap();
+      }
+      writeln!(result).unwrap();
+    }
+    result
+  }
+
+  /// Build a CSV table with a row for each day and the times in nanoseconds.
+  pub fn to_csv(&self) -> String {
+    let mut result = String::new();
+    writeln!(result, "day,part1,part2,parse_nanos,part1_nanos,part2_nanos").unwrap();
+    for day in &self.days {
+      write!(result, "{}", day.day.trim_start_matches("day")).unwrap();
+      for part in &day.parts {
+        write!(result, ",{}", csv_field(&part.answer.to_string())).unwrap();
+      }
+      write!(result, ",{}", day.parse_nanos).unwrap();
+      for part in &day.parts {
+        write!(result, ",{}", part.solve_nanos).unwrap();
+      }
+      writeln!(result).unwrap();
+    }
+    result
+  }
+}
+
+/// Quote a CSV field if it has a comma, quote, or newline in it.
+fn csv_field(field: &str) -> String {
+  if field.contains([',', '"', '\n']) {
+    format!("\"{}\"", field.replace('"', "\"\""))
+  } else {
+    field.to_string()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::PathBuf;
+  use std::time::Duration;
+  use crate::utils::{Answer, DayResult};
+  use super::Report;
+
+  fn make_report() -> Report {
+    let day1 = DayResult{day: "day1".to_string(), generate_time: Duration::from_micros(5),
+                         part1: (Duration::from_nanos(10), Answer::I64(11)),
+                         part2: (Duration::from_nanos(20), Answer::I64(31)),
+                         peak_memory: None};
+    let day17 = DayResult{day: "day17".to_string(), generate_time: Duration::from_micros(2),
+                          part1: (Duration::from_micros(3), Answer::Str("4,6,3".to_string())),
+                          part2: (Duration::from_millis(4), Answer::U64(117440)),
+                          peak_memory: None};
+    Report::new([(&day1, PathBuf::from("input/day1.txt")),
+                 (&day17, PathBuf::from("input/day17.txt"))])
+  }
+
+  #[test]
+  fn test_json() {
+    let report = make_report();
+    let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+    assert_eq!(env!("CARGO_PKG_VERSION"), json["version"]);
+    let day = &json["days"][0];
+    assert_eq!("day1", day["day"]);
+    assert_eq!("input/day1.txt", day["input"]);
+    assert_eq!(5000, day["parse_nanos"]);
+    assert_eq!(2, day["parts"][1]["part"]);
+    assert_eq!(31, day["parts"][1]["answer"]);
+    assert_eq!("4,6,3", json["days"][1]["parts"][0]["answer"]);
+    assert_eq!(20, day["parts"][1]["solve_nanos"]);
+  }
+
+  #[test]
+  fn test_tables() {
+    let report = make_report();
+    assert_eq!(
+"| Day | Part 1 | Part 2 | Parse | Part 1 time | Part 2 time |
+|----:|-------:|-------:|------:|------------:|------------:|
+| 1 | 11 | 31 | 5.00µs | 10.00ns | 20.00ns |
+| 17 | 4,6,3 | 117440 | 2.00µs | 3.00µs | 4.00ms |
+", report.to_markdown());
+    assert_eq!(
+"day,part1,part2,parse_nanos,part1_nanos,part2_nanos
+1,11,31,5000,10,20
+17,\"4,6,3\",117440,2000,3000,4000000
+", report.to_csv());
+  }
+}
@@ -0,0 +1,171 @@
+//! A log of when each part was first solved, kept in solves.yml in the year's
+//! directory of the input cache, so that it stays out of the checkout. A part counts as solved once the runner produces the
+//! answer that is already in answers.yml, and after that its time is kept.
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, NaiveDate};
+use serde::{Deserialize, Serialize};
+use crate::utils::DayResult;
+
+/// When the day's puzzle was released, which is midnight in US Eastern time,
+/// as seconds since the epoch.
+pub fn unlock_time(year: i32, day: usize) -> Option<i64> {
+  Some(NaiveDate::from_ymd_opt(year, 12, day as u32)?.and_hms_opt(5, 0, 0)?
+      .and_utc().timestamp())
+}
+
+/// Format the seconds as hours, minutes, and seconds.
+pub fn format_delay(seconds: i64) -> String {
+  format!("{}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)
+}
+
+/// The answer of a part and when it was first produced.
+#[derive(Clone,Debug,Deserialize,Eq,PartialEq,Serialize)]
+pub struct Solve {
+  pub answer: String,
+  /// Seconds since the epoch.
+  pub time: i64,
+  /// Did the answer match answers.yml? The older logs recorded any answer,
+  /// so their solves can still be replaced by a verified one.
+  #[serde(default)]
+  pub verified: bool,
+}
+
+/// The solves of each day's parts, keyed by the day's name.
+#[derive(Clone,Debug,Default,Deserialize,Eq,PartialEq,Serialize)]
+pub struct SolveLog {
+  pub days: BTreeMap<String, [Option<Solve>; 2]>,
+}
+
+impl SolveLog {
+  pub fn filename(directory: &Path) -> PathBuf {
+    Path::new(directory).join("solves.yml")
+  }
+
+  /// Read the log from the directory, which is empty if there isn't one.
+  pub fn read(directory: &Path) -> Result<Self, String> {
+    let filename = Self::filename(directory);
+    match fs::read_to_string(&filename) {
+      Ok(text) => serde_yaml::from_str(&text)
+          .map_err(|e| format!("Error in {}: {e}", filename.display())),
+      Err(_) => Ok(Self::default()),
+    }
+  }
+
+  /// Write the log, creating the directory if it doesn't exist yet.
+  pub fn write(&self, directory: &Path) -> Result<(), String> {
+    let filename = Self::filename(directory);
+    fs::create_dir_all(directory)
+        .map_err(|e| format!("Error creating {}: {e}", directory.display()))?;
+    let text = serde_yaml::to_string(self).map_err(|e| e.to_string())?;
+    fs::write(&filename, text).map_err(|e| format!("Error writing {}: {e}", filename.display()))
+  }
+
+  /// Record the verified answer of the part, numbered from 1, at the time
+  /// unless the part already has a verified solve. An unverified solve with
+  /// the same answer keeps its time. Returns whether it was recorded.
+  pub fn record(&mut self, day: &str, part: usize, answer: &str, time: i64) -> bool {
+    let slot = &mut self.days.entry(day.to_string()).or_default()[part - 1];
+    match slot {
+      Some(solve) if solve.verified => false,
+      Some(solve) if solve.answer == answer => {
+        solve.verified = true;
+        true
+      }
+      _ => {
+        *slot = Some(Solve{answer: answer.to_string(), time, verified: true});
+        true
+      }
+    }
+  }
+
+  /// Record the parts of the result that match the recorded answers.
+  pub fn record_result(&mut self, result: &DayResult, expected: Option<&Vec<String>>,
+                       time: i64) {
+    let Some(expected) = expected else {
+      return;
+    };
+    for (part, answer) in result.get_answers().iter().enumerate() {
+      if expected.get(part) == Some(answer) {
+        self.record(&result.day, part + 1, answer, time);
+      }
+    }
+  }
+
+  /// Describe when each part was solved, how long that was after the
+  /// puzzle unlocked, and how long part 2 took after part 1.
+  pub fn report(&self, year: i32) -> String {
+    let mut days: Vec<(usize, &[Option<Solve>; 2])> = self.days.iter()
+        .filter_map(|(name, solves)| Some((name.strip_prefix("day")?.parse().ok()?, solves)))
+        .collect();
+    days.sort_by_key(|(day, _)| *day);
+    let mut result = String::new();
+    for (day, [part1, part2]) in days {
+      let unlock = unlock_time(year, day);
+      let describe = |part: usize, solve: &Option<Solve>| match solve {
+        None => format!("part {part} unsolved"),
+        Some(solve) => {
+          let when = DateTime::from_timestamp(solve.time, 0)
+              .map_or(solve.time.to_string(), |t| t.format("%Y-%m-%d %H:%M:%S").to_string());
+          match unlock.filter(|&unlock| solve.time >= unlock) {
+            Some(unlock) => format!("part {part} at {when} (+{})",
+                                    format_delay(solve.time - unlock)),
+            None => format!("part {part} at {when}"),
+          }
+        }
+      };
+      write!(result, "Day {day}: {}, {}", describe(1, part1), describe(2, part2)).unwrap();
+      if let (Some(first), Some(second)) = (part1, part2) {
+        if second.time >= first.time {
+          write!(result, ", {} between them", format_delay(second.time - first.time)).unwrap();
+        }
+      }
+      result.push('\n');
+    }
+    result
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::env;
+  use super::{format_delay, unlock_time, Solve, SolveLog};
+
+  #[test]
+  fn test_solve_log() {
+    let unlock = unlock_time(2024, 6).unwrap();
+    assert_eq!(Some(1733029200), unlock_time(2024, 1));
+    assert_eq!("1:00:05", format_delay(3605));
+    let mut log = SolveLog::default();
+    assert!(log.record("day6", 1, "41", unlock + 750));
+    // a verified solve is never replaced
+    assert!(!log.record("day6", 1, "41", unlock + 900));
+    assert!(!log.record("day6", 1, "42", unlock + 900));
+    // while the unverified ones from older logs are
+    log.days.get_mut("day6").unwrap()[1] =
+        Some(Solve{answer: "5".to_string(), time: unlock + 1000, verified: false});
+    assert!(log.record("day6", 2, "6", unlock + 2400));
+    assert!(!log.record("day6", 2, "6", unlock + 2500));
+    let mut old: SolveLog = serde_yaml::from_str("days:\n  day6:\n  - answer: '41'\n    \
+                                                  time: 100\n  - null\n").unwrap();
+    assert!(old.record("day6", 1, "41", 200));
+    assert_eq!(Some(Solve{answer: "41".to_string(), time: 100, verified: true}),
+               old.days["day6"][0]);
+    assert!(log.record("day10", 1, "36", unlock - 100));
+    let report = log.report(2024);
+    assert!(report.starts_with("Day 6: part 1 at 2024-12-06 05:12:30 (+0:12:30), \
+                                part 2 at 2024-12-06 05:40:00 (+0:40:00), \
+                                0:27:30 between them\nDay 10:"), "{report}");
+    assert!(report.ends_with("Day 10: part 1 at 2024-12-06 04:58:20, part 2 unsolved\n"),
+            "{report}");
+
+    let root = env::temp_dir().join(format!("aoc2024-solves-{}", std::process::id()));
+    let directory = root.join("2024");
+    assert_eq!(SolveLog::default(), SolveLog::read(&directory).unwrap());
+    log.write(&directory).unwrap();
+    assert_eq!(log, SolveLog::read(&directory).unwrap());
+    std::fs::remove_dir_all(root).unwrap();
+  }
+}
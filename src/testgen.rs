@@ -0,0 +1,46 @@
+//! Random, but valid, inputs for the days. The proptest strategies are only
+//! built for the tests, while the seeded generator is shared with the benches.
+#[cfg(test)]
+mod strategies;
+
+#[cfg(test)]
+pub use strategies::*;
+
+/// A seeded xorshift64 generator, so that the tests and benches that need
+/// large random inputs get the same ones on every run.
+#[derive(Clone,Debug)]
+pub struct XorShift {
+  state: u64,
+}
+
+impl XorShift {
+  /// The seed must not be zero, since xorshift never leaves zero.
+  pub fn new(seed: u64) -> Self {
+    assert_ne!(0, seed, "The seed must not be zero");
+    XorShift{state: seed}
+  }
+
+  pub fn next_u64(&mut self) -> u64 {
+    self.state ^= self.state << 13;
+    self.state ^= self.state >> 7;
+    self.state ^= self.state << 17;
+    self.state
+  }
+
+  /// A number in 0..bound.
+  pub fn below(&mut self, bound: usize) -> usize {
+    (self.next_u64() % bound as u64) as usize
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::XorShift;
+
+  #[test]
+  fn test_xorshift() {
+    let mut rng = XorShift::new(1);
+    assert_eq!([1082269761, 1152992998833853505], [rng.next_u64(), rng.next_u64()]);
+    assert!((0..100).all(|_| rng.below(7) < 7));
+  }
+}
@@ -0,0 +1,124 @@
+//! The proptest strategies, so that the property tests can check the solvers
+//! against each other on more than the puzzle examples.
+use proptest::prelude::*;
+
+/// A maze with a border of walls, random walls inside, and a start 'S' and
+/// end 'E' on two different open cells. The end may not be reachable.
+pub fn maze(max_size: usize, wall_percent: u32) -> impl Strategy<Value=String> {
+  (5..=max_size, 5..=max_size).prop_flat_map(move |(width, height)| {
+    let inside = (width - 2) * (height - 2);
+    (Just((width, height)),
+     prop::collection::vec((0..100u32).prop_map(move |n| n < wall_percent), inside),
+     0..inside, 0..inside - 1)
+  }).prop_map(|((width, height), walls, start, end)| {
+    // skip over the start so that the two are different
+    let end = if end >= start { end + 1 } else { end };
+    let mut rows = vec![vec!['#'; width]; height];
+    for (i, wall) in walls.into_iter().enumerate() {
+      let cell = match i {
+        _ if i == start => 'S',
+        _ if i == end => 'E',
+        _ if wall => '#',
+        _ => '.',
+      };
+      rows[1 + i / (width - 2)][1 + i % (width - 2)] = cell;
+    }
+    rows.iter().map(|row| row.iter().collect::<String>()).collect::<Vec<_>>().join("\n")
+  })
+}
+
+/// A single track of open cells from a start 'S' to an end 'E' with walls
+/// everywhere else, like the day 20 racetracks. The track is a random walk
+/// that never touches itself, even diagonally, so there are no shortcuts
+/// without cheating.
+pub fn track(max_size: usize) -> impl Strategy<Value=String> {
+  (5..=max_size, 5..=max_size).prop_flat_map(|(width, height)| {
+    (Just((width, height)), (1..width - 1, 1..height - 1),
+     prop::collection::vec(any::<u8>(), width * height))
+  }).prop_map(|((width, height), start, choices)| {
+    let inside = |(x, y): (usize, usize)| (1..width - 1).contains(&x) && (1..height - 1).contains(&y);
+    let around = |(x, y): (usize, usize)| [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)];
+    let surrounding = |(x, y): (usize, usize)| [(x - 1, y - 1), (x, y - 1), (x + 1, y - 1),
+        (x - 1, y), (x + 1, y), (x - 1, y + 1), (x, y + 1), (x + 1, y + 1)];
+    let mut rows = vec![vec!['#'; width]; height];
+    let mut path = vec![start];
+    rows[start.1][start.0] = '.';
+    for choice in choices {
+      let current = *path.last().unwrap();
+      // after a turn, the cell before the current one is diagonal to the next
+      let previous = path.len().checked_sub(2).map(|i| path[i]);
+      let options = around(current).into_iter()
+          .filter(|&next| inside(next) && rows[next.1][next.0] == '#' &&
+              surrounding(next).iter().all(|&(x, y)| (x, y) == current ||
+                  Some((x, y)) == previous || rows[y][x] == '#'))
+          .collect::<Vec<_>>();
+      if options.is_empty() {
+        break;
+      }
+      let next = options[choice as usize % options.len()];
+      rows[next.1][next.0] = '.';
+      path.push(next);
+    }
+    let end = *path.last().unwrap();
+    rows[start.1][start.0] = 'S';
+    rows[end.1][end.0] = 'E';
+    rows.iter().map(|row| row.iter().collect::<String>()).collect::<Vec<_>>().join("\n")
+  })
+}
+
+/// A day 9 disk map of alternating file and free space sizes. The files
+/// always have at least one block.
+pub fn disk_map(max_files: usize) -> impl Strategy<Value=String> {
+  prop::collection::vec((1..10u32, 0..10u32), 1..=max_files).prop_map(|pairs| {
+    let mut result: String = pairs.iter()
+        .flat_map(|&(file, free)| [file, free])
+        .map(|digit| char::from_digit(digit, 10).unwrap())
+        .collect();
+    // the disk ends with a file
+    result.pop();
+    result
+  })
+}
+
+/// A day 13 claw machine along with the presses of the A and B buttons that
+/// reach its prize. The buttons move in independent directions, so those
+/// presses are the only way to win.
+pub fn machine() -> impl Strategy<Value=(String, (i64, i64))> {
+  ((1..100i64, 1..100i64), (1..100i64, 1..100i64), 0..=100i64, 0..=100i64)
+      .prop_filter("The buttons must not be parallel",
+                   |((ax, ay), (bx, by), _, _)| ax * by != ay * bx)
+      .prop_map(|((ax, ay), (bx, by), a, b)| {
+        (format!("Button A: X+{ax}, Y+{ay}\nButton B: X+{bx}, Y+{by}\nPrize: X={}, Y={}",
+                 a * ax + b * bx, a * ay + b * by), (a, b))
+      })
+}
+
+/// Day 13 input with several machines and the tokens that winning them all
+/// costs.
+pub fn machines(max_machines: usize) -> impl Strategy<Value=(String, i64)> {
+  prop::collection::vec(machine(), 1..=max_machines).prop_map(|machines| {
+    let tokens = machines.iter().map(|(_, (a, b))| 3 * a + b).sum();
+    let input = machines.into_iter().map(|(text, _)| text).collect::<Vec<_>>().join("\n\n");
+    (input, tokens)
+  })
+}
+
+/// Day 18 input with a header for the size of the memory space and bytes
+/// falling in it, which may include duplicates and the corners.
+pub fn falling_bytes(size: i16, max_bytes: usize) -> impl Strategy<Value=String> {
+  prop::collection::vec((0..size, 0..size), 0..=max_bytes)
+      .prop_map(move |bytes| format!("size={size}\n{}",
+          bytes.iter().map(|(x, y)| format!("{x},{y}\n")).collect::<String>()))
+}
+
+/// Day 19 towels and designs made from a small alphabet, so that many of the
+/// designs can be made in several ways.
+pub fn towels(max_towels: usize, max_designs: usize) -> impl Strategy<Value=String> {
+  let towel = "[wubrg]{1,3}";
+  (prop::collection::btree_set(towel, 1..=max_towels),
+   prop::collection::vec("[wubrg]{1,20}", 1..=max_designs))
+      .prop_map(|(towels, designs)| {
+        format!("{}\n\n{}", towels.into_iter().collect::<Vec<_>>().join(", "),
+                designs.join("\n"))
+      })
+}
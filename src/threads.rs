@@ -0,0 +1,33 @@
+//! The size of the thread pool that the parallel days use, so that they can
+//! be timed at different core counts without setting RAYON_NUM_THREADS. The
+//! parsed inputs aren't Send, so rather than moving the parts into a pool of
+//! their own, the runner sizes the pool that their parallel iterators use.
+use rayon::ThreadPoolBuilder;
+
+/// Size the pool for the rest of the process. It has to happen before
+/// anything runs in parallel, because the pool is started then.
+pub fn configure(threads: usize) -> Result<(), String> {
+  if threads == 0 {
+    return Err("The thread pool needs at least one thread".to_string());
+  }
+  ThreadPoolBuilder::new()
+      .num_threads(threads)
+      .thread_name(|index| format!("aoc-worker-{index}"))
+      .build_global()
+      .map_err(|e| format!("Can't configure the thread pool: {e}"))
+}
+
+/// The number of threads that the parallel days use.
+pub fn current() -> usize {
+  rayon::current_num_threads()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::configure;
+
+  #[test]
+  fn test_configure() {
+    assert!(configure(0).is_err());
+  }
+}
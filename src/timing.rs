@@ -0,0 +1,364 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use crate::utils::Solver;
+
+/// The summary of timing the same step several times.
+#[derive(Clone,Copy,Debug,Default,PartialEq)]
+pub struct Stats {
+  pub min: Duration,
+  pub median: Duration,
+  pub stddev: Duration,
+  pub samples: usize,
+}
+
+impl Stats {
+  pub fn from_samples(samples: &[Duration]) -> Self {
+    if samples.is_empty() {
+      return Stats::default();
+    }
+    let mut sorted = samples.to_vec();
+    sorted.sort_unstable();
+    let middle = sorted.len() / 2;
+    let median = if sorted.len().is_multiple_of(2) {
+      (sorted[middle - 1] + sorted[middle]) / 2
+    } else {
+      sorted[middle]
+    };
+    let seconds = sorted.iter().map(|d| d.as_secs_f64()).collect::<Vec<f64>>();
+    let mean = seconds.iter().sum::<f64>() / seconds.len() as f64;
+    let variance = seconds.iter().map(|s| (s - mean) * (s - mean)).sum::<f64>()
+        / seconds.len() as f64;
+    Stats { min: sorted[0], median, stddev: Duration::from_secs_f64(variance.sqrt()),
+            samples: sorted.len() }
+  }
+}
+
+impl fmt::Display for Stats {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "min {:>10.2?}  median {:>10.2?}  stddev {:>10.2?}",
+           self.min, self.median, self.stddev)
+  }
+}
+
+/// The sample at the percentile of the sorted samples, using the nearest rank.
+fn percentile(sorted: &[Duration], percent: usize) -> Duration {
+  let rank = (sorted.len() * percent).div_ceil(100).max(1);
+  sorted[rank - 1]
+}
+
+/// The summary of repeating the same step many times, after throwing away
+/// the outliers that are more than 1.5 times the interquartile range outside
+/// of the middle half.
+#[derive(Clone,Copy,Debug,Default,PartialEq)]
+pub struct Distribution {
+  pub mean: Duration,
+  pub median: Duration,
+  pub p95: Duration,
+  pub kept: usize,
+  pub outliers: usize,
+}
+
+impl Distribution {
+  pub fn from_samples(samples: &[Duration]) -> Self {
+    if samples.is_empty() {
+      return Distribution::default();
+    }
+    let mut sorted = samples.to_vec();
+    sorted.sort_unstable();
+    let (lower, upper) = (percentile(&sorted, 25), percentile(&sorted, 75));
+    let fence = (upper - lower) * 3 / 2;
+    let kept = sorted.iter().copied()
+        .filter(|&d| d + fence >= lower && d <= upper + fence)
+        .collect::<Vec<Duration>>();
+    let stats = Stats::from_samples(&kept);
+    Distribution { mean: kept.iter().sum::<Duration>() / kept.len() as u32,
+                   median: stats.median, p95: percentile(&kept, 95), kept: kept.len(),
+                   outliers: sorted.len() - kept.len() }
+  }
+}
+
+impl fmt::Display for Distribution {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "mean {:>10.2?}  median {:>10.2?}  p95 {:>10.2?}  {}",
+           self.mean, self.median, self.p95, format!("({} outliers)", self.outliers).dimmed())
+  }
+}
+
+/// How many times to run each step.
+#[derive(Clone,Copy,Debug)]
+pub struct TimingOptions {
+  /// The runs to throw away while the caches and branch predictors warm up.
+  pub warmup: usize,
+  /// The runs that are measured.
+  pub iterations: usize,
+}
+
+impl Default for TimingOptions {
+  fn default() -> Self {
+    TimingOptions { warmup: 3, iterations: 10 }
+  }
+}
+
+/// Run the function and collect how long each of the measured runs took.
+pub fn sample<T>(options: &TimingOptions, func: impl Fn() -> T) -> Vec<Duration> {
+  for _ in 0..options.warmup {
+    std::hint::black_box(func());
+  }
+  (0..options.iterations).map(|_| {
+    let start = Instant::now();
+    std::hint::black_box(func());
+    start.elapsed()
+  }).collect()
+}
+
+/// The timings for each step of a day.
+#[derive(Clone,Debug)]
+pub struct DayTiming {
+  pub day: String,
+  pub generator: Stats,
+  pub part1: Stats,
+  pub part2: Stats,
+}
+
+impl DayTiming {
+  /// Time the day's generator and each part separately.
+  pub fn measure(solver: &dyn Solver, data: &str, options: &TimingOptions) -> Self {
+    let generator = Stats::from_samples(&sample(options, || solver.generator(data)));
+    let input = solver.generator(data);
+    let part1 = Stats::from_samples(&sample(options, || solver.part1(&input)));
+    let part2 = Stats::from_samples(&sample(options, || solver.part2(&input)));
+    DayTiming { day: solver.name().to_string(), generator, part1, part2 }
+  }
+}
+
+impl DayTiming {
+  /// The name and median of each step.
+  fn steps(&self) -> [(&'static str, Duration); 3] {
+    [("generator", self.generator.median), ("part1", self.part1.median),
+     ("part2", self.part2.median)]
+  }
+}
+
+/// The median times from an earlier run to compare against.
+#[derive(Debug,Default,Deserialize,Serialize)]
+pub struct Baseline {
+  /// map from day to step to the median nanoseconds
+  pub days: BTreeMap<String, BTreeMap<String, u64>>,
+  /// the allowed slowdown in percent for a day, such as "day6", or a step,
+  /// such as "day6/part2", when the default is too strict
+  #[serde(default)]
+  pub thresholds: BTreeMap<String, f64>,
+}
+
+/// How a step's time compares to the baseline.
+#[derive(Clone,Debug,PartialEq)]
+pub struct Comparison {
+  pub day: String,
+  pub step: String,
+  pub baseline: Duration,
+  pub current: Duration,
+  /// the change in percent, which is positive when it got slower
+  pub change: f64,
+  pub regression: bool,
+}
+
+impl fmt::Display for Comparison {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let line = format!("{}/{}: {:.2?} -> {:.2?} ({:+.1}%)", self.day, self.step,
+                       self.baseline, self.current, self.change);
+    if self.regression {
+      write!(f, "{}", line.red().bold())
+    } else {
+      write!(f, "{line}")
+    }
+  }
+}
+
+impl Baseline {
+  pub fn from_timings(timings: &[DayTiming]) -> Self {
+    let days = timings.iter()
+        .map(|timing| (timing.day.clone(), timing.steps().iter()
+            .map(|(step, median)| (step.to_string(), median.as_nanos() as u64))
+            .collect()))
+        .collect();
+    Baseline { days, thresholds: BTreeMap::new() }
+  }
+
+  pub fn read(filename: &str) -> Result<Self, String> {
+    let file = File::open(filename).map_err(|e| format!("Error opening {filename}: {e}"))?;
+    serde_yaml::from_reader(file).map_err(|e| format!("Error reading {filename}: {e}"))
+  }
+
+  /// Write the baseline, keeping any thresholds that were set in it.
+  pub fn write(&self, filename: &str) -> Result<(), String> {
+    let file = File::create(filename).map_err(|e| format!("Error creating {filename}: {e}"))?;
+    serde_yaml::to_writer(file, self).map_err(|e| format!("Error writing {filename}: {e}"))
+  }
+
+  /// The allowed slowdown for a step, looking for the step, then the day.
+  fn threshold(&self, day: &str, step: &str, default: f64) -> f64 {
+    self.thresholds.get(&format!("{day}/{step}"))
+        .or_else(|| self.thresholds.get(day))
+        .copied().unwrap_or(default)
+  }
+
+  /// Compare the timings to the baseline for the steps that are in both.
+  pub fn compare(&self, timings: &[DayTiming], default_threshold: f64) -> Vec<Comparison> {
+    let mut result = Vec::new();
+    for timing in timings {
+      let Some(old) = self.days.get(&timing.day) else { continue };
+      for (step, current) in timing.steps() {
+        let Some(&baseline) = old.get(step) else { continue };
+        let baseline = Duration::from_nanos(baseline);
+        let change = if baseline.is_zero() {
+          0.0
+        } else {
+          (current.as_secs_f64() / baseline.as_secs_f64() - 1.0) * 100.0
+        };
+        let regression = change > self.threshold(&timing.day, step, default_threshold);
+        result.push(Comparison { day: timing.day.clone(), step: step.to_string(), baseline,
+                                 current, change, regression });
+      }
+    }
+    result
+  }
+}
+
+/// Every sample of repeating each step of a day many times.
+#[derive(Clone,Debug)]
+pub struct RepeatedDay {
+  pub day: String,
+  pub generator: Vec<Duration>,
+  pub part1: Vec<Duration>,
+  pub part2: Vec<Duration>,
+}
+
+impl RepeatedDay {
+  /// Run the day's generator and each part separately.
+  pub fn measure(solver: &dyn Solver, data: &str, options: &TimingOptions) -> Self {
+    let generator = sample(options, || solver.generator(data));
+    let input = solver.generator(data);
+    let part1 = sample(options, || solver.part1(&input));
+    let part2 = sample(options, || solver.part2(&input));
+    RepeatedDay { day: solver.name().to_string(), generator, part1, part2 }
+  }
+
+  /// The name and samples of each step.
+  fn steps(&self) -> [(&'static str, &[Duration]); 3] {
+    [("generator", &self.generator), ("part1", &self.part1), ("part2", &self.part2)]
+  }
+
+  /// Write each sample as a CSV line of the day, step, and nanoseconds.
+  pub fn write_samples(&self, output: &mut impl Write) -> io::Result<()> {
+    for (step, samples) in self.steps() {
+      for sample in samples {
+        writeln!(output, "{},{step},{}", self.day, sample.as_nanos())?;
+      }
+    }
+    Ok(())
+  }
+}
+
+impl fmt::Display for RepeatedDay {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    writeln!(f, "{} {}", self.day.replace("day", "Day ").bold(),
+             format!("({} runs)", self.part1.len()).dimmed())?;
+    writeln!(f, " · Generator  {}", Distribution::from_samples(&self.generator))?;
+    writeln!(f, " · Part 1     {}", Distribution::from_samples(&self.part1))?;
+    writeln!(f, " · Part 2     {}", Distribution::from_samples(&self.part2))
+  }
+}
+
+impl fmt::Display for DayTiming {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    writeln!(f, "{} {}", self.day.replace("day", "Day ").bold(),
+             format!("({} runs)", self.part1.samples).dimmed())?;
+    writeln!(f, " · Generator  {}", self.generator)?;
+    writeln!(f, " · Part 1     {}", self.part1)?;
+    writeln!(f, " · Part 2     {}", self.part2)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::cell::Cell;
+  use std::time::Duration;
+  use crate::y2024::SOLVERS;
+  use super::{sample, Baseline, DayTiming, Distribution, RepeatedDay, Stats, TimingOptions};
+
+  #[test]
+  fn test_stats() {
+    let samples = [4, 1, 3, 2].map(Duration::from_millis);
+    let stats = Stats::from_samples(&samples);
+    assert_eq!(Duration::from_millis(1), stats.min);
+    assert_eq!(Duration::from_micros(2500), stats.median);
+    assert_eq!(1118, stats.stddev.as_micros());
+    assert_eq!(4, stats.samples);
+    assert_eq!(Duration::from_millis(3), Stats::from_samples(&samples[..3]).median);
+    assert_eq!(Stats::default(), Stats::from_samples(&[]));
+  }
+
+  #[test]
+  fn test_distribution() {
+    let samples = [13, 100, 10, 12, 11].map(Duration::from_millis);
+    let distribution = Distribution::from_samples(&samples);
+    assert_eq!(1, distribution.outliers);
+    assert_eq!(4, distribution.kept);
+    assert_eq!(Duration::from_micros(11500), distribution.mean);
+    assert_eq!(Duration::from_micros(11500), distribution.median);
+    assert_eq!(Duration::from_millis(13), distribution.p95);
+    assert_eq!(Distribution::default(), Distribution::from_samples(&[]));
+    let options = TimingOptions { warmup: 0, iterations: 3 };
+    let repeated = RepeatedDay::measure(SOLVERS[0], "1 2\n3 4", &options);
+    let mut output = Vec::new();
+    repeated.write_samples(&mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert_eq!(9, output.lines().count());
+    assert!(output.lines().all(|line| line.starts_with("day1,")));
+  }
+
+  #[test]
+  fn test_sample() {
+    let calls = Cell::new(0);
+    let options = TimingOptions { warmup: 2, iterations: 5 };
+    assert_eq!(5, sample(&options, || calls.set(calls.get() + 1)).len());
+    assert_eq!(7, calls.get());
+    let timing = DayTiming::measure(SOLVERS[0], "1 2\n3 4", &options);
+    assert_eq!("day1", timing.day);
+    assert_eq!(5, timing.part2.samples);
+  }
+
+  fn make_timing(day: &str, millis: [u64; 3]) -> DayTiming {
+    let [generator, part1, part2] = millis.map(|m|
+        Stats::from_samples(&[Duration::from_millis(m)]));
+    DayTiming { day: day.to_string(), generator, part1, part2 }
+  }
+
+  #[test]
+  fn test_baseline() {
+    let mut baseline = Baseline::from_timings(&[make_timing("day1", [10, 20, 30]),
+                                                make_timing("day2", [10, 10, 10])]);
+    baseline.thresholds.insert("day2".to_string(), 50.0);
+    baseline.thresholds.insert("day2/part2".to_string(), 5.0);
+    let filename = std::env::temp_dir()
+        .join(format!("aoc2024-baseline-{}.yml", std::process::id()));
+    let filename = filename.to_str().unwrap();
+    baseline.write(filename).unwrap();
+    let baseline = Baseline::read(filename).unwrap();
+    std::fs::remove_file(filename).unwrap();
+
+    let current = [make_timing("day1", [10, 25, 15]), make_timing("day2", [14, 10, 11]),
+                   make_timing("day3", [1, 1, 1])];
+    let comparisons = baseline.compare(&current, 10.0);
+    assert_eq!(6, comparisons.len());
+    let regressions = comparisons.iter().filter(|c| c.regression)
+        .map(|c| format!("{}/{} {:.0}", c.day, c.step, c.change)).collect::<Vec<String>>();
+    assert_eq!(vec!["day1/part1 25", "day2/part2 10"], regressions);
+    assert_eq!(-50.0, comparisons[2].change);
+  }
+}
@@ -0,0 +1,93 @@
+//! Plays the visualizations in the terminal with ratatui, using the player
+//! from utils::anim for the frame rate and controls.
+use std::io;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::style::{Color as TermColor, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph};
+use ratatui::DefaultTerminal;
+use crate::utils::anim::{Color, Control, FrameBuffer, Player, Simulation};
+
+fn term_color(color: Color) -> TermColor {
+  match color {
+    Color::Default => TermColor::Reset,
+    Color::Gray => TermColor::DarkGray,
+    Color::Red => TermColor::Red,
+    Color::Green => TermColor::Green,
+    Color::Yellow => TermColor::Yellow,
+    Color::Blue => TermColor::Blue,
+    Color::Magenta => TermColor::Magenta,
+    Color::Cyan => TermColor::Cyan,
+  }
+}
+
+/// Turn the frame into lines of text with a span for each run of cells that
+/// have the same color.
+fn frame_lines(frame: &FrameBuffer) -> Vec<Line<'static>> {
+  frame.cells.rows()
+      .map(|row| Line::from(row.chunk_by(|left, right| left.color == right.color)
+          .map(|run| Span::styled(run.iter().map(|cell| cell.symbol).collect::<String>(),
+                                  Style::default().fg(term_color(run[0].color))))
+          .collect::<Vec<_>>()))
+      .collect()
+}
+
+fn control(event: &Event) -> Option<Control> {
+  match event {
+    Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+      KeyCode::Esc => Some(Control::Quit),
+      KeyCode::Right => Some(Control::Step),
+      KeyCode::Char(ch) => Control::from_key(ch),
+      _ => None,
+    },
+    _ => None,
+  }
+}
+
+fn run(terminal: &mut DefaultTerminal, player: &mut Player<dyn Simulation>) -> io::Result<()> {
+  let title = player.frame.title();
+  while !player.is_done() {
+    let frame = player.frame.render();
+    terminal.draw(|screen| {
+      let block = Block::bordered().title(title.as_str())
+          .title_bottom(format!("{} ({})", frame.status, player.status()));
+      screen.render_widget(Paragraph::new(frame_lines(&frame)).block(block), screen.area());
+    })?;
+    if player.is_waiting() || event::poll(player.delay())? {
+      if let Some(control) = control(&event::read()?) {
+        player.handle(control);
+      }
+    } else {
+      player.tick();
+    }
+  }
+  Ok(())
+}
+
+/// Play the frame in the terminal at the frame rate until the user quits.
+pub fn play(frame: Box<dyn Simulation>, fps: u32) -> io::Result<()> {
+  let mut player = Player::new(frame, fps);
+  let mut terminal = ratatui::init();
+  let result = run(&mut terminal, &mut player);
+  ratatui::restore();
+  result
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::utils::grid::Grid;
+  use crate::utils::anim::{Cell, Color, FrameBuffer};
+  use super::frame_lines;
+
+  #[test]
+  fn test_frame_lines() {
+    let cells = Grid::from_str("..#\n#..", |ch| Ok(match ch {
+      '#' => Cell::new(ch, Color::Gray),
+      _ => Cell::new(ch, Color::Default),
+    })).unwrap();
+    let lines = frame_lines(&FrameBuffer{cells, status: String::new()});
+    assert_eq!(2, lines.len());
+    assert_eq!(vec!["..", "#"], lines[0].spans.iter().map(|span| &span.content).collect::<Vec<_>>());
+    assert_eq!(vec!["#", ".."], lines[1].spans.iter().map(|span| &span.content).collect::<Vec<_>>());
+  }
+}
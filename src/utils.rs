@@ -1,17 +1,40 @@
 use colored::Colorize;
+use std::any::Any;
 use std::cmp::min;
+use std::io::{BufRead, Write};
+use crate::budget;
+use crate::explain;
+use crate::memory::{self, PeakMemory};
+use crate::params::{self, Params};
+use crate::progress::{self, Progress};
 use std::{fmt, fs};
 use std::path::Path;
+use std::sync::Arc;
 use std::time;
+use tracing::info_span;
+
+pub mod anim;
+#[cfg(feature = "arena")]
+pub mod arena;
+pub mod dsu;
+pub mod graph;
+pub mod grid;
+pub mod maze;
+pub mod parse;
+pub mod render;
+pub mod simd;
 
 /// Format the output of each line of the output.
 /// Includes the category, time, and result.
 fn pretty_print(f: &mut fmt::Formatter<'_>, line: &str,
-                duration: time::Duration,
+                duration: time::Duration, memory: Option<usize>,
                 output: Option<&str>) -> fmt::Result {
     const DISPLAY_WIDTH: usize = 40;
 
-    let duration = format!("({:.2?})", duration);
+    let duration = match memory {
+        Some(bytes) => format!("({:.2?}, {})", duration, memory::format_bytes(bytes)),
+        None => format!("({:.2?})", duration),
+    };
     write!(f, "{} {}", line, duration.dimmed())?;
 
     match output {
@@ -62,8 +85,10 @@ pub fn read_inputs(in_dir: &str, days: &[&str], picked: &[bool]) -> Result<Vec<S
 pub struct DayResult {
     pub day: String,
     pub generate_time: time::Duration,
-    pub part1: (time::Duration, String),
-    pub part2: (time::Duration, String),
+    pub part1: (time::Duration, Answer),
+    pub part2: (time::Duration, Answer),
+    /// The peak heap usage of each step, when the memory feature is enabled.
+    pub peak_memory: Option<PeakMemory>,
 }
 
 impl DayResult {
@@ -82,41 +107,442 @@ impl fmt::Display for DayResult {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let duration = format!("({:.2?})", self.generate_time + self.part1.0 + self.part2.0);
         writeln!(f, "{} {}", self.pretty_day().bold(), duration.dimmed())?;
-        pretty_print(f," · Generator", self.generate_time, None)?;
-        pretty_print(f, " · Part 1", self.part1.0, Some(&self.part1.1))?;
-        pretty_print(f, " · Part 2", self.part2.0, Some(&self.part2.1))
+        let memory = self.peak_memory.as_ref();
+        pretty_print(f," · Generator", self.generate_time, memory.map(|m| m.generator), None)?;
+        pretty_print(f, " · Part 1", self.part1.0, memory.map(|m| m.part1),
+                     Some(&self.part1.1.to_string()))?;
+        pretty_print(f, " · Part 2", self.part2.0, memory.map(|m| m.part2),
+                     Some(&self.part2.1.to_string()))
+    }
+}
+
+/// The answer to one part of a day. Most of the days give a number, but a
+/// few give text, such as day 17's output.
+#[derive(Clone,Debug,Eq,PartialEq,serde::Serialize)]
+#[serde(untagged)]
+pub enum Answer {
+  U64(u64),
+  I64(i64),
+  Usize(usize),
+  U128(u128),
+  Str(String),
+}
+
+impl fmt::Display for Answer {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Answer::U64(value) => write!(f, "{value}"),
+      Answer::I64(value) => write!(f, "{value}"),
+      Answer::Usize(value) => write!(f, "{value}"),
+      Answer::U128(value) => write!(f, "{value}"),
+      Answer::Str(value) => write!(f, "{value}"),
     }
+  }
+}
+
+impl From<u64> for Answer {
+  fn from(value: u64) -> Self {
+    Answer::U64(value)
+  }
+}
+
+impl From<i64> for Answer {
+  fn from(value: i64) -> Self {
+    Answer::I64(value)
+  }
+}
+
+impl From<i32> for Answer {
+  fn from(value: i32) -> Self {
+    Answer::I64(value as i64)
+  }
+}
+
+impl From<usize> for Answer {
+  fn from(value: usize) -> Self {
+    Answer::Usize(value)
+  }
+}
+
+impl From<u128> for Answer {
+  fn from(value: u128) -> Self {
+    Answer::U128(value)
+  }
+}
+
+impl From<String> for Answer {
+  fn from(value: String) -> Self {
+    Answer::Str(value)
+  }
+}
+
+/// What a part returns, which is its answer or, for the parts that can't
+/// answer every input that parses, such as day 17's part 2, a Result.
+pub trait PartResult {
+  /// The type of the answer.
+  type Value;
+
+  fn into_answer(self) -> Result<Answer, String>;
+}
+
+impl<T: Into<Answer>> PartResult for T {
+  type Value = T;
+
+  fn into_answer(self) -> Result<Answer, String> {
+    Ok(self.into())
+  }
+}
+
+impl<T: Into<Answer>> PartResult for Result<T, String> {
+  type Value = T;
+
+  fn into_answer(self) -> Result<Answer, String> {
+    self.map(Into::into)
+  }
+}
+
+/// The parsed input for a day, which only that day's solver understands.
+pub type Parsed = Box<dyn Any>;
+
+/// What the registry knows about a day, so that tools can find out which
+/// days are available.
+#[derive(Clone,Debug,Eq,PartialEq,serde::Serialize)]
+pub struct DayInfo {
+  pub year: usize,
+  pub day: usize,
+  pub name: &'static str,
+  pub title: &'static str,
+  /// The type of part 1's answer, such as "usize".
+  pub part1: &'static str,
+  /// The type of part 2's answer, or None if the day doesn't have one.
+  pub part2: Option<&'static str>,
+}
+
+/// Another implementation of one of a day's parts, which --compare runs
+/// alongside the registered one to check that they agree.
+pub struct Algorithm {
+  /// The day's name, such as "day18".
+  pub day: &'static str,
+  pub part: usize,
+  pub name: &'static str,
+  pub solve: fn(&Parsed) -> Result<Answer, String>,
+}
+
+/// The name of the type of a part's answer, without its module path.
+pub fn answer_type<T: ?Sized, R: PartResult>(_part: impl Fn(&T) -> R) -> &'static str {
+  let name = std::any::type_name::<R::Value>();
+  name.rsplit("::").next().unwrap_or(name)
+}
+
+/// A day's solution, so that the runner, benchmarks, and tests can work
+/// through all of the days without naming each module.
+pub trait Solver: Sync {
+  /// The name of the day, such as "day1".
+  fn name(&self) -> &'static str;
+
+  /// The day's number, title, and answer types.
+  fn info(&self) -> DayInfo;
+
+  /// Parse the day's input.
+  fn generator(&self, input: &str) -> Parsed;
+
+  /// Parse the day's input, returning an error instead of panicking when it
+  /// is malformed.
+  fn try_generator(&self, input: &str) -> Result<Parsed, String>;
+
+  /// Parse the day's input from a reader, so that a large input doesn't
+  /// have to be read into a string first.
+  fn try_generator_from_reader(&self, reader: &mut dyn BufRead) -> Result<Parsed, String>;
+
+  /// Solve part 1 from the parsed input, or return why it can't be.
+  fn part1(&self, input: &Parsed) -> Result<Answer, String>;
+
+  /// Solve part 2 from the parsed input, or return why it can't be.
+  fn part2(&self, input: &Parsed) -> Result<Answer, String>;
+
+  /// The other implementations of the day's parts from the year's
+  /// ALGORITHMS.
+  fn algorithms(&self) -> Vec<&'static Algorithm>;
+
+  /// Write the intermediate results that lead to the answers, for the days
+  /// in explain::DAYS.
+  fn explain(&self, input: &Parsed, out: &mut dyn Write) -> Result<(), String> {
+    let info = self.info();
+    explain::for_day(info.year, info.day, input, out)
+  }
+
+  /// Run the whole day, timing each step.
+  fn run(&self, data: &str) -> DayResult {
+    run_steps(self, || Ok(self.generator(data))).unwrap_or_else(|e| panic!("{e}"))
+  }
+
+  /// Run the whole day like run, but return an error for a malformed input
+  /// or a part that can't answer it instead of panicking.
+  fn try_run(&self, data: &str) -> Result<DayResult, String> {
+    run_steps(self, || self.try_generator(data))
+  }
+
+  /// Run the whole day like run, but parse the input from the reader.
+  fn run_from_reader(&self, reader: &mut dyn BufRead) -> Result<DayResult, String> {
+    run_steps(self, || self.try_generator_from_reader(reader))
+  }
+
+  /// Run the whole day like run, but send the progress of the slow parts to
+  /// the reporter.
+  fn run_with_progress(&self, data: &str, reporter: Arc<dyn Progress>) -> DayResult {
+    let _installed = progress::install(reporter);
+    self.run(data)
+  }
+
+  /// Run the whole day like run, but with the given parameters instead of
+  /// the current ones.
+  fn run_with_params(&self, data: &str, params: Params) -> DayResult {
+    let _installed = params::install(params);
+    self.run(data)
+  }
+
+  /// Write the parsed input as JSON, so that it can be inspected or loaded
+  /// again without parsing.
+  #[cfg(feature = "serde")]
+  fn dump(&self, input: &Parsed) -> Result<String, String>;
+
+  /// Load a parsed input that dump wrote.
+  #[cfg(feature = "serde")]
+  fn load(&self, json: &str) -> Result<Parsed, String>;
+
+  /// Run the whole day like run, but load the parsed input from the JSON
+  /// instead of parsing, so the generator time is the time to load it.
+  #[cfg(feature = "serde")]
+  fn run_loaded(&self, json: &str) -> Result<DayResult, String> {
+    run_steps(self, || self.load(json))
+  }
+}
+
+/// Time and trace getting the parsed input and then each part.
+fn run_steps<S: Solver + ?Sized>(solver: &S, parse: impl FnOnce() -> Result<Parsed, String>)
+    -> Result<DayResult, String> {
+  let _day = info_span!("day", name = solver.name()).entered();
+  let ((generate_time, input), generator_memory) = memory::peak_during(|| {
+    let start = time::Instant::now();
+    let input = info_span!("generator").in_scope(parse);
+    (start.elapsed(), input)
+  });
+  let input = input?;
+  let ((part1_time, part1), part1_memory) = memory::peak_during(|| budget::watch(solver.name(),
+      "part 1", || time(&|| info_span!("part1").in_scope(|| solver.part1(&input)))));
+  let part1 = (part1_time, part1?);
+  let ((part2_time, part2), part2_memory) = memory::peak_during(|| budget::watch(solver.name(),
+      "part 2", || time(&|| info_span!("part2").in_scope(|| solver.part2(&input)))));
+  let part2 = (part2_time, part2?);
+  let peak_memory = generator_memory.zip(part1_memory).zip(part2_memory)
+      .map(|((generator, part1), part2)| PeakMemory{generator, part1, part2});
+  Ok(DayResult{day: solver.name().to_string(), generate_time, part1, part2, peak_memory})
+}
+
+/// Get the parsed input back out as the type that the generator returns.
+pub fn downcast<T: 'static>(_generator: impl Fn(&str) -> T, input: &Parsed) -> &T {
+  input.downcast_ref().expect("Parsed input is from a different day")
+}
+
+/// Write the parsed input as JSON.
+#[cfg(feature = "serde")]
+pub fn dump<T: serde::Serialize + 'static>(generator: impl Fn(&str) -> T,
+                                           input: &Parsed) -> Result<String, String> {
+  serde_json::to_string(downcast(generator, input)).map_err(|e| e.to_string())
+}
+
+/// Read the parsed input from JSON as the type that the generator returns.
+#[cfg(feature = "serde")]
+pub fn load<T: serde::de::DeserializeOwned + 'static>(_generator: impl Fn(&str) -> T,
+                                                      json: &str) -> Result<Parsed, String> {
+  Ok(Box::new(serde_json::from_str::<T>(json).map_err(|e| e.to_string())?))
 }
 
 #[macro_export]
 macro_rules! day_list_internal {
-    ( $($day:ident),*) => {
+    ( $year:literal; $($day:ident),*) => {
+        /// The year of the puzzles.
+        pub const YEAR: usize = $year;
+
         // Each day's code should be in src/day?.rs.
         $(pub mod $day;)*
 
-        /// Build a lambda to run each day's code
-        pub const FUNCS : &[&dyn Fn(&str) -> $crate::utils::DayResult] = &[
-            $(&|data| {
-                let (generate_time, input) = $crate::utils::time(&|| $day::generator(data));
-                let part1 = $crate::utils::time(&|| $day::part1(&input));
-                let part2 = $crate::utils::time(&|| $day::part2(&input));
-                $crate::utils::DayResult{day: stringify!($day).to_string(),
-                          generate_time,
-                          part1: (part1.0, part1.1.to_string()),
-                          part2: (part2.0, part2.1.to_string())}},)*
-        ];
+        /// The solver for each day.
+        pub mod solvers {
+          use $crate::utils::{answer_type, downcast, Answer, DayInfo, Parsed, PartResult, Solver};
+          paste::paste!{
+            $(
+              pub struct [<$day:camel>];
+
+              impl Solver for [<$day:camel>] {
+                fn name(&self) -> &'static str {
+                  stringify!($day)
+                }
+
+                fn info(&self) -> DayInfo {
+                  DayInfo{
+                    year: $year,
+                    day: stringify!($day).trim_start_matches("day").parse()
+                        .expect("Bad day name"),
+                    name: stringify!($day),
+                    title: super::$day::TITLE,
+                    part1: answer_type(super::$day::part1),
+                    part2: Some(answer_type(super::$day::part2)),
+                  }
+                }
+
+                fn generator(&self, input: &str) -> Parsed {
+                  Box::new(super::$day::generator(input))
+                }
+
+                fn try_generator(&self, input: &str) -> Result<Parsed, String> {
+                  Ok(Box::new(super::$day::try_generator(input)?))
+                }
+
+                fn try_generator_from_reader(&self, reader: &mut dyn std::io::BufRead)
+                    -> Result<Parsed, String> {
+                  Ok(Box::new(super::$day::generator_from_reader(reader)?))
+                }
+
+                fn algorithms(&self) -> Vec<&'static $crate::utils::Algorithm> {
+                  super::ALGORITHMS.iter()
+                      .filter(|algorithm| algorithm.day == stringify!($day)).collect()
+                }
+
+                fn part1(&self, input: &Parsed) -> Result<Answer, String> {
+                  let input = downcast(super::$day::generator, input);
+                  super::$day::part1(input).into_answer()
+                }
+
+                fn part2(&self, input: &Parsed) -> Result<Answer, String> {
+                  let input = downcast(super::$day::generator, input);
+                  super::$day::part2(input).into_answer()
+                }
+
+                #[cfg(feature = "serde")]
+                fn dump(&self, input: &Parsed) -> Result<String, String> {
+                  $crate::utils::dump(super::$day::generator, input)
+                }
+
+                #[cfg(feature = "serde")]
+                fn load(&self, json: &str) -> Result<Parsed, String> {
+                  $crate::utils::load(super::$day::generator, json)
+                }
+              }
+            )*
+          }
+        }
+
+        /// The registry of the implemented days.
+        pub const SOLVERS: &[&dyn $crate::utils::Solver] =
+            paste::paste!{ &[$(&solvers::[<$day:camel>]),*] };
 
         /// Define the list of implemented day names.
         pub const NAMES: &[&str] = &[$(stringify!($day)),*];
     }
 }
 
+/// Register another implementation of a day's part in the year's
+/// ALGORITHMS, such as algorithm!(day7, 1, "forward", part1_forward).
+#[macro_export]
+macro_rules! algorithm {
+  ( $day:ident, $part:literal, $name:literal, $solve:ident ) => {
+    $crate::utils::Algorithm {
+      day: stringify!($day),
+      part: $part,
+      name: $name,
+      solve: |input| $crate::utils::PartResult::into_answer(
+          $day::$solve($crate::utils::downcast($day::generator, input))),
+    }
+  }
+}
+
 #[macro_export]
 macro_rules! day_list {
-  ( $($day:literal),* ) => {
-    paste::paste!{ $crate::utils::day_list_internal!{$( [<day $day>] ),*} }
+  ( $year:literal => $($day:literal),* ) => {
+    paste::paste!{ $crate::utils::day_list_internal!{$year; $( [<day $day>] ),*} }
   }
 }
 
+pub use algorithm;
 pub use day_list_internal;
-pub use day_list;
\ No newline at end of file
+pub use day_list;
+#[cfg(test)]
+mod tests {
+  use proptest::prelude::*;
+  use crate::{registry, solve};
+  use crate::y2024::{NAMES, SOLVERS};
+  use super::{Answer, DayInfo};
+
+  #[test]
+  fn test_registry() {
+    let names: Vec<&str> = SOLVERS.iter().map(|solver| solver.name()).collect();
+    assert_eq!(NAMES, names.as_slice());
+    let result = SOLVERS[0].run("3   4\n4   3\n2   5\n1   3\n3   9\n3   3");
+    assert_eq!("day1", result.day);
+    assert_eq!(vec!["11", "31"], result.get_answers());
+    let input = SOLVERS[20].generator("029A\n980A\n179A\n456A\n379A");
+    assert_eq!(Ok(Answer::Usize(126384)), SOLVERS[20].part1(&input));
+  }
+
+  #[test]
+  fn test_registry_info() {
+    let days = registry();
+    assert_eq!(NAMES.len(), days.len());
+    assert_eq!(DayInfo{year: 2024, day: 1, name: "day1", title: "Historian Hysteria", part1: "i32",
+      part2: Some("i32")}, days[0]);
+    assert_eq!("String", days[16].part1);
+    assert!(days.iter().enumerate().all(|(i, info)| info.day == i + 1));
+    assert_eq!(Ok(Answer::I64(11)), solve(2024, 1, 1, "3   4\n4   3\n2   5\n1   3\n3   9\n3   3"));
+    assert_eq!(Err("Day 30 of 2024 isn't implemented".to_string()), solve(2024, 30, 1, ""));
+    assert_eq!(Err("Year 2015 isn't implemented".to_string()), solve(2015, 1, 1, ""));
+    assert_eq!(Err("Part 3 doesn't exist".to_string()), solve(2024, 1, 3, "1 2"));
+  }
+
+  #[test]
+  fn test_try_generator() {
+    assert!(SOLVERS[8].try_generator("12x").is_err());
+    assert!(SOLVERS[12].try_generator("Button A: X+1").is_err());
+    assert!(SOLVERS[8].try_generator("12345").is_ok());
+  }
+
+  #[test]
+  fn test_run_from_reader() {
+    let input = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n";
+    let result = SOLVERS[0].run_from_reader(&mut input.as_bytes()).unwrap();
+    assert_eq!("11", result.part1.1.to_string());
+    assert_eq!("31", result.part2.1.to_string());
+    assert!(SOLVERS[12].try_generator_from_reader(&mut "Button A: X+1".as_bytes()).is_err());
+  }
+
+  proptest! {
+    #[test]
+    fn test_generators_dont_panic(input in "[0-9A-Za-z#.@^v<>|,:=+ \n-]{0,200}") {
+      for solver in SOLVERS {
+        let _ = solver.try_generator(&input);
+      }
+    }
+  }
+
+  #[cfg(feature = "serde")]
+  #[test]
+  fn test_dump_and_load() {
+    let input = SOLVERS[1].generator("7 6 4 2 1\n1 2 7 8 9");
+    let json = SOLVERS[1].dump(&input).unwrap();
+    assert_eq!("[[7,6,4,2,1],[1,2,7,8,9]]", json);
+    let result = SOLVERS[1].run_loaded(&json).unwrap();
+    assert_eq!(vec!["1", "1"], result.get_answers());
+    assert!(SOLVERS[1].run_loaded("{}").is_err());
+    // the towels of day 19 rebuild their trie when they are loaded
+    let loaded = SOLVERS[18].load(&SOLVERS[18].dump(
+        &SOLVERS[18].generator("r, wr, b\n\nbrwrr\nubwu")).unwrap()).unwrap();
+    assert_eq!(Ok(Answer::Usize(1)), SOLVERS[18].part1(&loaded));
+  }
+
+  #[test]
+  #[should_panic(expected = "different day")]
+  fn test_wrong_day() {
+    let input = SOLVERS[0].generator("1 2");
+    let _ = SOLVERS[1].part1(&input);
+  }
+}
@@ -0,0 +1,424 @@
+//! The animation engine for the grid days. A day implements Simulation to
+//! take steps and draw its state into a frame buffer, while the Player keeps
+//! the frame rate and the pause and step controls, redraw only sends the
+//! cells that changed to the terminal, and debug_step walks through the
+//! steps from commands.
+use std::fmt::Write as _;
+use std::io::{self, BufRead, Write};
+use std::time::Duration;
+use crate::utils::grid::Grid;
+
+/// The colors that a cell can be drawn in.
+#[derive(Clone,Copy,Debug,Default,Eq,PartialEq)]
+pub enum Color {
+  #[default]
+  Default,
+  Gray,
+  Red,
+  Green,
+  Yellow,
+  Blue,
+  Magenta,
+  Cyan,
+}
+
+impl Color {
+  /// The ANSI escape sequence that switches to the color.
+  fn ansi(self) -> &'static str {
+    match self {
+      Color::Default => "\x1b[0m",
+      Color::Gray => "\x1b[90m",
+      Color::Red => "\x1b[31m",
+      Color::Green => "\x1b[32m",
+      Color::Yellow => "\x1b[33m",
+      Color::Blue => "\x1b[34m",
+      Color::Magenta => "\x1b[35m",
+      Color::Cyan => "\x1b[36m",
+    }
+  }
+}
+
+/// A single character of a frame.
+#[derive(Clone,Copy,Debug,Default,Eq,PartialEq)]
+pub struct Cell {
+  pub symbol: char,
+  pub color: Color,
+}
+
+impl Cell {
+  pub fn new(symbol: char, color: Color) -> Self {
+    Cell{symbol, color}
+  }
+}
+
+/// The cells that the maze days share, so that their grids look the same in
+/// the terminal: walls are gray, paths yellow, and the start and end green
+/// and red.
+pub const WALL: Cell = Cell{symbol: '#', color: Color::Gray};
+pub const FLOOR: Cell = Cell{symbol: '.', color: Color::Default};
+pub const PATH: Cell = Cell{symbol: 'O', color: Color::Yellow};
+pub const START: Cell = Cell{symbol: 'S', color: Color::Green};
+pub const END: Cell = Cell{symbol: 'E', color: Color::Red};
+
+/// Render any grid as text with ANSI colors, mapping each cell to how it
+/// is drawn. The color only changes where it differs from the previous
+/// character, and the text ends back in the default color.
+pub fn colored<T>(grid: &Grid<T>, style: impl Fn(&T) -> Cell) -> String {
+  let mut text = String::with_capacity((grid.width() + 1) * grid.height());
+  let mut color = Color::Default;
+  for row in grid.rows() {
+    for cell in row.iter().map(&style) {
+      if cell.color != color {
+        text.push_str(cell.color.ansi());
+        color = cell.color;
+      }
+      text.push(cell.symbol);
+    }
+    text.push('\n');
+  }
+  if color != Color::Default {
+    text.push_str(Color::Default.ansi());
+  }
+  text
+}
+
+/// One picture of an animation along with a line of status text.
+#[derive(Clone,Debug)]
+pub struct FrameBuffer {
+  pub cells: Grid<Cell>,
+  pub status: String,
+}
+
+impl FrameBuffer {
+  /// The frame as plain text, without the colors.
+  pub fn to_text(&self) -> String {
+    format!("{}\n{}", self.status, self.cells.display(|cell| cell.symbol))
+  }
+
+  /// The cells that are different from the previous frame. Every cell is
+  /// different if the frames aren't the same size.
+  pub fn diff<'a>(&'a self, previous: &'a FrameBuffer)
+      -> impl Iterator<Item=((usize, usize), Cell)> + 'a {
+    let resized = self.cells.width() != previous.cells.width() ||
+        self.cells.height() != previous.cells.height();
+    self.cells.iter()
+        .filter(move |&((x, y), cell)| resized || previous.cells[(x, y)] != *cell)
+        .map(|(position, cell)| (position, *cell))
+  }
+}
+
+/// A day's puzzle being worked one step at a time.
+pub trait Simulation {
+  /// The name to show above the frames.
+  fn title(&self) -> String;
+
+  /// Describe the current state in more detail than the frame's status,
+  /// such as where the day's robot is.
+  fn state(&self) -> String;
+
+  /// Draw the current state.
+  fn render(&self) -> FrameBuffer;
+
+  /// Take the next step, returning false once there are no more steps.
+  fn step(&mut self) -> bool;
+}
+
+/// The keys that control the player.
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+pub enum Control {
+  Quit,
+  Pause,
+  Step,
+  Faster,
+  Slower,
+}
+
+impl Control {
+  /// The control for a key: q to quit, space to pause, n to step, and + or -
+  /// to change the speed.
+  pub fn from_key(key: char) -> Option<Control> {
+    match key {
+      'q' => Some(Control::Quit),
+      ' ' => Some(Control::Pause),
+      'n' => Some(Control::Step),
+      '+' | '=' => Some(Control::Faster),
+      '-' => Some(Control::Slower),
+      _ => None,
+    }
+  }
+}
+
+/// The frame rates that the player allows.
+const FPS_RANGE: std::ops::RangeInclusive<u32> = 1..=240;
+
+/// Runs a frame at a frame rate with pause and single step controls, but
+/// leaves the drawing and key reading to the caller.
+pub struct Player<F: Simulation + ?Sized> {
+  pub frame: Box<F>,
+  fps: u32,
+  paused: bool,
+  finished: bool,
+  quit: bool,
+}
+
+impl<F: Simulation + ?Sized> Player<F> {
+  pub fn new(frame: Box<F>, fps: u32) -> Self {
+    Player{frame, fps: fps.clamp(*FPS_RANGE.start(), *FPS_RANGE.end()), paused: false,
+      finished: false, quit: false}
+  }
+
+  pub fn fps(&self) -> u32 {
+    self.fps
+  }
+
+  /// The time to wait between frames.
+  pub fn delay(&self) -> Duration {
+    Duration::from_secs(1) / self.fps
+  }
+
+  /// Is the player waiting for a key rather than the next frame?
+  pub fn is_waiting(&self) -> bool {
+    self.paused || self.finished
+  }
+
+  pub fn is_done(&self) -> bool {
+    self.quit
+  }
+
+  /// A description of the player's state for the status line.
+  pub fn status(&self) -> String {
+    let state = if self.finished {
+      "done"
+    } else if self.paused {
+      "paused"
+    } else {
+      "playing"
+    };
+    format!("{state} at {} fps, q quit, space pause, n step, +/- speed", self.fps)
+  }
+
+  fn advance(&mut self) {
+    if !self.finished && !self.frame.step() {
+      self.finished = true;
+    }
+  }
+
+  /// Move to the next frame once the delay has passed without a key.
+  pub fn tick(&mut self) {
+    if !self.is_waiting() {
+      self.advance();
+    }
+  }
+
+  pub fn handle(&mut self, control: Control) {
+    match control {
+      Control::Quit => self.quit = true,
+      Control::Pause => self.paused = !self.paused,
+      Control::Step => {
+        self.paused = true;
+        self.advance();
+      }
+      Control::Faster => self.fps = (self.fps * 2).min(*FPS_RANGE.end()),
+      Control::Slower => self.fps = (self.fps / 2).max(*FPS_RANGE.start()),
+    }
+  }
+}
+
+/// Draw the frame on an ANSI terminal, only sending the cells that changed
+/// since the previous frame. The status goes on the line below the cells.
+pub fn redraw(output: &mut impl Write, previous: Option<&FrameBuffer>,
+              next: &FrameBuffer) -> io::Result<()> {
+  let mut text = String::new();
+  let changes: Vec<((usize, usize), Cell)> = match previous {
+    Some(previous) => next.diff(previous).collect(),
+    None => {
+      text.push_str("\x1b[H\x1b[2J");
+      next.cells.iter().map(|(position, cell)| (position, *cell)).collect()
+    }
+  };
+  let mut color = None;
+  let mut cursor = None;
+  for ((x, y), cell) in changes {
+    if cursor != Some((x, y)) {
+      write!(text, "\x1b[{};{}H", y + 1, x + 1).unwrap();
+    }
+    if color != Some(cell.color) {
+      text.push_str(cell.color.ansi());
+      color = Some(cell.color);
+    }
+    text.push(cell.symbol);
+    cursor = Some((x + 1, y));
+  }
+  if previous.is_none_or(|previous| previous.status != next.status) {
+    write!(text, "\x1b[0m\x1b[{};1H\x1b[2K{}", next.cells.height() + 1, next.status).unwrap();
+  }
+  text.push_str("\x1b[0m");
+  output.write_all(text.as_bytes())?;
+  output.flush()
+}
+
+/// Play the frame on an ANSI terminal without any controls, waiting for the
+/// delay between frames.
+pub fn play(frame: &mut dyn Simulation, output: &mut impl Write, fps: u32) -> io::Result<()> {
+  let delay = Duration::from_secs(1) / fps.clamp(*FPS_RANGE.start(), *FPS_RANGE.end());
+  let mut previous = None;
+  loop {
+    let next = frame.render();
+    redraw(output, previous.as_ref(), &next)?;
+    previous = Some(next);
+    if !frame.step() {
+      return writeln!(output);
+    }
+    std::thread::sleep(delay);
+  }
+}
+
+const DEBUG_HELP: &str =
+"Commands:
+  s [n]  take n steps (defaults to 1)
+  c      take steps until the end
+  p      print the frame
+  i      print the state
+  q      quit";
+
+/// Walk through the simulation's steps with commands read from input,
+/// printing the state after each command.
+pub fn debug_step(simulation: &mut dyn Simulation, input: impl BufRead,
+                  mut output: impl Write) -> io::Result<()> {
+  writeln!(output, "{}\n{DEBUG_HELP}", simulation.title())?;
+  let mut steps = 0;
+  for line in input.lines() {
+    let line = line?;
+    let mut words = line.split_whitespace();
+    match (words.next(), words.next()) {
+      (Some("s"), count) => match count.map_or(Ok(1), str::parse::<usize>) {
+        Ok(count) => {
+          let mut done = false;
+          for _ in 0..count {
+            if !simulation.step() {
+              done = true;
+              break;
+            }
+            steps += 1;
+          }
+          let end = if done { ", done" } else { "" };
+          writeln!(output, "Step {steps}{end}: {}", simulation.state())?;
+        }
+        Err(_) => writeln!(output, "Bad count")?,
+      }
+      (Some("c"), _) => {
+        while simulation.step() {
+          steps += 1;
+        }
+        writeln!(output, "Step {steps}, done: {}", simulation.state())?;
+      }
+      (Some("p"), _) => write!(output, "{}", simulation.render().to_text())?,
+      (Some("i"), _) => writeln!(output, "Step {steps}: {}", simulation.state())?,
+      (Some("q"), _) => break,
+      (None, _) => {}
+      _ => writeln!(output, "{DEBUG_HELP}")?,
+    }
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::utils::grid::Grid;
+  use super::{colored, debug_step, redraw, Cell, Color, Control, FrameBuffer, Player, Simulation,
+              FLOOR, START, WALL};
+
+  /// Counts down from a number, drawing it as a row of '*'.
+  struct Countdown(usize);
+
+  impl Simulation for Countdown {
+    fn title(&self) -> String {
+      "Countdown".to_string()
+    }
+
+    fn state(&self) -> String {
+      format!("{} left", self.0)
+    }
+
+    fn render(&self) -> FrameBuffer {
+      let cells = (0..3).map(|i| if i < self.0 {
+        Cell::new('*', Color::Red)
+      } else {
+        Cell::new('.', Color::Default)
+      }).collect();
+      FrameBuffer{cells: Grid::new(3, 1, cells).unwrap(), status: format!("{}", self.0)}
+    }
+
+    fn step(&mut self) -> bool {
+      if self.0 == 0 {
+        return false;
+      }
+      self.0 -= 1;
+      true
+    }
+  }
+
+  #[test]
+  fn test_diff() {
+    let first = Countdown(3).render();
+    let second = Countdown(2).render();
+    assert_eq!(vec![((2, 0), Cell::new('.', Color::Default))],
+               second.diff(&first).collect::<Vec<_>>());
+    assert_eq!(0, first.diff(&first).count());
+    let mut output = Vec::new();
+    redraw(&mut output, Some(&first), &second).unwrap();
+    assert_eq!("\x1b[1;3H\x1b[0m.\x1b[0m\x1b[2;1H\x1b[2K2\x1b[0m",
+               String::from_utf8(output).unwrap());
+  }
+
+  #[test]
+  fn test_debug_step() {
+    let mut output = Vec::new();
+    debug_step(&mut Countdown(3), "s\ni\np\nx\ns 5\nq\ns".as_bytes(), &mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.starts_with("Countdown\nCommands:"));
+    assert!(output.contains("Step 1: 2 left\nStep 1: 2 left\n2\n**.\nCommands:"));
+    assert!(output.ends_with("Step 3, done: 0 left\n"));
+  }
+
+  #[test]
+  fn test_player() {
+    let mut player = Player::new(Box::new(Countdown(3)), 10);
+    assert_eq!(100, player.delay().as_millis());
+    player.tick();
+    assert_eq!("2\n**.\n", player.frame.render().to_text());
+    player.handle(Control::Pause);
+    player.tick();
+    assert!(player.is_waiting());
+    assert_eq!("2", player.frame.render().status);
+    player.handle(Control::Step);
+    assert_eq!("1", player.frame.render().status);
+    player.handle(Control::Pause);
+    player.tick();
+    player.tick();
+    assert!(player.is_waiting());
+    assert!(player.status().starts_with("done at 10 fps"));
+    player.handle(Control::Faster);
+    assert_eq!(20, player.fps());
+    player.handle(Control::Slower);
+    player.handle(Control::Slower);
+    assert_eq!(5, player.fps());
+    assert!(!player.is_done());
+    player.handle(Control::Quit);
+    assert!(player.is_done());
+    assert_eq!(Some(Control::Step), Control::from_key('n'));
+  }
+
+  #[test]
+  fn test_colored() {
+    let grid = Grid::new(4, 2, "##.S#..#".chars().collect()).unwrap();
+    let text = colored(&grid, |&ch| match ch {
+      '#' => WALL,
+      'S' => START,
+      _ => FLOOR,
+    });
+    assert_eq!("\x1b[90m##\x1b[0m.\x1b[32mS\n\x1b[90m#\x1b[0m..\x1b[90m#\n\x1b[0m", text);
+    assert_eq!("ab\n", colored(&Grid::new(2, 1, vec!['a', 'b']).unwrap(),
+                               |&ch| Cell::new(ch, Color::Default)));
+  }
+}
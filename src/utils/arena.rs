@@ -0,0 +1,38 @@
+//! A bump arena for the scratch space of building the days' structures, so
+//! that a day that is parsed many times, such as with --time, reuses the
+//! same memory instead of allocating and freeing it piece by piece.
+use std::cell::RefCell;
+use bumpalo::Bump;
+
+thread_local! {
+  /// The arena for the structures that are built on this thread.
+  static ARENA: RefCell<Bump> = RefCell::new(Bump::new());
+}
+
+/// Call the function with this thread's arena, which is reset afterwards.
+/// A nested call gets a fresh arena of its own.
+pub fn scoped<R>(f: impl FnOnce(&Bump) -> R) -> R {
+  ARENA.with(|arena| match arena.try_borrow_mut() {
+    Ok(mut arena) => {
+      let result = f(&arena);
+      arena.reset();
+      result
+    }
+    Err(_) => f(&Bump::new()),
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::scoped;
+
+  #[test]
+  fn test_scoped() {
+    let total = scoped(|outer| {
+      let numbers = outer.alloc_slice_fill_iter(1..5);
+      let nested = scoped(|inner| inner.alloc_slice_fill_copy(3, 2).iter().sum::<i32>());
+      numbers.iter().sum::<i32>() + nested
+    });
+    assert_eq!(16, total);
+  }
+}
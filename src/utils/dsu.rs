@@ -0,0 +1,150 @@
+use std::ops::DerefMut;
+#[cfg(feature = "arena")]
+use bumpalo::Bump;
+
+/// A disjoint-set forest over the elements 0..len that merges by size and
+/// compresses the paths as it finds the roots. The forest is kept in
+/// vectors, or in slices of an arena with new_in.
+#[derive(Clone,Debug)]
+pub struct DisjointSet<S = Vec<usize>> {
+  parent: S,
+  /// The size of each component, which is only valid for the roots.
+  size: S,
+  components: usize,
+}
+
+impl DisjointSet {
+  /// Create a forest where each element is in a component by itself.
+  pub fn new(len: usize) -> Self {
+    DisjointSet { parent: (0..len).collect(), size: vec![1; len], components: len }
+  }
+}
+
+#[cfg(feature = "arena")]
+impl<'a> DisjointSet<&'a mut [usize]> {
+  /// Create a forest like new, but in the arena.
+  pub fn new_in(len: usize, arena: &'a Bump) -> Self {
+    DisjointSet { parent: arena.alloc_slice_fill_iter(0..len),
+      size: arena.alloc_slice_fill_copy(len, 1), components: len }
+  }
+}
+
+impl<S: DerefMut<Target = [usize]>> DisjointSet<S> {
+  pub fn len(&self) -> usize {
+    self.parent.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.parent.is_empty()
+  }
+
+  /// The number of separate components.
+  pub fn component_count(&self) -> usize {
+    self.components
+  }
+
+  /// Find the root of the element's component.
+  pub fn find(&mut self, element: usize) -> usize {
+    let mut root = element;
+    while self.parent[root] != root {
+      root = self.parent[root];
+    }
+    // point everything along the way directly at the root
+    let mut current = element;
+    while self.parent[current] != root {
+      let next = self.parent[current];
+      self.parent[current] = root;
+      current = next;
+    }
+    root
+  }
+
+  /// Merge the components of the two elements. Returns false if they were
+  /// already in the same component.
+  pub fn union(&mut self, left: usize, right: usize) -> bool {
+    let (mut left, mut right) = (self.find(left), self.find(right));
+    if left == right {
+      return false;
+    }
+    if self.size[left] < self.size[right] {
+      (left, right) = (right, left);
+    }
+    self.parent[right] = left;
+    self.size[left] += self.size[right];
+    self.components -= 1;
+    true
+  }
+
+  /// Are the two elements in the same component?
+  pub fn same(&mut self, left: usize, right: usize) -> bool {
+    self.find(left) == self.find(right)
+  }
+
+  /// The number of elements in the element's component.
+  pub fn size(&mut self, element: usize) -> usize {
+    let root = self.find(element);
+    self.size[root]
+  }
+
+  /// The root of each component.
+  pub fn roots(&self) -> impl Iterator<Item=usize> + '_ {
+    self.parent.iter().enumerate()
+        .filter(|&(element, &parent)| element == parent)
+        .map(|(element, _)| element)
+  }
+
+  /// The elements in the element's component in increasing order.
+  pub fn members(&mut self, element: usize) -> Vec<usize> {
+    let root = self.find(element);
+    (0..self.len()).filter(|&other| self.find(other) == root).collect()
+  }
+
+  /// The elements of each component in increasing order. The components are
+  /// ordered by their smallest element.
+  pub fn components(&mut self) -> Vec<Vec<usize>> {
+    let mut index = vec![usize::MAX; self.len()];
+    let mut result: Vec<Vec<usize>> = Vec::new();
+    for element in 0..self.len() {
+      let root = self.find(element);
+      if index[root] == usize::MAX {
+        index[root] = result.len();
+        result.push(Vec::new());
+      }
+      result[index[root]].push(element);
+    }
+    result
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::DisjointSet;
+
+  #[test]
+  fn test_union() {
+    let mut sets = DisjointSet::new(6);
+    assert!(sets.union(0, 1));
+    assert!(sets.union(4, 1));
+    assert!(sets.union(2, 3));
+    assert!(!sets.union(0, 4));
+    assert!(sets.same(0, 4));
+    assert!(!sets.same(0, 2));
+    assert_eq!(3, sets.size(4));
+    assert_eq!(1, sets.size(5));
+    assert_eq!(3, sets.component_count());
+    assert_eq!(3, sets.roots().count());
+    assert_eq!(vec![0, 1, 4], sets.members(1));
+    assert_eq!(vec![vec![0, 1, 4], vec![2, 3], vec![5]], sets.components());
+  }
+
+  #[cfg(feature = "arena")]
+  #[test]
+  fn test_union_in() {
+    let arena = bumpalo::Bump::new();
+    let mut sets = DisjointSet::new_in(4, &arena);
+    assert!(sets.union(3, 1));
+    assert!(!sets.union(1, 3));
+    assert_eq!(2, sets.size(1));
+    assert_eq!(vec![vec![0], vec![1, 3], vec![2]], sets.components());
+  }
+}
@@ -0,0 +1,320 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+use ahash::{AHashMap, AHashSet};
+
+/// An in-progress Dijkstra search over states of type S with costs of type C.
+/// The states can be anything hashable, such as a node and the direction it
+/// was entered from. The start states all have the default cost of zero.
+pub struct Dijkstra<S, C> {
+  costs: AHashMap<S, C>,
+  previous: AHashMap<S, S>,
+  heap: BinaryHeap<Reverse<(C, S)>>,
+}
+
+impl<S, C> Dijkstra<S, C>
+    where S: Copy + Hash + Ord, C: Copy + Ord + Add<Output=C> + Default {
+  /// Start a search from each of the starts.
+  pub fn new(starts: impl IntoIterator<Item=S>) -> Self {
+    let mut costs = AHashMap::new();
+    let mut heap = BinaryHeap::new();
+    for start in starts {
+      costs.insert(start, C::default());
+      heap.push(Reverse((C::default(), start)));
+    }
+    Dijkstra { costs, previous: AHashMap::new(), heap }
+  }
+
+  /// The lowest cost that is still pending.
+  pub fn peek(&self) -> Option<C> {
+    self.heap.peek().map(|Reverse((cost, _))| *cost)
+  }
+
+  /// Settle the cheapest pending state and relax the edges that neighbors
+  /// returns for it, calling improved for each state that got cheaper.
+  /// Returns the settled state and its cost or None when the search is done.
+  pub fn step<I: IntoIterator<Item=(S, C)>>(&mut self, neighbors: impl FnOnce(S) -> I,
+                                            mut improved: impl FnMut(S, C))
+      -> Option<(S, C)> {
+    let (cost, state) = loop {
+      let Reverse((cost, state)) = self.heap.pop()?;
+      // skip the entries that were superseded by a cheaper route
+      if self.costs.get(&state).is_some_and(|&best| best == cost) {
+        break (cost, state);
+      }
+    };
+    for (next, weight) in neighbors(state) {
+      let next_cost = cost + weight;
+      if self.costs.get(&next).is_none_or(|&best| next_cost < best) {
+        self.costs.insert(next, next_cost);
+        self.previous.insert(next, state);
+        self.heap.push(Reverse((next_cost, next)));
+        improved(next, next_cost);
+      }
+    }
+    Some((state, cost))
+  }
+
+  /// Run the search until a goal state is settled, which is returned along
+  /// with its cost, or until everything reachable has been settled.
+  pub fn run<I: IntoIterator<Item=(S, C)>>(&mut self, mut neighbors: impl FnMut(S) -> I,
+                                           is_goal: impl Fn(&S) -> bool) -> Option<(S, C)> {
+    while let Some(settled) = self.step(&mut neighbors, |_, _| {}) {
+      if is_goal(&settled.0) {
+        return Some(settled);
+      }
+    }
+    None
+  }
+
+  /// The number of states that have been reached.
+  pub fn len(&self) -> usize {
+    self.costs.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.costs.is_empty()
+  }
+
+  /// The cheapest known cost to the state.
+  pub fn cost(&self, state: &S) -> Option<C> {
+    self.costs.get(state).copied()
+  }
+
+  /// The cheapest known cost to each state that was reached.
+  pub fn into_costs(self) -> AHashMap<S, C> {
+    self.costs
+  }
+
+  /// The route from a start to the state, including both ends. The route is
+  /// empty if the state hasn't been reached.
+  pub fn path_to(&self, state: S) -> Vec<S> {
+    if !self.costs.contains_key(&state) {
+      return Vec::new();
+    }
+    trace_path(&self.previous, state)
+  }
+}
+
+/// Follow the previous links back from the state to a start and return the
+/// route in order, including both ends.
+fn trace_path<S: Copy + Hash + Eq>(previous: &AHashMap<S, S>, state: S) -> Vec<S> {
+  let mut route = vec![state];
+  let mut current = state;
+  while let Some(&prev) = previous.get(&current) {
+    route.push(prev);
+    current = prev;
+  }
+  route.reverse();
+  route
+}
+
+/// Find the cheapest cost from any of the starts to each state. The search
+/// stops early once a state matching is_goal is settled, in which case the
+/// costs of the states that weren't settled yet are only upper bounds.
+pub fn dijkstra<S, C, I>(starts: impl IntoIterator<Item=S>, neighbors: impl FnMut(S) -> I,
+                         is_goal: impl Fn(&S) -> bool) -> AHashMap<S, C>
+    where S: Copy + Hash + Ord, C: Copy + Ord + Add<Output=C> + Default,
+          I: IntoIterator<Item=(S, C)> {
+  let mut search = Dijkstra::new(starts);
+  search.run(neighbors, is_goal);
+  search.into_costs()
+}
+
+/// Find the cheapest route from any of the starts to a goal using A*. The
+/// heuristic estimates the remaining cost to a goal and must never overestimate
+/// it. Ties are broken in favor of the states that are closer to a goal.
+/// Returns the cost and the route, including both ends, or None if no goal can
+/// be reached.
+pub fn astar<S, C, I>(starts: impl IntoIterator<Item=S>, mut neighbors: impl FnMut(S) -> I,
+                      heuristic: impl Fn(&S) -> C,
+                      is_goal: impl Fn(&S) -> bool) -> Option<(C, Vec<S>)>
+    where S: Copy + Hash + Ord, C: Copy + Ord + Add<Output=C> + Default,
+          I: IntoIterator<Item=(S, C)> {
+  let mut costs = AHashMap::new();
+  let mut previous = AHashMap::new();
+  let mut heap = BinaryHeap::new();
+  for start in starts {
+    let remaining = heuristic(&start);
+    costs.insert(start, C::default());
+    heap.push(Reverse((remaining, remaining, C::default(), start)));
+  }
+  while let Some(Reverse((_, _, cost, state))) = heap.pop() {
+    // skip the entries that were superseded by a cheaper route
+    if costs.get(&state).is_some_and(|&best| best != cost) {
+      continue;
+    }
+    if is_goal(&state) {
+      return Some((cost, trace_path(&previous, state)));
+    }
+    for (next, weight) in neighbors(state) {
+      let next_cost = cost + weight;
+      if costs.get(&next).is_none_or(|&best| next_cost < best) {
+        costs.insert(next, next_cost);
+        previous.insert(next, state);
+        let remaining = heuristic(&next);
+        heap.push(Reverse((next_cost + remaining, remaining, next_cost, next)));
+      }
+    }
+  }
+  None
+}
+
+/// An in-progress breadth-first search where every edge has the same cost.
+/// The start states are all at distance zero.
+pub struct Bfs<S> {
+  distances: AHashMap<S, usize>,
+  previous: AHashMap<S, S>,
+  pending: VecDeque<S>,
+}
+
+impl<S: Copy + Hash + Eq> Bfs<S> {
+  /// Start a search from each of the starts.
+  pub fn new(starts: impl IntoIterator<Item=S>) -> Self {
+    let mut distances = AHashMap::new();
+    let mut pending = VecDeque::new();
+    for start in starts {
+      if distances.insert(start, 0).is_none() {
+        pending.push_back(start);
+      }
+    }
+    Bfs { distances, previous: AHashMap::new(), pending }
+  }
+
+  /// Visit the closest pending state and queue the neighbors of it that
+  /// haven't been seen yet. Returns the visited state and its distance or None
+  /// when the search is done.
+  pub fn step<I: IntoIterator<Item=S>>(&mut self, neighbors: impl FnOnce(S) -> I)
+      -> Option<(S, usize)> {
+    let state = self.pending.pop_front()?;
+    let distance = self.distances[&state];
+    for next in neighbors(state) {
+      if !self.distances.contains_key(&next) {
+        self.distances.insert(next, distance + 1);
+        self.previous.insert(next, state);
+        self.pending.push_back(next);
+      }
+    }
+    Some((state, distance))
+  }
+
+  /// Run the search until a goal state is visited, which is returned along
+  /// with its distance, or until everything reachable has been visited.
+  pub fn run<I: IntoIterator<Item=S>>(&mut self, mut neighbors: impl FnMut(S) -> I,
+                                      is_goal: impl Fn(&S) -> bool) -> Option<(S, usize)> {
+    while let Some(visited) = self.step(&mut neighbors) {
+      if is_goal(&visited.0) {
+        return Some(visited);
+      }
+    }
+    None
+  }
+
+  /// The fewest steps from a start to the state.
+  pub fn distance(&self, state: &S) -> Option<usize> {
+    self.distances.get(state).copied()
+  }
+
+  /// Has the search found the state?
+  pub fn is_seen(&self, state: &S) -> bool {
+    self.distances.contains_key(state)
+  }
+
+  /// The fewest steps from a start to each state that was found.
+  pub fn into_distances(self) -> AHashMap<S, usize> {
+    self.distances
+  }
+
+  /// The route from a start to the state, including both ends. The route is
+  /// empty if the state hasn't been found.
+  pub fn path_to(&self, state: S) -> Vec<S> {
+    if !self.is_seen(&state) {
+      return Vec::new();
+    }
+    trace_path(&self.previous, state)
+  }
+}
+
+/// Find the fewest steps from any of the starts to each reachable state.
+pub fn bfs<S, I>(starts: impl IntoIterator<Item=S>, neighbors: impl FnMut(S) -> I)
+    -> AHashMap<S, usize>
+    where S: Copy + Hash + Eq, I: IntoIterator<Item=S> {
+  let mut search = Bfs::new(starts);
+  search.run(neighbors, |_| false);
+  search.into_distances()
+}
+
+/// Find the set of states that can be reached from any of the starts.
+pub fn flood_fill<S, I>(starts: impl IntoIterator<Item=S>, neighbors: impl FnMut(S) -> I)
+    -> AHashSet<S>
+    where S: Copy + Hash + Eq, I: IntoIterator<Item=S> {
+  bfs(starts, neighbors).into_keys().collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use itertools::Itertools;
+  use super::{astar, bfs, dijkstra, flood_fill, Bfs, Dijkstra};
+
+  /// A small weighted graph where the direct edge 0 -> 3 is more expensive
+  /// than going around.
+  fn edges(node: usize) -> Vec<(usize, u32)> {
+    match node {
+      0 => vec![(1, 1), (3, 10)],
+      1 => vec![(2, 2)],
+      2 => vec![(3, 3)],
+      _ => vec![],
+    }
+  }
+
+  #[test]
+  fn test_dijkstra() {
+    let costs = dijkstra([0], edges, |_| false);
+    assert_eq!(Some(&6), costs.get(&3));
+    assert_eq!(4, costs.len());
+    // stopping at node 1 leaves node 3 with its first estimate
+    let costs = dijkstra([0], edges, |&n| n == 1);
+    assert_eq!(Some(&3), costs.get(&2));
+    assert_eq!(Some(&10), costs.get(&3));
+  }
+
+  #[test]
+  fn test_states() {
+    // the state is the node and the number of edges taken modulo 2
+    let mut search = Dijkstra::new([(0, false)]);
+    let goal = search.run(|(node, odd)| edges(node).into_iter()
+                              .map(move |(next, cost)| ((next, !odd), cost)),
+                          |&(node, odd)| node == 3 && !odd);
+    assert_eq!(None, goal);
+    assert_eq!(Some(6), search.cost(&(3, true)));
+    assert_eq!(vec![(0, false), (1, true), (2, false), (3, true)],
+               search.path_to((3, true)));
+    assert!(search.path_to((3, false)).is_empty());
+  }
+
+  #[test]
+  fn test_astar() {
+    // the heuristic never overestimates the remaining cost to node 3
+    let heuristic = |&node: &usize| 3 - node.min(3) as u32;
+    assert_eq!(Some((6, vec![0, 1, 2, 3])), astar([0], edges, heuristic, |&n| n == 3));
+    assert_eq!(Some((0, vec![2])), astar([0, 2], edges, heuristic, |&n| n == 2));
+    assert_eq!(None, astar([1], edges, heuristic, |&n| n == 0));
+  }
+
+  #[test]
+  fn test_bfs() {
+    // the direct edge is the fewest steps even though it costs more
+    let next = |node| edges(node).into_iter().map(|(next, _)| next);
+    let distances = bfs([0], next);
+    assert_eq!(Some(&1), distances.get(&3));
+    assert_eq!(Some(&2), distances.get(&2));
+    assert_eq!(vec![1, 2, 3], flood_fill([1], next).into_iter().sorted().collect::<Vec<_>>());
+    let mut search = Bfs::new([0]);
+    assert_eq!(Some((1, 1)), search.run(next, |&n| n == 1));
+    assert!(search.is_seen(&3));
+    assert_eq!(Some(2), search.distance(&2));
+    assert!(search.path_to(4).is_empty());
+    assert_eq!(vec![0, 3], search.path_to(3));
+  }
+}
@@ -0,0 +1,294 @@
+use std::ops::{Add, Index, IndexMut};
+
+/// The type of the coordinates in the days that keep their own positions
+/// rather than grid indexes. The wide-positions feature makes it an i64 for
+/// very large generated inputs.
+#[cfg(not(feature = "wide-positions"))]
+pub type Position = i32;
+#[cfg(feature = "wide-positions")]
+pub type Position = i64;
+
+/// Convert a grid index or size into a position, checking in debug builds
+/// that it fits rather than silently wrapping.
+#[inline]
+pub fn to_position(value: usize) -> Position {
+  debug_assert!(Position::try_from(value).is_ok(), "{value} doesn't fit in a Position");
+  value as Position
+}
+
+/// Convert a position back into a grid index, checking in debug builds that
+/// it isn't negative.
+#[inline]
+pub fn to_index(position: Position) -> usize {
+  debug_assert!(usize::try_from(position).is_ok(), "{position} isn't a valid index");
+  position as usize
+}
+
+/// An offset from a cell to one of its neighbors as (dx, dy).
+pub type Offset = (i8, i8);
+
+const ORTHOGONAL: [Offset; 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+
+const DIAGONAL: [Offset; 4] = [(-1, -1), (1, -1), (-1, 1), (1, 1)];
+
+const SURROUNDING: [Offset; 8] =
+  [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)];
+
+const KNIGHT_MOVES: [Offset; 8] =
+  [(-1, -2), (1, -2), (-2, -1), (2, -1), (-2, 1), (2, 1), (-1, 2), (1, 2)];
+
+/// Which of the cells around a cell count as its neighbors. The offsets of
+/// the named ones are in reading order, so the neighbors come out sorted
+/// by (y, x) and the ones before the cell come first.
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+pub enum Neighborhood {
+  /// The four cells that share a side.
+  Orthogonal,
+  /// The four cells that only share a corner.
+  Diagonal,
+  /// All eight of the cells around it.
+  All,
+  /// Any other offsets, such as a knight's moves.
+  Custom(&'static [Offset]),
+}
+
+impl Neighborhood {
+  pub const KNIGHT: Neighborhood = Neighborhood::Custom(&KNIGHT_MOVES);
+
+  pub fn offsets(self) -> &'static [Offset] {
+    match self {
+      Neighborhood::Orthogonal => &ORTHOGONAL,
+      Neighborhood::Diagonal => &DIAGONAL,
+      Neighborhood::All => &SURROUNDING,
+      Neighborhood::Custom(offsets) => offsets,
+    }
+  }
+
+  /// The coordinates of the neighbors of (x, y), which may be outside of
+  /// whatever they are looked up in.
+  pub fn around<P>(self, x: P, y: P) -> impl Iterator<Item=(P, P)>
+      where P: Copy + Add<Output=P> + From<i8> {
+    self.offsets().iter().map(move |&(dx, dy)| (x + P::from(dx), y + P::from(dy)))
+  }
+}
+
+/// A rectangular grid stored as a flat vector in row-major order. Cells are
+/// addressed as (x, y) with x being the column.
+#[derive(Clone,Debug,Eq,Hash,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
+pub struct Grid<T> {
+  cells: Vec<T>,
+  width: usize,
+  height: usize,
+}
+
+impl<T> Grid<T> {
+  /// Build a grid from the cells in row-major order.
+  pub fn new(width: usize, height: usize, cells: Vec<T>) -> Result<Self, String> {
+    if cells.len() != width * height {
+      return Err(format!("{} cells don't fill a {width}x{height} grid", cells.len()));
+    }
+    Ok(Grid { cells, width, height })
+  }
+
+  pub fn filled(width: usize, height: usize, value: T) -> Self where T: Clone {
+    Grid { cells: vec![value; width * height], width, height }
+  }
+
+  /// Parse a grid with a line per row, mapping each character to a cell.
+  /// All of the rows must be the same length.
+  pub fn from_str(input: &str, mut cell: impl FnMut(char) -> Result<T, String>)
+      -> Result<Self, String> {
+    let mut cells = Vec::new();
+    let mut width = None;
+    let mut height = 0;
+    for (y, line) in input.lines().enumerate() {
+      let before = cells.len();
+      for ch in line.chars() {
+        cells.push(cell(ch)?);
+      }
+      let len = cells.len() - before;
+      if *width.get_or_insert(len) != len {
+        return Err(format!("Row {y} has length {len} instead of {}", width.unwrap()));
+      }
+      height += 1;
+    }
+    Ok(Grid { cells, width: width.unwrap_or(0), height })
+  }
+
+  pub fn width(&self) -> usize {
+    self.width
+  }
+
+  pub fn height(&self) -> usize {
+    self.height
+  }
+
+  /// Find the index into the cells if the coordinate is in bounds. Negative
+  /// or otherwise unrepresentable coordinates are out of bounds.
+  fn offset<C: TryInto<usize>>(&self, x: C, y: C) -> Option<usize> {
+    let (x, y) = (x.try_into().ok()?, y.try_into().ok()?);
+    (x < self.width && y < self.height).then(|| y * self.width + x)
+  }
+
+  pub fn in_bounds<C: TryInto<usize>>(&self, x: C, y: C) -> bool {
+    self.offset(x, y).is_some()
+  }
+
+  pub fn get<C: TryInto<usize>>(&self, x: C, y: C) -> Option<&T> {
+    self.offset(x, y).map(|i| &self.cells[i])
+  }
+
+  pub fn get_mut<C: TryInto<usize>>(&mut self, x: C, y: C) -> Option<&mut T> {
+    self.offset(x, y).map(|i| &mut self.cells[i])
+  }
+
+  /// Iterate through the rows from top to bottom.
+  pub fn rows(&self) -> impl Iterator<Item=&[T]> {
+    self.cells.chunks(self.width.max(1))
+  }
+
+  /// Iterate through the cells in row-major order with their coordinates.
+  pub fn iter(&self) -> impl Iterator<Item=((usize, usize), &T)> {
+    let width = self.width;
+    self.cells.iter().enumerate().map(move |(i, cell)| ((i % width, i / width), cell))
+  }
+
+  /// Find the first cell in row-major order that matches the predicate.
+  pub fn position(&self, predicate: impl Fn(&T) -> bool) -> Option<(usize, usize)> {
+    self.cells.iter().position(predicate).map(|i| (i % self.width, i / self.width))
+  }
+
+  /// Build a new grid of the same size by mapping each cell.
+  pub fn map<U>(&self, func: impl FnMut(&T) -> U) -> Grid<U> {
+    Grid { cells: self.cells.iter().map(func).collect(), width: self.width,
+           height: self.height }
+  }
+
+  /// Build a new grid of the same size by mapping each cell, stopping at
+  /// the first error.
+  pub fn try_map<U, E>(&self, func: impl FnMut(&T) -> Result<U, E>) -> Result<Grid<U>, E> {
+    Ok(Grid { cells: self.cells.iter().map(func).collect::<Result<_, _>>()?,
+              width: self.width, height: self.height })
+  }
+
+  /// The coordinates of the neighbors in the grid in the neighborhood.
+  pub fn neighbors(&self, x: usize, y: usize, neighborhood: Neighborhood)
+      -> impl Iterator<Item=(usize, usize)> + '_ {
+    neighborhood.offsets().iter()
+        .map(move |&(dx, dy)| {
+          (x.wrapping_add_signed(dx.into()), y.wrapping_add_signed(dy.into()))
+        })
+        .filter(|&(x, y)| self.in_bounds(x, y))
+  }
+
+  /// The coordinates of the up to four orthogonal neighbors in the grid.
+  pub fn neighbors4(&self, x: usize, y: usize) -> impl Iterator<Item=(usize, usize)> + '_ {
+    self.neighbors(x, y, Neighborhood::Orthogonal)
+  }
+
+  /// The coordinates of the up to eight neighbors in the grid.
+  pub fn neighbors8(&self, x: usize, y: usize) -> impl Iterator<Item=(usize, usize)> + '_ {
+    self.neighbors(x, y, Neighborhood::All)
+  }
+
+  /// Render the grid with a line per row, mapping each cell to a character.
+  pub fn display(&self, cell: impl Fn(&T) -> char) -> String {
+    let mut result = String::with_capacity((self.width + 1) * self.height);
+    for row in self.rows() {
+      result.extend(row.iter().map(&cell));
+      result.push('\n');
+    }
+    result
+  }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+  type Output = T;
+
+  fn index(&self, (x, y): (usize, usize)) -> &T {
+    self.get(x, y).unwrap_or_else(|| panic!("({x}, {y}) is outside of the grid"))
+  }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+  fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+    self.get_mut(x, y).unwrap_or_else(|| panic!("({x}, {y}) is outside of the grid"))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use itertools::Itertools;
+  use super::{to_index, to_position, Grid, Neighborhood};
+
+  fn parse(input: &str) -> Result<Grid<bool>, String> {
+    Grid::from_str(input, |ch| match ch {
+      '#' => Ok(true),
+      '.' => Ok(false),
+      _ => Err(format!("Bad char {ch}")),
+    })
+  }
+
+  #[test]
+  fn test_parse() {
+    let grid = parse("#..\n.#.").unwrap();
+    assert_eq!((3, 2), (grid.width(), grid.height()));
+    assert_eq!(Some(&true), grid.get(1, 1));
+    assert_eq!(None, grid.get(3, 0));
+    assert_eq!(None, grid.get(-1, 0));
+    assert_eq!(None, grid.get(0i16, 2));
+    assert!(grid[(0, 0)]);
+    assert_eq!(Some((1, 1)), grid.iter().skip(1).find(|(_, &c)| c).map(|(p, _)| p));
+    assert_eq!("#..\n.#.\n", grid.display(|&c| if c { '#' } else { '.' }));
+    assert!(parse("#..\n.#").is_err());
+    assert!(parse("#x.").is_err());
+    assert!(Grid::new(2, 2, vec![1, 2, 3]).is_err());
+    assert_eq!(0, parse("").unwrap().width());
+  }
+
+  #[test]
+  fn test_neighbors() {
+    let mut grid = Grid::filled(3, 3, 0);
+    grid[(1, 2)] = 5;
+    *grid.get_mut(2, 0).unwrap() = 7;
+    assert_eq!(Some((2, 0)), grid.position(|&c| c == 7));
+    assert_eq!(vec![(1, 0), (0, 1), (2, 1), (1, 2)], grid.neighbors4(1, 1).collect_vec());
+    assert_eq!(vec![(1, 0), (0, 1)], grid.neighbors4(0, 0).collect_vec());
+    assert_eq!(8, grid.neighbors8(1, 1).count());
+    assert_eq!(vec![(1, 1), (2, 1), (1, 2)], grid.neighbors8(2, 2).collect_vec());
+    assert_eq!(vec![(0, 0), (2, 0), (0, 2), (2, 2)],
+               grid.neighbors(1, 1, Neighborhood::Diagonal).collect_vec());
+    assert_eq!(vec![(2, 1), (1, 2)], grid.neighbors(0, 0, Neighborhood::KNIGHT).collect_vec());
+    assert_eq!("007\n000\n050\n", grid.map(|c| char::from(b'0' + *c as u8)).display(|&c| c));
+    assert!(grid.try_map(|&c| if c < 6 { Ok(c) } else { Err(c) }).is_err());
+  }
+
+  #[test]
+  fn test_neighborhood() {
+    for neighborhood in [Neighborhood::Orthogonal, Neighborhood::Diagonal, Neighborhood::All,
+                         Neighborhood::KNIGHT] {
+      let around = neighborhood.around(10i32, 20).collect_vec();
+      // in reading order without the cell itself
+      assert!(around.iter().tuple_windows().all(|(a, b)| (a.1, a.0) < (b.1, b.0)), "{around:?}");
+      assert!(!around.contains(&(10, 20)));
+    }
+    assert_eq!(vec![(4, 4), (6, 4), (4, 6), (6, 6)],
+               Neighborhood::Diagonal.around(5i64, 5).collect_vec());
+    assert_eq!(8, Neighborhood::All.offsets().len());
+    assert_eq!(vec![(1, 0)], Neighborhood::Custom(&[(1, 0)]).around(0, 0).collect_vec());
+  }
+
+  #[test]
+  fn test_positions() {
+    assert_eq!(40_000, to_position(40_000));
+    assert_eq!(40_000, to_index(to_position(40_000)));
+  }
+
+  #[test]
+  #[cfg(debug_assertions)]
+  #[should_panic(expected = "isn't a valid index")]
+  fn test_negative_index() {
+    to_index(-1);
+  }
+}
@@ -0,0 +1,229 @@
+//! A maze of walls and open tiles with starts and ends, which days 16, 18,
+//! and 20 share. It parses the map, answers which tiles are open, and finds
+//! the distances from a tile and the shortest path between two of them.
+use std::str::FromStr;
+use array2d::Array2D;
+use smallvec::SmallVec;
+use crate::utils::anim::{Cell, END, FLOOR, START, WALL};
+use crate::utils::graph::{bfs, Bfs};
+use crate::utils::grid::{to_index, to_position, Grid, Neighborhood, Position};
+
+/// A tile of the maze, where x is the column.
+#[derive(Clone,Copy,Debug,Eq,Hash,Ord,PartialEq,PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
+pub struct Point {
+  pub y: Position,
+  pub x: Position,
+}
+
+impl Point {
+  pub fn new(y: usize, x: usize) -> Point {
+    Point{y: to_position(y), x: to_position(x)}
+  }
+
+  pub fn x(&self) -> Position {
+    self.x
+  }
+
+  pub fn y(&self) -> Position {
+    self.y
+  }
+
+  /// The number of steps between the points when there aren't any walls.
+  pub fn manhattan(&self, other: &Point) -> usize {
+    self.x.abs_diff(other.x) as usize + self.y.abs_diff(other.y) as usize
+  }
+}
+
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
+pub enum Tile {
+  Empty, Wall, Start, End,
+}
+
+impl Tile {
+  #[inline]
+  pub fn is_open(self) -> bool {
+    self != Tile::Wall
+  }
+}
+
+pub type NeighborList = SmallVec<[Point; 4]>;
+
+#[derive(Clone,Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
+pub struct Maze {
+  tiles: Grid<Tile>,
+  starts: Vec<Point>,
+  ends: Vec<Point>,
+}
+
+impl FromStr for Maze {
+  type Err = String;
+
+  /// Parse a map of '#' for the walls and '.' for the open tiles, with at
+  /// least one 'S' for a start and one 'E' for an end.
+  fn from_str(input: &str) -> Result<Self, String> {
+    let tiles = Grid::from_str(input, |ch| match ch {
+      '#' => Ok(Tile::Wall),
+      '.' => Ok(Tile::Empty),
+      'S' => Ok(Tile::Start),
+      'E' => Ok(Tile::End),
+      _ => Err(format!("Invalid character '{}'", ch))})?;
+    let find = |kind| tiles.iter()
+        .filter(|(_, &tile)| tile == kind)
+        .map(|((x, y), _)| Point::new(y, x))
+        .collect::<Vec<Point>>();
+    let starts = find(Tile::Start);
+    let ends = find(Tile::End);
+    if starts.is_empty() {
+      return Err("Can't find start".to_string());
+    }
+    if ends.is_empty() {
+      return Err("Can't find end".to_string());
+    }
+    Ok(Maze{tiles, starts, ends})
+  }
+}
+
+impl Maze {
+  /// An open maze of the size with a start and an end, where the walls are
+  /// added later.
+  pub fn open(width: usize, height: usize, start: Point, end: Point) -> Self {
+    let mut tiles = Grid::filled(width, height, Tile::Empty);
+    tiles[(to_index(start.x), to_index(start.y))] = Tile::Start;
+    tiles[(to_index(end.x), to_index(end.y))] = Tile::End;
+    Maze{tiles, starts: vec![start], ends: vec![end]}
+  }
+
+  /// Put a wall on the tile, even if it is a start or an end.
+  pub fn set_wall(&mut self, point: Point) {
+    self.tiles[(to_index(point.x), to_index(point.y))] = Tile::Wall;
+  }
+
+  pub fn width(&self) -> usize {
+    self.tiles.width()
+  }
+
+  pub fn height(&self) -> usize {
+    self.tiles.height()
+  }
+
+  pub fn tiles(&self) -> &Grid<Tile> {
+    &self.tiles
+  }
+
+  /// The tile at the point, where everything outside of the maze is a wall.
+  #[inline]
+  pub fn get(&self, point: Point) -> Tile {
+    self.tiles.get(point.x, point.y).copied().unwrap_or(Tile::Wall)
+  }
+
+  #[inline]
+  pub fn is_open(&self, point: Point) -> bool {
+    self.get(point).is_open()
+  }
+
+  /// The first start, for the mazes that only have one.
+  pub fn start(&self) -> Point {
+    self.starts[0]
+  }
+
+  pub fn starts(&self) -> &[Point] {
+    &self.starts
+  }
+
+  /// The first end, for the mazes that only have one.
+  pub fn end(&self) -> Point {
+    self.ends[0]
+  }
+
+  pub fn ends(&self) -> &[Point] {
+    &self.ends
+  }
+
+  /// The open tiles next to the point.
+  pub fn neighbors(&self, point: Point) -> NeighborList {
+    Neighborhood::Orthogonal.around(point.x, point.y)
+        .map(|(x, y)| Point{y, x})
+        .filter(|&next| self.is_open(next))
+        .collect()
+  }
+
+  /// Find the distance from the source to each tile with a BFS, indexed by
+  /// (y, x). Walls and unreachable tiles are usize::MAX.
+  pub fn distances_from(&self, source: Point) -> Array2D<usize> {
+    let mut result = Array2D::filled_with(usize::MAX, self.height(), self.width());
+    for (point, distance) in bfs([source], |point| self.neighbors(point)) {
+      result[(to_index(point.y), to_index(point.x))] = distance;
+    }
+    result
+  }
+
+  /// One of the shortest paths from the source to the destination,
+  /// including both ends. It is empty if the destination can't be reached.
+  pub fn shortest_path(&self, source: Point, destination: Point) -> Vec<Point> {
+    if !self.is_open(source) {
+      return Vec::new();
+    }
+    let mut search = Bfs::new([source]);
+    search.run(|point| self.neighbors(point), |&point| point == destination);
+    search.path_to(destination)
+  }
+
+  /// The map with the same characters that it was parsed from.
+  pub fn to_chars(&self) -> Vec<Vec<char>> {
+    self.tiles.rows()
+        .map(|row| row.iter().map(|tile| match tile {
+          Tile::Wall => '#',
+          Tile::Empty => '.',
+          Tile::Start => 'S',
+          Tile::End => 'E',
+        }).collect())
+        .collect()
+  }
+
+  /// The colored cells for drawing the maze in the terminal.
+  pub fn cells(&self) -> Grid<Cell> {
+    self.tiles.map(|tile| match tile {
+      Tile::Wall => WALL,
+      Tile::Empty => FLOOR,
+      Tile::Start => START,
+      Tile::End => END,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::str::FromStr;
+  use super::{Maze, Point, Tile};
+
+  #[test]
+  fn test_maze() {
+    let maze = Maze::from_str("#####\n#S#E#\n#...#\n#####").unwrap();
+    assert_eq!((5, 4), (maze.width(), maze.height()));
+    assert_eq!((Point::new(1, 1), Point::new(1, 3)), (maze.start(), maze.end()));
+    assert_eq!(Tile::Wall, maze.get(Point{y: -1, x: 2}));
+    assert_eq!(vec![Point::new(2, 1)], maze.neighbors(maze.start()).to_vec());
+    let distances = maze.distances_from(maze.start());
+    assert_eq!(4, distances[(1, 3)]);
+    assert_eq!(usize::MAX, distances[(1, 2)]);
+    let path = maze.shortest_path(maze.start(), maze.end());
+    assert_eq!(5, path.len());
+    assert_eq!(vec!["#####", "#S#E#", "#...#", "#####"],
+               maze.to_chars().iter().map(|row| row.iter().collect::<String>())
+                   .collect::<Vec<_>>());
+    assert!(Maze::from_str("#.E#").is_err());
+    assert!(Maze::from_str("#S.#").is_err());
+    assert!(Maze::from_str("#SxE#").is_err());
+
+    let mut maze = Maze::open(3, 3, Point::new(0, 0), Point::new(2, 2));
+    assert_eq!(5, maze.shortest_path(maze.start(), maze.end()).len());
+    maze.set_wall(Point::new(1, 1));
+    maze.set_wall(Point::new(0, 1));
+    maze.set_wall(Point::new(1, 0));
+    assert!(maze.shortest_path(maze.start(), maze.end()).is_empty());
+    assert_eq!(4, maze.start().manhattan(&maze.end()));
+  }
+}
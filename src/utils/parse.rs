@@ -0,0 +1,148 @@
+//! The small pieces of parsing that many of the days need, such as reading
+//! a number, splitting a pair, and reading labeled fields like "X+94" or
+//! "Register A: 729", along with reading the input from a BufRead. The
+//! errors convert into the String errors that the generators return.
+use std::fmt;
+use std::io::BufRead;
+use std::str::FromStr;
+
+/// What was wrong with a piece of the input.
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub enum ParseError {
+  /// The text isn't a number of the wanted type.
+  BadInt(String),
+  /// The text doesn't have the separator in it.
+  MissingSeparator { separator: String, text: String },
+  /// The field doesn't start with the label.
+  WrongLabel { label: String, text: String },
+  /// The input doesn't have the number of sections between blank lines
+  /// that the day needs.
+  Sections { expected: usize, found: usize },
+  /// The input couldn't be read.
+  Read(String),
+}
+
+impl fmt::Display for ParseError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      ParseError::BadInt(text) => write!(f, "Can't parse integer - '{text}'"),
+      ParseError::MissingSeparator { separator, text } =>
+        write!(f, "Can't find '{separator}' in '{text}'"),
+      ParseError::WrongLabel { label, text } => write!(f, "Expected {label} in '{text}'"),
+      ParseError::Sections { expected, found } =>
+        write!(f, "Expected {expected} sections separated by blank lines, found {found}"),
+      ParseError::Read(error) => write!(f, "Can't read input: {error}"),
+    }
+  }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<ParseError> for String {
+  fn from(error: ParseError) -> Self {
+    error.to_string()
+  }
+}
+
+/// Parse a number of any type.
+pub fn parse_int<T: FromStr>(s: &str) -> Result<T, ParseError> {
+  s.parse().map_err(|_| ParseError::BadInt(s.to_string()))
+}
+
+/// Split the text around the first separator.
+pub fn split_pair<'a>(s: &'a str, separator: &str) -> Result<(&'a str, &'a str), ParseError> {
+  s.split_once(separator).ok_or_else(|| ParseError::MissingSeparator {
+    separator: separator.to_string(), text: s.to_string() })
+}
+
+/// Parse two numbers around the separator, such as "3,4".
+pub fn int_pair<T: FromStr>(s: &str, separator: &str) -> Result<(T, T), ParseError> {
+  let (left, right) = split_pair(s, separator)?;
+  Ok((parse_int(left)?, parse_int(right)?))
+}
+
+/// Parse the value of a labeled field, such as "X+94", "Y=5400", or
+/// "Register A: 729", after checking the label.
+pub fn labeled<T: FromStr>(s: &str, label: &str) -> Result<T, ParseError> {
+  let s = s.trim();
+  s.strip_prefix(label)
+      .and_then(|rest| rest.strip_prefix(['+', '=', ':']))
+      .ok_or_else(|| ParseError::WrongLabel { label: label.to_string(), text: s.to_string() })
+      .and_then(|value| parse_int(value.trim()))
+}
+
+/// Split the input into the sections between its blank lines.
+pub fn sections(input: &str) -> impl Iterator<Item=&str> {
+  input.trim_end_matches('\n').split("\n\n")
+}
+
+/// Split an input that has two sections between a blank line, such as a
+/// grid and the moves on it.
+pub fn two_sections(input: &str) -> Result<(&str, &str), ParseError> {
+  input.split_once("\n\n")
+      .ok_or(ParseError::Sections { expected: 2, found: 1 })
+}
+
+/// Read the whole input, for the days that need all of it before they can
+/// parse any of it, such as the grids.
+pub fn read_all(mut reader: impl BufRead) -> Result<String, ParseError> {
+  let mut result = String::new();
+  reader.read_to_string(&mut result).map_err(|e| ParseError::Read(e.to_string()))?;
+  Ok(result)
+}
+
+/// Parse the whole input from the reader with the day's string generator.
+pub fn from_reader<T>(reader: impl BufRead,
+                      try_generator: impl FnOnce(&str) -> Result<T, String>) -> Result<T, String> {
+  try_generator(&read_all(reader)?)
+}
+
+/// The lines of the reader, for the days that parse their input a line at a
+/// time but need more than one kind of line, such as the ones with sections.
+pub fn read_lines(reader: impl BufRead) -> impl Iterator<Item=Result<String, String>> {
+  reader.lines().map(|line| line.map_err(|e| ParseError::Read(e.to_string()).into()))
+}
+
+/// Parse the input a line at a time, for the days with a record on each
+/// line, so that the whole input is never in memory.
+pub fn parse_lines<T, C: FromIterator<T>>(reader: impl BufRead,
+                                          mut parse_line: impl FnMut(&str) -> Result<T, String>)
+    -> Result<C, String> {
+  read_lines(reader).map(|line| parse_line(&line?)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{from_reader, int_pair, labeled, parse_int, parse_lines, read_lines, sections,
+              split_pair, two_sections, ParseError};
+
+  #[test]
+  fn test_parse() {
+    assert_eq!(Ok(-12), parse_int::<i16>("-12"));
+    assert_eq!(Err(ParseError::BadInt("300".to_string())), parse_int::<u8>("300"));
+    assert_eq!("Can't parse integer - 'x'", String::from(parse_int::<u64>("x").unwrap_err()));
+    assert_eq!(Ok(("p", "0,4")), split_pair("p=0,4", "="));
+    assert_eq!("Can't find ',' in '12'", split_pair("12", ",").unwrap_err().to_string());
+    assert_eq!(Ok((3, 4)), int_pair::<i32>("3,4", ","));
+    assert_eq!(Ok(94), labeled::<i64>(" X+94", "X"));
+    assert_eq!(Ok(-5400), labeled::<i64>("Y=-5400", "Y"));
+    assert_eq!(Ok(729), labeled::<u64>("Register A: 729", "Register A"));
+    assert!(labeled::<i64>("Y+3", "X").is_err());
+    assert!(labeled::<i64>("X3", "X").is_err());
+    assert_eq!(vec!["a\nb", "c"], sections("a\nb\n\nc\n").collect::<Vec<_>>());
+    assert_eq!(Ok(("a", "b\n")), two_sections("a\n\nb\n"));
+    assert!(two_sections("a\nb").is_err());
+  }
+
+  #[test]
+  fn test_readers() {
+    let numbers: Vec<u32> = parse_lines("1\n2\n3\n".as_bytes(), |line| Ok(parse_int(line)?))
+        .unwrap();
+    assert_eq!(vec![1, 2, 3], numbers);
+    assert!(parse_lines::<u32, Vec<u32>>("1\nx".as_bytes(), |line| Ok(parse_int(line)?))
+        .is_err());
+    assert_eq!(Ok(4), from_reader("a\nbc".as_bytes(), |input| Ok(input.len())));
+    assert_eq!(vec![Ok("a".to_string()), Ok(String::new())],
+               read_lines("a\n\n".as_bytes()).collect::<Vec<_>>());
+  }
+}
@@ -0,0 +1,61 @@
+//! Pictures of the days' grids that can be saved and looked at later.
+pub mod gif;
+pub mod png;
+pub mod svg;
+
+use crate::utils::anim::{Cell, Color};
+
+/// The red, green, and blue of each color that a raster picture uses, in the
+/// order of palette_index.
+pub type Palette = [[u8; 3]; 9];
+
+/// The colors on the dark background of the Advent of Code site.
+pub const PALETTE: Palette = [
+  [0x0f, 0x0f, 0x23],
+  [0xcc, 0xcc, 0xcc],
+  [0x66, 0x66, 0x66],
+  [0xff, 0x40, 0x40],
+  [0x00, 0xcc, 0x00],
+  [0xff, 0xff, 0x66],
+  [0x40, 0x80, 0xff],
+  [0xff, 0x40, 0xff],
+  [0x40, 0xe0, 0xe0],
+];
+
+/// Dark colors on white for printing.
+pub const LIGHT_PALETTE: Palette = [
+  [0xff, 0xff, 0xff],
+  [0x33, 0x33, 0x33],
+  [0x99, 0x99, 0x99],
+  [0xd0, 0x00, 0x00],
+  [0x00, 0x99, 0x00],
+  [0xc8, 0xa0, 0x00],
+  [0x00, 0x50, 0xd0],
+  [0xb0, 0x00, 0xb0],
+  [0x00, 0x90, 0x90],
+];
+
+/// Find a palette by its name, dark or light.
+pub fn palette_by_name(name: &str) -> Result<Palette, String> {
+  match name {
+    "dark" => Ok(PALETTE),
+    "light" => Ok(LIGHT_PALETTE),
+    _ => Err(format!("Unknown palette '{name}', expected dark or light")),
+  }
+}
+
+/// The palette index for a cell. Empty cells, which are blanks or dots
+/// without a color, are the background.
+pub fn palette_index(cell: &Cell) -> u8 {
+  match cell.color {
+    Color::Default if matches!(cell.symbol, ' ' | '.') => 0,
+    Color::Default => 1,
+    Color::Gray => 2,
+    Color::Red => 3,
+    Color::Green => 4,
+    Color::Yellow => 5,
+    Color::Blue => 6,
+    Color::Magenta => 7,
+    Color::Cyan => 8,
+  }
+}
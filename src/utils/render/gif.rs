@@ -0,0 +1,163 @@
+//! Record the frames of a visualization as an animated GIF. Each cell is a
+//! square of pixels in its color from the palette, and after the first
+//! picture only the rectangle of cells that changed is written. The status
+//! line isn't drawn.
+use std::borrow::Cow;
+use std::io::Write;
+use ::gif::{DisposalMethod, Encoder, Repeat};
+use crate::utils::anim::{Cell, FrameBuffer, Simulation};
+use crate::utils::grid::Grid;
+use crate::utils::render::{palette_index, Palette, PALETTE};
+
+/// How the frames are recorded.
+#[derive(Clone,Debug)]
+pub struct GifOptions {
+  /// The width and height of each cell in pixels.
+  pub cell_size: usize,
+  pub palette: Palette,
+  pub fps: u32,
+  /// The number of steps to take before the first picture.
+  pub skip: usize,
+  /// The most frames to record.
+  pub max_frames: usize,
+}
+
+impl Default for GifOptions {
+  fn default() -> Self {
+    GifOptions{cell_size: 4, palette: PALETTE, fps: 20, skip: 0, max_frames: 1000}
+  }
+}
+
+/// A rectangle of cells given by its first and last column and row.
+type Area = ((usize, usize), (usize, usize));
+
+/// The smallest rectangle that holds all of the cells that changed.
+fn changed_area(next: &FrameBuffer, previous: &FrameBuffer) -> Option<Area> {
+  next.diff(previous).map(|(position, _)| position)
+      .fold(None, |area, (x, y)| match area {
+        None => Some(((x, y), (x, y))),
+        Some(((left, top), (right, bottom))) =>
+          Some(((left.min(x), top.min(y)), (right.max(x), bottom.max(y)))),
+      })
+}
+
+/// Draw the area of the cells as a GIF frame.
+fn picture(cells: &Grid<Cell>, ((left, top), (right, bottom)): Area, cell_size: usize,
+           delay: u16) -> ::gif::Frame<'static> {
+  let mut pixels = Vec::with_capacity((right + 1 - left) * (bottom + 1 - top) *
+      cell_size * cell_size);
+  for y in top * cell_size..(bottom + 1) * cell_size {
+    for x in left * cell_size..(right + 1) * cell_size {
+      pixels.push(palette_index(&cells[(x / cell_size, y / cell_size)]));
+    }
+  }
+  ::gif::Frame{
+    delay,
+    dispose: DisposalMethod::Keep,
+    left: (left * cell_size) as u16,
+    top: (top * cell_size) as u16,
+    width: ((right + 1 - left) * cell_size) as u16,
+    height: ((bottom + 1 - top) * cell_size) as u16,
+    buffer: Cow::Owned(pixels),
+    ..::gif::Frame::default()
+  }
+}
+
+/// Record the frame's steps as an animated GIF that loops forever. Frames
+/// that don't change anything make the previous picture last longer. Returns
+/// the number of frames that were recorded.
+pub fn record(frame: &mut dyn Simulation, output: impl Write,
+              options: &GifOptions) -> Result<usize, String> {
+  for _ in 0..options.skip {
+    if !frame.step() {
+      break;
+    }
+  }
+  let cell_size = options.cell_size.max(1);
+  let first = frame.render();
+  let (width, height) = (first.cells.width(), first.cells.height());
+  if width == 0 || height == 0 {
+    return Err("The frame doesn't have any cells".to_string());
+  }
+  let size = |cells: usize| u16::try_from(cells * cell_size)
+      .map_err(|_| format!("The frame is too large for a GIF at {cell_size} pixels per cell"));
+  let palette = options.palette.iter().flatten().copied().collect::<Vec<u8>>();
+  let mut encoder = Encoder::new(output, size(width)?, size(height)?, &palette)
+      .map_err(|e| e.to_string())?;
+  encoder.set_repeat(Repeat::Infinite).map_err(|e| e.to_string())?;
+  // the delay is in hundredths of a second and browsers slow anything under 2
+  let delay = (100 / options.fps.max(1)).clamp(2, u16::MAX as u32) as u16;
+  let mut pending = picture(&first.cells, ((0, 0), (width - 1, height - 1)), cell_size, delay);
+  let mut previous = first;
+  let mut count = 1;
+  while count < options.max_frames && frame.step() {
+    let next = frame.render();
+    if (next.cells.width(), next.cells.height()) != (width, height) {
+      return Err("The frames changed size".to_string());
+    }
+    match changed_area(&next, &previous) {
+      Some(area) => {
+        encoder.write_frame(&pending).map_err(|e| e.to_string())?;
+        pending = picture(&next.cells, area, cell_size, delay);
+      }
+      None => pending.delay = pending.delay.saturating_add(delay),
+    }
+    previous = next;
+    count += 1;
+  }
+  encoder.write_frame(&pending).map_err(|e| e.to_string())?;
+  encoder.into_inner().map_err(|e| e.to_string())?;
+  Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::utils::anim::{Cell, Color, FrameBuffer, Simulation};
+  use crate::utils::grid::Grid;
+  use super::{record, GifOptions};
+
+  /// Moves a red cell along a row, waiting at the end for a step.
+  struct Slide(usize);
+
+  impl Simulation for Slide {
+    fn title(&self) -> String {
+      "Slide".to_string()
+    }
+
+    fn state(&self) -> String {
+      format!("at {}", self.0)
+    }
+
+    fn render(&self) -> FrameBuffer {
+      let cells = (0..4).map(|i| if i == self.0.min(3) {
+        Cell::new('@', Color::Red)
+      } else {
+        Cell::new('.', Color::Default)
+      }).collect();
+      FrameBuffer{cells: Grid::new(4, 1, cells).unwrap(), status: String::new()}
+    }
+
+    fn step(&mut self) -> bool {
+      self.0 += 1;
+      self.0 < 5
+    }
+  }
+
+  #[test]
+  fn test_record() {
+    let mut output = Vec::new();
+    let options = GifOptions{cell_size: 2, fps: 10, ..GifOptions::default()};
+    assert_eq!(5, record(&mut Slide(0), &mut output, &options).unwrap());
+    let mut decoder = ::gif::DecodeOptions::new().read_info(output.as_slice()).unwrap();
+    assert_eq!((8, 2), (decoder.width(), decoder.height()));
+    let mut frames = Vec::new();
+    while let Some(frame) = decoder.read_next_frame().unwrap() {
+      frames.push((frame.left, frame.width, frame.delay));
+    }
+    // the last step doesn't change anything, so the final picture lasts longer
+    assert_eq!(vec![(0, 8, 10), (0, 4, 10), (2, 4, 10), (4, 4, 20)], frames);
+    let mut output = Vec::new();
+    let options = GifOptions{skip: 2, max_frames: 2, ..GifOptions::default()};
+    assert_eq!(2, record(&mut Slide(0), &mut output, &options).unwrap());
+  }
+}
@@ -0,0 +1,79 @@
+//! Draw a grid of cells as a PNG image with a square of color for each cell.
+use tiny_skia::{Paint, Pixmap, Rect, Transform};
+use crate::utils::anim::Cell;
+use crate::utils::grid::Grid;
+use crate::utils::render::{palette_index, Palette, PALETTE};
+
+/// How the image is drawn.
+#[derive(Clone,Debug)]
+pub struct PngOptions {
+  /// The width and height of each cell in pixels.
+  pub cell_size: usize,
+  pub palette: Palette,
+}
+
+impl Default for PngOptions {
+  fn default() -> Self {
+    PngOptions{cell_size: 8, palette: PALETTE}
+  }
+}
+
+fn color([red, green, blue]: [u8; 3]) -> tiny_skia::Color {
+  tiny_skia::Color::from_rgba8(red, green, blue, 255)
+}
+
+/// Draw the cells as a PNG file. Runs of cells in a row with the same color
+/// are filled as one rectangle.
+pub fn render(cells: &Grid<Cell>, options: &PngOptions) -> Result<Vec<u8>, String> {
+  let cell_size = options.cell_size.max(1);
+  let mut pixmap = Pixmap::new((cells.width() * cell_size) as u32,
+                               (cells.height() * cell_size) as u32)
+      .ok_or(format!("Can't draw a {}x{} grid at {cell_size} pixels per cell",
+                     cells.width(), cells.height()))?;
+  pixmap.fill(color(options.palette[0]));
+  let mut paint = Paint{anti_alias: false, ..Paint::default()};
+  for (y, row) in cells.rows().enumerate() {
+    let mut x = 0;
+    for run in row.chunk_by(|left, right| palette_index(left) == palette_index(right)) {
+      let index = palette_index(&run[0]) as usize;
+      if index != 0 {
+        paint.set_color(color(options.palette[index]));
+        let rect = Rect::from_xywh((x * cell_size) as f32, (y * cell_size) as f32,
+                                   (run.len() * cell_size) as f32, cell_size as f32)
+            .ok_or("Bad rectangle")?;
+        pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+      }
+      x += run.len();
+    }
+  }
+  pixmap.encode_png().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+  use tiny_skia::Pixmap;
+  use crate::utils::anim::{Cell, Color};
+  use crate::utils::grid::Grid;
+  use crate::utils::render::LIGHT_PALETTE;
+  use super::{render, PngOptions};
+
+  #[test]
+  fn test_render() {
+    let cells = Grid::from_str("#.\n.@", |ch| Ok(match ch {
+      '#' => Cell::new(ch, Color::Gray),
+      '@' => Cell::new(ch, Color::Red),
+      _ => Cell::new(ch, Color::Default),
+    })).unwrap();
+    let png = render(&cells, &PngOptions{cell_size: 3, palette: LIGHT_PALETTE}).unwrap();
+    let image = Pixmap::decode_png(&png).unwrap();
+    assert_eq!((6, 6), (image.width(), image.height()));
+    let rgb = |x, y| {
+      let pixel = image.pixel(x, y).unwrap();
+      [pixel.red(), pixel.green(), pixel.blue()]
+    };
+    assert_eq!(LIGHT_PALETTE[2], rgb(2, 2));
+    assert_eq!(LIGHT_PALETTE[0], rgb(3, 0));
+    assert_eq!(LIGHT_PALETTE[3], rgb(5, 3));
+    assert!(render(&Grid::new(0, 0, vec![]).unwrap(), &PngOptions::default()).is_err());
+  }
+}
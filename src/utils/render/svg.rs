@@ -0,0 +1,185 @@
+//! Draw a grid as an SVG document with a square for each cell and lines
+//! on top of it for the paths through the grid, or draw a bar chart.
+use std::fmt::Write;
+use crate::utils::grid::Grid;
+
+/// The width and height of each cell in the document.
+const CELL_SIZE: usize = 10;
+
+/// How a cell is drawn. Cells without a fill are left transparent.
+#[derive(Clone,Debug,Default,Eq,PartialEq)]
+pub struct Style {
+  pub fill: Option<String>,
+}
+
+impl Style {
+  /// Fill the cell with a color, such as "#808080" or "hsl(120, 70%, 60%)".
+  pub fn fill(color: impl Into<String>) -> Self {
+    Style{fill: Some(color.into())}
+  }
+
+  pub fn empty() -> Self {
+    Style{fill: None}
+  }
+}
+
+/// A line through the centers of a list of cells.
+#[derive(Clone,Debug,PartialEq)]
+pub struct Path {
+  pub cells: Vec<(usize, usize)>,
+  pub color: String,
+  pub width: f64,
+}
+
+impl Path {
+  pub fn new(cells: Vec<(usize, usize)>, color: impl Into<String>) -> Self {
+    Path{cells, color: color.into(), width: CELL_SIZE as f64 / 4.0}
+  }
+}
+
+fn center(cell: usize) -> usize {
+  cell * CELL_SIZE + CELL_SIZE / 2
+}
+
+/// Draw the grid with the style of each cell and then the paths in order.
+/// Runs of cells in a row with the same style are drawn as one rectangle to
+/// keep the document small.
+pub fn render<T>(grid: &Grid<T>, style: impl Fn(&T) -> Style, paths: &[Path]) -> String {
+  let (width, height) = (grid.width() * CELL_SIZE, grid.height() * CELL_SIZE);
+  let mut result = String::new();
+  writeln!(result, "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" \
+                    height=\"{height}\" viewBox=\"0 0 {width} {height}\">").unwrap();
+  for (y, row) in grid.rows().enumerate() {
+    let styles = row.iter().map(&style).collect::<Vec<Style>>();
+    let mut x = 0;
+    for run in styles.chunk_by(|left, right| left == right) {
+      if let Some(fill) = &run[0].fill {
+        writeln!(result, "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{CELL_SIZE}\" \
+                          fill=\"{fill}\"/>",
+                 x * CELL_SIZE, y * CELL_SIZE, run.len() * CELL_SIZE).unwrap();
+      }
+      x += run.len();
+    }
+  }
+  for path in paths.iter().filter(|path| !path.cells.is_empty()) {
+    let points = path.cells.iter()
+        .map(|&(x, y)| format!("{},{}", center(x), center(y)))
+        .collect::<Vec<_>>().join(" ");
+    writeln!(result, "  <polyline points=\"{points}\" fill=\"none\" stroke=\"{}\" \
+                      stroke-width=\"{}\" stroke-linecap=\"round\" \
+                      stroke-linejoin=\"round\"/>", path.color, path.width).unwrap();
+  }
+  result.push_str("</svg>\n");
+  result
+}
+
+/// A color for each of many ids, spread around the color wheel so that
+/// neighboring ids look different.
+pub fn id_color(id: usize) -> String {
+  // the golden angle keeps consecutive hues far apart
+  format!("hsl({}, 70%, 60%)", (id * 137) % 360)
+}
+
+/// One kind of bar in a chart, with a value for each group.
+#[derive(Clone,Debug,PartialEq)]
+pub struct Series {
+  pub name: String,
+  pub color: String,
+  pub values: Vec<f64>,
+}
+
+/// The height of a bar chart's plot and the width of each bar.
+const CHART_HEIGHT: f64 = 300.0;
+const BAR_WIDTH: usize = 12;
+/// The space around the plot for the axis and the labels.
+const CHART_LEFT: usize = 60;
+const CHART_TOP: usize = 30;
+const CHART_BOTTOM: usize = 30;
+
+/// Draw a bar chart with a group of bars for each label, one from each of
+/// the series, on a logarithmic scale with a line at each power of ten.
+/// The tick function labels those lines. Values that aren't positive are
+/// left out.
+pub fn bar_chart(labels: &[String], series: &[Series], tick: impl Fn(f64) -> String) -> String {
+  let positive = || series.iter().flat_map(|series| &series.values).filter(|&&v| v > 0.0);
+  let low = positive().fold(f64::INFINITY, |low, v| low.min(v.log10().floor()));
+  let high = positive().fold(f64::NEG_INFINITY, |high, v| high.max(v.log10().ceil()));
+  let (low, high) = if low.is_finite() { (low, high.max(low + 1.0)) } else { (0.0, 1.0) };
+  let y = |value: f64| CHART_TOP as f64 + CHART_HEIGHT * (high - value.log10()) / (high - low);
+  let group_width = series.len() * BAR_WIDTH + BAR_WIDTH;
+  // leave room for the legend, which has an entry every 100 pixels
+  let width = CHART_LEFT + (labels.len() * group_width + BAR_WIDTH).max(series.len() * 100);
+  let height = CHART_TOP + CHART_HEIGHT as usize + CHART_BOTTOM;
+  let mut result = String::new();
+  writeln!(result, "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" \
+                    height=\"{height}\" viewBox=\"0 0 {width} {height}\" \
+                    font-family=\"sans-serif\" font-size=\"10\">").unwrap();
+  for power in low as i32..=high as i32 {
+    let line = y(10f64.powi(power));
+    writeln!(result, "  <line x1=\"{CHART_LEFT}\" y1=\"{line:.1}\" x2=\"{width}\" \
+                      y2=\"{line:.1}\" stroke=\"#d0d0d0\"/>").unwrap();
+    writeln!(result, "  <text x=\"{}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>",
+             CHART_LEFT - 4, line + 3.0, tick(10f64.powi(power))).unwrap();
+  }
+  for (group, label) in labels.iter().enumerate() {
+    let left = CHART_LEFT + BAR_WIDTH + group * group_width;
+    for (index, series) in series.iter().enumerate() {
+      let Some(&value) = series.values.get(group).filter(|&&v| v > 0.0) else { continue };
+      let top = y(value);
+      writeln!(result, "  <rect x=\"{}\" y=\"{top:.1}\" width=\"{BAR_WIDTH}\" \
+                        height=\"{:.1}\" fill=\"{}\"/>",
+               left + index * BAR_WIDTH, CHART_TOP as f64 + CHART_HEIGHT - top,
+               series.color).unwrap();
+    }
+    writeln!(result, "  <text x=\"{}\" y=\"{}\" text-anchor=\"middle\">{label}</text>",
+             left + series.len() * BAR_WIDTH / 2, height - CHART_BOTTOM / 2).unwrap();
+  }
+  for (index, series) in series.iter().enumerate() {
+    let left = CHART_LEFT + index * 100;
+    writeln!(result, "  <rect x=\"{left}\" y=\"8\" width=\"10\" height=\"10\" \
+                      fill=\"{}\"/>", series.color).unwrap();
+    writeln!(result, "  <text x=\"{}\" y=\"17\">{}</text>", left + 14, series.name).unwrap();
+  }
+  result.push_str("</svg>\n");
+  result
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::utils::grid::Grid;
+  use super::{bar_chart, id_color, render, Path, Series, Style};
+
+  #[test]
+  fn test_render() {
+    let grid = Grid::from_str("##.\n#..", |ch| Ok(ch == '#')).unwrap();
+    let svg = render(&grid, |&wall| if wall { Style::fill("black") } else { Style::empty() },
+                     &[Path::new(vec![(2, 0), (2, 1), (1, 1)], "red"), Path::new(vec![], "blue")]);
+    assert_eq!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"30\" height=\"20\" \
+                viewBox=\"0 0 30 20\">\n\
+                \x20 <rect x=\"0\" y=\"0\" width=\"20\" height=\"10\" fill=\"black\"/>\n\
+                \x20 <rect x=\"0\" y=\"10\" width=\"10\" height=\"10\" fill=\"black\"/>\n\
+                \x20 <polyline points=\"25,5 25,15 15,15\" fill=\"none\" stroke=\"red\" \
+                stroke-width=\"2.5\" stroke-linecap=\"round\" stroke-linejoin=\"round\"/>\n\
+                </svg>\n", svg);
+    assert_ne!(id_color(1), id_color(2));
+  }
+
+  #[test]
+  fn test_bar_chart() {
+    let labels = vec!["1".to_string(), "2".to_string()];
+    let series = |name: &str, color: &str, values| {
+      Series{name: name.to_string(), color: color.to_string(), values}
+    };
+    let series = [series("parse", "red", vec![10.0, 0.0]),
+                  series("solve", "blue", vec![50.0, 2.0])];
+    let svg = bar_chart(&labels, &series, |value| format!("{value}ns"));
+    // one bar for each positive value and one in each of the legend's entries
+    assert_eq!(5, svg.matches("<rect").count());
+    // the lines go from 1ns to 100ns
+    assert!(svg.contains(">1ns</text>") && svg.contains(">100ns</text>"), "{svg}");
+    assert!(!svg.contains(">1000ns</text>"), "{svg}");
+    assert!(svg.contains("<rect x=\"72\" y=\"180.0\" width=\"12\" height=\"150.0\" \
+                          fill=\"red\"/>"), "{svg}");
+    assert!(bar_chart(&[], &[], |value| value.to_string()).ends_with("</svg>\n"));
+  }
+}
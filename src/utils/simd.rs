@@ -0,0 +1,108 @@
+//! Scanning rows of bytes sixteen at a time. On x86_64 the rows are compared
+//! with SSE2, which every x86_64 processor has, while the other targets,
+//! such as wasm, use the scalar versions in the same order.
+
+#[cfg(target_arch = "x86_64")]
+pub use sse2::{count_aligned, find_any};
+#[cfg(not(target_arch = "x86_64"))]
+pub use scalar::{count_aligned, find_any};
+
+/// The number of bytes that are compared at once.
+pub const LANES: usize = 16;
+
+/// The position of the first byte that is the needle, like memchr.
+pub fn find_byte(haystack: &[u8], needle: u8) -> Option<usize> {
+  find_any(haystack, &[needle])
+}
+
+/// The byte at a time versions, which handle the ends of the rows that
+/// don't fill the lanes.
+pub mod scalar {
+  /// The position of the first byte that is any of the needles.
+  pub fn find_any(haystack: &[u8], needles: &[u8]) -> Option<usize> {
+    haystack.iter().position(|byte| needles.contains(byte))
+  }
+
+  /// Count the positions where each of the rows has its byte of the
+  /// pattern, going only as far as the shortest row.
+  pub fn count_aligned<const N: usize>(rows: [&[u8]; N], pattern: [u8; N]) -> usize {
+    let len = rows.iter().map(|row| row.len()).min().unwrap_or(0);
+    (0..len).filter(|&i| rows.iter().zip(pattern).all(|(row, byte)| row[i] == byte)).count()
+  }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod sse2 {
+  use std::arch::x86_64::*;
+  use super::{scalar, LANES};
+
+  /// A bit for each byte of the chunk that is one of the needles.
+  fn matches(chunk: &[u8], needles: &[u8]) -> u32 {
+    assert_eq!(LANES, chunk.len());
+    // SAFETY: every x86_64 processor has SSE2, and the chunk has the sixteen
+    // bytes that are loaded
+    unsafe {
+      let bytes = _mm_loadu_si128(chunk.as_ptr().cast());
+      let found = needles.iter().fold(_mm_setzero_si128(), |found, &needle| {
+        _mm_or_si128(found, _mm_cmpeq_epi8(bytes, _mm_set1_epi8(needle as i8)))
+      });
+      _mm_movemask_epi8(found) as u32
+    }
+  }
+
+  pub fn find_any(haystack: &[u8], needles: &[u8]) -> Option<usize> {
+    let mut chunks = haystack.chunks_exact(LANES);
+    for (index, chunk) in chunks.by_ref().enumerate() {
+      let mask = matches(chunk, needles);
+      if mask != 0 {
+        return Some(index * LANES + mask.trailing_zeros() as usize);
+      }
+    }
+    let done = haystack.len() - chunks.remainder().len();
+    scalar::find_any(chunks.remainder(), needles).map(|position| done + position)
+  }
+
+  pub fn count_aligned<const N: usize>(rows: [&[u8]; N], pattern: [u8; N]) -> usize {
+    let len = rows.iter().map(|row| row.len()).min().unwrap_or(0);
+    let whole = len - len % LANES;
+    let mut result = 0;
+    for start in (0..whole).step_by(LANES) {
+      let mask = rows.iter().zip(pattern)
+          .fold(u32::MAX, |mask, (row, byte)| mask & matches(&row[start..start + LANES], &[byte]));
+      result += mask.count_ones() as usize;
+    }
+    result + scalar::count_aligned(rows.map(|row| &row[whole..len]), pattern)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{count_aligned, find_any, find_byte, scalar};
+
+  #[test]
+  fn test_find() {
+    let text = b"xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+    for start in 0..text.len() {
+      let haystack = &text[start..];
+      assert_eq!(scalar::find_any(haystack, b"md"), find_any(haystack, b"md"), "{start}");
+      assert_eq!(scalar::find_any(haystack, b"?"), find_byte(haystack, b'?'), "{start}");
+    }
+    assert_eq!(Some(20), find_byte(text, b'd'));
+    assert_eq!(None, find_any(text, b"z~"));
+  }
+
+  #[test]
+  fn test_count_aligned() {
+    let rows: Vec<Vec<u8>> = (0..4).map(|row| (0..50).map(|i| b"XMAS"[(i + row) % 4]).collect())
+        .collect();
+    for len in [0, 3, 16, 17, 40, 50] {
+      let rows = [&rows[0][..len], &rows[1][..len], &rows[2][..len], &rows[3][..len]];
+      for pattern in [*b"XMAS", *b"MASX", *b"XXXX"] {
+        assert_eq!(scalar::count_aligned(rows, pattern), count_aligned(rows, pattern));
+      }
+    }
+    assert_eq!(13, count_aligned([&rows[0][..], &rows[1][..]], *b"XM"));
+    // only as far as the shorter row
+    assert_eq!(1, count_aligned([&b"ab"[..], &b"abb"[..]], *b"bb"));
+  }
+}
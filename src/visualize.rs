@@ -0,0 +1,144 @@
+//! Pictures of the grid days: step by step views, which are played by the
+//! animation engine in utils::anim, SVG documents of the answers, and
+//! snapshots of the grids.
+use crate::params;
+use crate::utils::anim::{Cell, Color, Simulation};
+use crate::utils::grid::Grid;
+use crate::y2024::{day10, day12, day14, day15, day16, day18, day20, day4, day6, day8};
+
+/// The days that have a visualization.
+pub const DAYS: &[usize] = &[6, 14, 15, 18];
+
+/// Build the simulation that the day's visualization plays from its input.
+pub fn for_day(day: usize, input: &str) -> Result<Box<dyn Simulation>, String> {
+  match day {
+    6 => Ok(Box::new(day6::Patrol::new(&day6::try_generator(input)?))),
+    14 => Ok(Box::new(day14::Restroom::new(&day14::try_generator(input)?))),
+    15 => Ok(Box::new(day15::Warehouse::new(&day15::try_generator(input)?))),
+    18 => {
+      let memory = day18::try_generator(input)?;
+      Ok(Box::new(day18::FallingBytes::new(&memory.bytes, memory.bounds())))
+    }
+    _ => Err(format!("Day {day} doesn't have a visualization, try one of {DAYS:?}")),
+  }
+}
+
+/// The days that can be drawn as SVG.
+pub const SVG_DAYS: &[usize] = &[12, 16, 20];
+
+/// Draw the day's answer as an SVG document: the regions of day 12, the
+/// best paths of day 16, and the part 1 cheats of day 20.
+pub fn svg_for_day(day: usize, input: &str) -> Result<String, String> {
+  match day {
+    12 => Ok(day12::to_svg(&day12::try_generator(input)?)),
+    16 => day16::to_svg(input),
+    20 => Ok(day20::to_svg(&day20::try_generator(input)?, params::current().cheat_limit, 2)),
+    _ => Err(format!("Day {day} can't be drawn as SVG, try one of {SVG_DAYS:?}")),
+  }
+}
+
+/// The days with a grid that can be drawn as a snapshot.
+pub const SNAPSHOT_DAYS: &[usize] = &[4, 6, 8, 10, 12, 14, 15, 16, 18, 20];
+
+/// The color of a character in the input grid of a day without a
+/// visualization.
+fn input_color(day: usize, ch: char) -> Color {
+  const LETTERS: [Color; 6] =
+      [Color::Red, Color::Green, Color::Yellow, Color::Blue, Color::Magenta, Color::Cyan];
+  match (day, ch) {
+    (_, '.') => Color::Default,
+    (_, '#') => Color::Gray,
+    (4, 'X') => Color::Red,
+    (4, 'M') => Color::Yellow,
+    (4, 'A') => Color::Green,
+    (4, _) => Color::Blue,
+    // the trailheads and the peaks
+    (10, '0') => Color::Green,
+    (10, '9') => Color::Red,
+    (10, _) => Color::Gray,
+    (16 | 20, 'S') => Color::Green,
+    (16 | 20, 'E') => Color::Red,
+    // each antenna frequency and garden plant gets its own color
+    _ => LETTERS[ch as usize % LETTERS.len()],
+  }
+}
+
+/// The day's grid as cells. The days with a visualization are drawn after
+/// the given number of steps, while the others draw their input.
+pub fn snapshot_for_day(day: usize, input: &str, skip: usize) -> Result<Grid<Cell>, String> {
+  if DAYS.contains(&day) {
+    let mut frame = for_day(day, input)?;
+    for _ in 0..skip {
+      if !frame.step() {
+        break;
+      }
+    }
+    return Ok(frame.render().cells);
+  }
+  // make sure that the day can parse the input before drawing it
+  match day {
+    4 => { day4::try_generator(input)?; }
+    8 => { day8::try_generator(input)?; }
+    10 => { day10::try_generator(input)?; }
+    12 => { day12::try_generator(input)?; }
+    16 => { day16::try_generator(input)?; }
+    20 => { day20::try_generator(input)?; }
+    _ => return Err(format!("Day {day} doesn't have a grid to draw, try one of {SNAPSHOT_DAYS:?}")),
+  }
+  Grid::from_str(input, |ch| Ok(Cell::new(ch, input_color(day, ch))))
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::utils::anim::{Cell, Color};
+  use super::{for_day, snapshot_for_day, svg_for_day, DAYS};
+
+  #[test]
+  fn test_for_day() {
+    let mut patrol = for_day(6, "..#\n.^.\n...").unwrap();
+    assert_eq!("Visited: 1\n..#\n.^.\n...\n", patrol.render().to_text());
+    assert!(patrol.step());
+    assert_eq!("Visited: 2\n.^#\n.X.\n...\n", patrol.render().to_text());
+    // the guard walks off of the top
+    assert!(patrol.step());
+    assert_eq!("Visited: 2\n.X#\n.X.\n...\n", patrol.render().to_text());
+    assert!(!patrol.step());
+    let mut warehouse = for_day(15, "#####\n#@O.#\n#####\n\n>").unwrap();
+    assert!(warehouse.step());
+    assert!(!warehouse.step());
+    assert_eq!("Moves: 1/1, GPS: 104\n##########\n##.@[]..##\n##########\n",
+               warehouse.render().to_text());
+    let mut restroom = for_day(14, "p=0,0 v=1,2").unwrap();
+    assert!(restroom.step());
+    let frame = restroom.render();
+    assert_eq!("Seconds: 1, safety: 0", frame.status);
+    assert_eq!(Cell::new('#', Color::Green), frame.cells[(1, 2)]);
+    assert_eq!(1, frame.cells.iter().filter(|(_, cell)| cell.symbol == '#').count());
+    assert!(for_day(6, "").is_err());
+    assert_eq!(format!("Day 1 doesn't have a visualization, try one of {DAYS:?}"),
+               for_day(1, "").err().unwrap());
+  }
+
+  #[test]
+  fn test_svg_for_day() {
+    let svg = svg_for_day(12, "AAAA\nBBCD\nBBCC\nEEEC").unwrap();
+    assert!(svg.starts_with("<svg "));
+    // each run of a region in a row is a single rectangle
+    assert_eq!(8, svg.matches("<rect ").count());
+    assert!(svg_for_day(16, "").is_err());
+    assert!(svg_for_day(6, "").is_err());
+  }
+
+  #[test]
+  fn test_snapshot_for_day() {
+    let cells = snapshot_for_day(10, "0129\n9876", 0).unwrap();
+    assert_eq!(Cell::new('0', Color::Green), cells[(0, 0)]);
+    assert_eq!(Cell::new('9', Color::Red), cells[(3, 0)]);
+    assert_eq!(Cell::new('8', Color::Gray), cells[(1, 1)]);
+    // the visualizations take steps first
+    let cells = snapshot_for_day(6, "..#\n.^.\n...", 1).unwrap();
+    assert_eq!(Cell::new('X', Color::Yellow), cells[(1, 1)]);
+    assert!(snapshot_for_day(10, "01a", 0).is_err());
+    assert!(snapshot_for_day(1, "", 0).is_err());
+  }
+}
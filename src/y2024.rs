@@ -0,0 +1,11 @@
+//! The puzzles from 2024, which are the ones that this crate started with.
+crate::day_list!(2024 => 1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,16,17,18,19,20,21);
+
+/// The other ways of solving some of the parts, which --compare checks
+/// against the registered ones.
+pub const ALGORITHMS: &[crate::utils::Algorithm] = &[
+  crate::algorithm!(day7, 1, "forward", part1_forward),
+  crate::algorithm!(day7, 2, "forward", part2_forward),
+  crate::algorithm!(day18, 1, "astar", part1_astar),
+  crate::algorithm!(day18, 2, "binary search", part2_search),
+];
@@ -1,10 +1,10 @@
+use std::io::BufRead;
 use std::iter::zip;
 use itertools::Itertools;
 use smallvec::SmallVec;
+use crate::utils::parse::{parse_int, parse_lines};
 
-fn parse_int(s: &str) -> Result<i32, String> {
-  s.parse().map_err(|_| format!("Can't parse integer - '{s}'"))
-}
+pub const TITLE: &str = "Historian Hysteria";
 
 fn parse_line(s: &str) -> Result<(i32,i32), String> {
   let words: SmallVec<[i32; 2]> = s.split_whitespace().map(parse_int).try_collect()?;
@@ -12,8 +12,17 @@ fn parse_line(s: &str) -> Result<(i32,i32), String> {
   Ok((words[0], words[1]))
 }
 
+pub fn try_generator(input: &str) -> Result<Vec<(i32,i32)>, String> {
+  input.lines().map(parse_line).try_collect()
+}
+
 pub fn generator(input: &str) -> Vec<(i32,i32)> {
-  input.lines().map(parse_line).try_collect().expect("Can't parse input")
+  try_generator(input).expect("Can't parse input")
+}
+
+/// Parse the input a line at a time from the reader.
+pub fn generator_from_reader(reader: impl BufRead) -> Result<Vec<(i32,i32)>, String> {
+  parse_lines(reader, parse_line)
 }
 
 pub fn part1(input: &[(i32,i32)]) -> i32 {
@@ -35,28 +44,3 @@ pub fn part2(input: &[(i32,i32)]) -> i32 {
         _ => 0,
       }).sum()
 }
-
-#[cfg(test)]
-mod tests {
-  use super::{generator, part1, part2};
-
-  const INPUT: &str =
-"3   4
-4   3
-2   5
-1   3
-3   9
-3   3";
-
-  #[test]
-  fn test_part1() {
-    let data = generator(INPUT);
-    assert_eq!(11, part1(&data));
-  }
-
-  #[test]
-  fn test_part2() {
-    let data = generator(INPUT);
-    assert_eq!(31, part2(&data));
-  }
-}
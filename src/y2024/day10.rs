@@ -1,9 +1,17 @@
+use std::io::BufRead;
+use itertools::Itertools;
 use smallvec::SmallVec;
+use crate::utils::graph::flood_fill;
+use crate::utils::grid::Neighborhood;
+use crate::utils::parse::from_reader;
+
+pub const TITLE: &str = "Hoof It";
 
 type Elevation = u8;
 type Position = i32;
 
-#[derive(Clone,Copy,Debug,Eq,Ord,PartialEq,PartialOrd)]
+#[derive(Clone,Copy,Debug,Eq,Hash,Ord,PartialEq,PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
 pub struct Coordinate {
   y: Position,
   x: Position,
@@ -11,6 +19,7 @@ pub struct Coordinate {
 
 type NeighborList = SmallVec<[Coordinate; 4]>;
 
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
 pub struct Map {
   grid: Vec<Vec<Elevation>>,
   starts: Vec<Coordinate>,
@@ -29,8 +38,8 @@ impl Map {
   }
 
   fn neighbors(&self, coordinate: Coordinate) -> NeighborList {
-    [(-1, 0), (1, 0), (0, -1), (0, 1)].iter()
-        .map(|(xd,yd)| Coordinate{x: coordinate.x + xd, y: coordinate.y + yd})
+    Neighborhood::Orthogonal.around(coordinate.x, coordinate.y)
+        .map(|(x, y)| Coordinate{x, y})
         .collect()
   }
 
@@ -43,40 +52,44 @@ impl Map {
 const START: Elevation = 0;
 const END: Elevation = 9;
 
-pub fn generator(input: &str) -> Map {
+pub fn try_generator(input: &str) -> Result<Map, String> {
   let mut starts = Vec::new();
   let mut ends = Vec::new();
   let grid = input.lines().enumerate()
       .map(|(y,line)| line.chars().enumerate().
           map(|(x, c)| {
-            let ele = c.to_digit(10).unwrap() as Elevation;
+            let ele = c.to_digit(10)
+                .ok_or(format!("Invalid elevation '{c}'"))? as Elevation;
             match ele {
               START => starts.push(Coordinate{x: x as Position, y: y as Position}),
               END => ends.push(Coordinate{x: x as Position, y: y as Position}),
               _ => {},
             }
-            ele
-          }).collect())
-      .collect();
-  Map{grid, starts, ends}
+            Ok::<_, String>(ele)
+          }).try_collect())
+      .try_collect()?;
+  Ok(Map{grid, starts, ends})
+}
+
+pub fn generator(input: &str) -> Map {
+  try_generator(input).expect("Can't parse input")
+}
+
+pub fn generator_from_reader(reader: impl BufRead) -> Result<Map, String> {
+  from_reader(reader, try_generator)
 }
 
 pub fn part1(input: &Map) -> u64 {
-  let mut counts = vec![0; input.starts.len()];
+  let mut result = 0;
   for dest in &input.ends {
-    let mut current = vec![*dest];
-    for elevation in (START..END).rev() {
-      let mut next: Vec<Coordinate> = current.iter()
-          .flat_map(|c| input.potential_previous(*c, elevation+1)).collect();
-      next.sort_unstable();
-      next.dedup();
-      current = next;
-    }
-    for start in &current {
-      counts[input.starts.binary_search(start).unwrap()] += 1;
-    }
+    // walk back down from the summit to find the trailheads that reach it
+    let reached = flood_fill([*dest], |c| match input.get(c) {
+      Some(START) | None => NeighborList::new(),
+      Some(elevation) => input.potential_previous(c, elevation),
+    });
+    result += input.starts.iter().filter(|start| reached.contains(start)).count() as u64;
   }
-  counts.into_iter().sum()
+  result
 }
 
 pub fn part2(input: &Map) -> u64 {
@@ -92,30 +105,3 @@ pub fn part2(input: &Map) -> u64 {
   }
   result
 }
-
-#[cfg(test)]
-mod tests {
-  use super::{generator, part1, part2};
-
-  const INPUT: &str =
-"89010123
-78121874
-87430965
-96549874
-45678903
-32019012
-01329801
-10456732";
-
-  #[test]
-  fn test_part1() {
-    let data = generator(INPUT);
-    assert_eq!(36, part1(&data));
-  }
-
-  #[test]
-  fn test_part2() {
-    let data = generator(INPUT);
-    assert_eq!(81, part2(&data));
-  }
-}
@@ -1,21 +1,29 @@
+use std::io::BufRead;
 use ahash::AHashMap;
+use crate::params::{self, Params};
+use crate::utils::parse::{from_reader, parse_int};
 
-fn parse_int(s: &str) -> Result<u64, String> {
-  s.parse().map_err(|_| format!("Can't parse integer - '{s}'"))
-}
+pub const TITLE: &str = "Plutonian Pebbles";
 
-pub fn generator(input: &str) -> AHashMap<u64, usize> {
+pub fn try_generator(input: &str) -> Result<AHashMap<u64, usize>, String> {
   let mut result: AHashMap<u64, usize> = AHashMap::default();
   for number in input.split_whitespace().map(parse_int) {
-    let number = number.expect("Could not parse number");
-    *result.entry(number).or_insert(0) += 1;
+    *result.entry(number?).or_insert(0) += 1;
   }
-  result
+  Ok(result)
+}
+
+pub fn generator(input: &str) -> AHashMap<u64, usize> {
+  try_generator(input).expect("Can't parse input")
+}
+
+pub fn generator_from_reader(reader: impl BufRead) -> Result<AHashMap<u64, usize>, String> {
+  from_reader(reader, try_generator)
 }
 
 fn split_number(num: u64) -> Option<(u64,u64)> {
   let digits = num.ilog10() + 1;
-  if digits % 2 == 0 {
+  if digits.is_multiple_of(2) {
     let pow10 = 10u64.pow(digits / 2);
     Some((num / pow10, num % pow10))
   } else {
@@ -49,35 +57,29 @@ fn do_blinks(input: &AHashMap<u64, usize>, blinks: usize) -> usize {
   work.values().sum()
 }
 
+pub fn part1_with(input: &AHashMap<u64, usize>, params: &Params) -> usize {
+  do_blinks(input, params.blinks1)
+}
+
 pub fn part1(input: &AHashMap<u64, usize>) -> usize {
-  do_blinks(input, 25)
+  part1_with(input, &params::current())
+}
+
+pub fn part2_with(input: &AHashMap<u64, usize>, params: &Params) -> usize {
+  do_blinks(input, params.blinks2)
 }
 
 pub fn part2(input: &AHashMap<u64, usize>) -> usize {
-  do_blinks(input, 75)
+  part2_with(input, &params::current())
 }
 
 #[cfg(test)]
 mod tests {
-  use super::{generator, part1, part2, split_number};
-
-  const INPUT: &str = "125 17";
-
-  #[test]
-  fn test_part1() {
-    let data = generator(INPUT);
-    assert_eq!(55312, part1(&data));
-  }
+  use super::split_number;
 
   #[test]
   fn test_split() {
     assert_eq!(Some((12, 34)), split_number(1234));
     assert_eq!(None, split_number(12345));
   }
-
-  #[test]
-  fn test_part2() {
-    let data = generator(INPUT);
-    assert_eq!(65601038650482, part2(&data));
-  }
 }
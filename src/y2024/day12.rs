@@ -0,0 +1,239 @@
+use std::cmp::Ordering;
+use std::io::BufRead;
+use std::ops::{DerefMut, Range};
+use std::str::FromStr;
+#[cfg(feature = "arena")]
+use bumpalo::Bump;
+use itertools::Itertools;
+use smallvec::SmallVec;
+#[cfg(feature = "arena")]
+use crate::utils::arena;
+use crate::utils::dsu::DisjointSet;
+use crate::utils::grid::{Grid as FloorGrid, Neighborhood};
+use crate::utils::render::svg::{self, Style};
+use crate::utils::parse::from_reader;
+
+pub const TITLE: &str = "Garden Groups";
+
+fn parse_line(s: &str) -> Vec<u8> {
+  s.chars().map(|c| c as u8).collect()
+}
+
+type Position = i32;
+
+#[derive(Clone,Debug,Eq,Ord,PartialEq,PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
+struct Coordinate {
+  y: Position,
+  x: Position,
+}
+
+#[derive(Clone,Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
+pub struct Grid {
+  plots: Vec<Vec<u8>>,
+  x_bound: Range<Position>,
+  y_bound: Range<Position>,
+}
+
+impl Grid {
+  /// Find the neighbors who are growing the same crop.
+  /// SE controls whether the south and east neighbors are included.
+  /// The output is sorted.
+  fn neighbors<const SE:bool>(&self, pos: &Coordinate, crop: u8) -> SmallVec<[Coordinate; 4]> {
+    // the north and west neighbors come first
+    let offsets = &Neighborhood::Orthogonal.offsets()[..if SE { 4 } else { 2 }];
+    Neighborhood::Custom(offsets).around(pos.x, pos.y)
+        .map(|(x, y)| Coordinate{x, y})
+        .filter(|coord| self.x_bound.contains(&coord.x)
+            && self.y_bound.contains(&coord.y) && self.get(coord) == crop)
+        .collect()
+  }
+
+  /// Count the neighbors in the neighborhood that are growing the crop.
+  fn count_neighbors(&self, pos: &Coordinate, crop: u8, neighborhood: Neighborhood) -> usize {
+    neighborhood.around(pos.x, pos.y)
+        .map(|(x, y)| Coordinate{x, y})
+        .filter(|coord| self.x_bound.contains(&coord.x)
+            && self.y_bound.contains(&coord.y) && self.get(coord) == crop)
+        .count()
+  }
+
+  #[inline]
+  fn get(&self, pos: &Coordinate) -> u8 {
+    self.plots[pos.y as usize][pos.x as usize]
+  }
+
+  /// Count the number of half corners of the shape that this position includes.
+  fn count_corners(&self, pos: &Coordinate) -> usize {
+    let crop = self.get(pos);
+    let neighbors = self.neighbors::<true>(pos, crop);
+    match neighbors.len() {
+      0 => 8,
+      1 => {
+        4 + self.count_neighbors(pos, crop,
+                                 match neighbors[0].y.cmp(&pos.y) {
+                                   Ordering::Less => Neighborhood::Custom(&[(-1, -1), (1, -1)]),
+                                   Ordering::Equal => {
+                                     if neighbors[0].x < pos.x {
+                                       Neighborhood::Custom(&[(-1, -1), (-1, 1)])
+                                     } else {
+                                       Neighborhood::Custom(&[(1, -1), (1, 1)])
+                                     }
+                                   }
+                                   Ordering::Greater => Neighborhood::Custom(&[(-1, 1), (1, 1)]),
+                                 })
+      },
+      2 => {
+        // Are they in a straight line?
+        if neighbors[0].x == neighbors[1].x ||
+            neighbors[0].y == neighbors[1].y {
+          self.count_neighbors(pos, crop, Neighborhood::Diagonal)
+        } else {
+          2 + self.count_neighbors(pos, crop,
+                                   if neighbors[1].x < pos.x || neighbors[0].x > pos.x {
+                                     Neighborhood::Custom(&[(1, -1), (-1, 1)])
+                                   } else {
+                                     Neighborhood::Custom(&[(-1, -1), (1, 1)])
+                                   })
+        }
+      },
+      3 => {
+        self.count_neighbors(pos, crop,
+                             if neighbors[0].x == neighbors[2].x {
+                               if neighbors[1].x < pos.x {
+                                 Neighborhood::Custom(&[(1, -1), (1, 1)])
+                               } else {
+                                 Neighborhood::Custom(&[(-1, -1), (-1, 1)])
+                               }
+                             } else if neighbors[0].y < pos.y {
+                               Neighborhood::Custom(&[(-1, 1), (1, 1)])
+                             } else {
+                               Neighborhood::Custom(&[(-1, -1), (1, -1)])
+                             })
+      }
+      _ => 0,
+    }
+  }
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
+pub struct Input {
+  grid: Grid,
+  sizes: Vec<Vec<usize>>,
+}
+
+impl FromStr for Grid {
+  type Err = String;
+
+  fn from_str(input: &str) -> Result<Self, String> {
+    let plots: Vec<Vec<u8>> = input.lines().map(parse_line).collect();
+    let width = plots.first().ok_or("Empty garden")?.len();
+    if let Some((y, row)) = plots.iter().find_position(|row| row.len() != width) {
+      return Err(format!("Row {y} has length {} instead of {width}", row.len()));
+    }
+    let y_bound = 0..(plots.len() as Position);
+    let x_bound = 0..(width as Position);
+    Ok(Grid{ plots, x_bound, y_bound})
+  }
+}
+
+impl Input {
+  /// Find the size of the region that each location is in.
+  pub fn new(grid: Grid) -> Input {
+    let sizes = find_sizes(&grid, find_regions(&grid));
+    Input { grid, sizes }
+  }
+
+  /// Find the sizes like new, but with the regions' forest in the arena.
+  #[cfg(feature = "arena")]
+  pub fn new_in(grid: Grid, arena: &Bump) -> Input {
+    let mut unionfind = DisjointSet::new_in(grid.x_bound.len() * grid.y_bound.len(), arena);
+    merge_regions(&grid, &mut unionfind);
+    let sizes = find_sizes(&grid, unionfind);
+    Input { grid, sizes }
+  }
+}
+
+pub fn try_generator(input: &str) -> Result<Input, String> {
+  let grid = Grid::from_str(input)?;
+  #[cfg(feature = "arena")]
+  let input = arena::scoped(|arena| Input::new_in(grid, arena));
+  #[cfg(not(feature = "arena"))]
+  let input = Input::new(grid);
+  Ok(input)
+}
+
+pub fn generator(input: &str) -> Input {
+  try_generator(input).expect("Can't parse input")
+}
+
+pub fn generator_from_reader(reader: impl BufRead) -> Result<Input, String> {
+  from_reader(reader, try_generator)
+}
+
+/// Merge the adjacent locations of the same crop into regions. Each
+/// location is numbered in row-major order.
+fn merge_regions<S: DerefMut<Target = [usize]>>(grid: &Grid, unionfind: &mut DisjointSet<S>) {
+  let width = grid.x_bound.len() as Position;
+  for y in grid.y_bound.clone() {
+    for x in grid.x_bound.clone() {
+      let cur = Coordinate{x,y};
+      for neighbor in grid.neighbors::<false>(&cur, grid.get(&cur)).iter() {
+        unionfind.union((y * width + x) as usize,
+                        (neighbor.y * width + neighbor.x) as usize);
+      }
+    }
+  }
+}
+
+/// Group the locations into the regions of the same crop.
+fn find_regions(grid: &Grid) -> DisjointSet {
+  // Each location starts as its own set
+  let mut unionfind = DisjointSet::new(grid.x_bound.len() * grid.y_bound.len());
+  merge_regions(grid, &mut unionfind);
+  unionfind
+}
+
+/// For each location, find the size of the field it is part of
+fn find_sizes<S: DerefMut<Target = [usize]>>(grid: &Grid, mut unionfind: DisjointSet<S>)
+    -> Vec<Vec<usize>> {
+  let width = grid.x_bound.len();
+  // For each location, find the size of the associated set.
+  grid.plots.iter().enumerate()
+      .map(|(y, row) | row.iter().enumerate()
+          .map(|(x, _) | unionfind.size(y * width + x))
+          .collect())
+      .collect()
+}
+
+pub fn part1(input: &Input) -> usize {
+  input.sizes.iter().enumerate()
+      .map(|(y, row)| row.iter().enumerate()
+          .map(|(x, size)| {
+            let coord = Coordinate{x: x as i32, y: y as i32};
+            size * (4 - input.grid.neighbors::<true>(&coord, input.grid.get(&coord)).len())})
+          .sum::<usize>())
+      .sum()
+}
+
+pub fn part2(input: &Input) -> usize {
+  input.sizes.iter().enumerate()
+      .map(|(y, row)| row.iter().enumerate()
+          .map(|(x, size)| {
+            let coord = Coordinate{x: x as i32, y: y as i32};
+            size * input.grid.count_corners(&coord)})
+          .sum::<usize>())
+      .sum::<usize>() / 2
+}
+
+/// Draw the garden with a different color for each region.
+pub fn to_svg(input: &Input) -> String {
+  let grid = &input.grid;
+  let mut unionfind = find_regions(grid);
+  let roots = (0..unionfind.len()).map(|i| unionfind.find(i)).collect();
+  let roots = FloorGrid::new(grid.x_bound.len(), grid.y_bound.len(), roots)
+      .expect("a root for each plot");
+  svg::render(&roots, |&root| Style::fill(svg::id_color(root)), &[])
+}
@@ -0,0 +1,146 @@
+use std::io::{self, BufRead, Write};
+use crate::utils::parse::{labeled, read_lines, split_pair};
+
+pub const TITLE: &str = "Claw Contraption";
+
+type Position = i64;
+
+#[derive(Clone,Debug,Eq,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
+pub struct Coordinate {
+  y: Position,
+  x: Position,
+}
+
+#[derive(Clone,Debug)]
+pub struct Pushes {
+  button_a: i64,
+  button_b: i64,
+}
+
+impl Pushes {
+  fn price(&self) -> i64 {
+    self.button_a * 3 + self.button_b
+  }
+}
+
+#[derive(Clone,Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
+pub struct Machine {
+  button_a: Coordinate,
+  button_b: Coordinate,
+  goal: Coordinate,
+}
+
+impl Machine {
+  fn solve(&self) -> Option<Pushes> {
+    let top = self.button_a.y * self.goal.x - self.button_a.x * self.goal.y;
+    let bottom = self.button_a.y * self.button_b.x - self.button_a.x * self.button_b.y;
+    if bottom == 0 || top % bottom != 0 {
+      None
+    } else {
+      let button_b = top / bottom;
+      let top = self.goal.x - self.button_b.x * button_b;
+      if self.button_a.x == 0 || top % self.button_a.x != 0 {
+        None
+      } else {
+        Some(Pushes{button_a: top / self.button_a.x, button_b})
+      }
+    }
+  }
+}
+
+fn parse_line(s: &str) -> Result<Coordinate, String> {
+  let (_, values) = split_pair(s, ": ")?;
+  let (x_str, y_str) = split_pair(values, ",")?;
+  Ok(Coordinate{x: labeled(x_str, "X")?, y: labeled(y_str, "Y")?})
+}
+
+fn parse_machine(lines: &[Coordinate]) -> Result<Machine, String> {
+  match lines {
+    [button_a, button_b, goal] =>
+      Ok(Machine{button_a: button_a.clone(), button_b: button_b.clone(), goal: goal.clone()}),
+    _ => Err(format!("Can't parse machine with {} lines", lines.len())),
+  }
+}
+
+/// Parse the machines a line at a time, where each one is three lines and
+/// blank lines separate them.
+fn parse_machines<S: AsRef<str>>(lines: impl Iterator<Item=Result<S, String>>)
+    -> Result<Vec<Machine>, String> {
+  let mut result = Vec::new();
+  let mut current = Vec::with_capacity(3);
+  for line in lines {
+    let line = line?;
+    if line.as_ref().is_empty() {
+      result.push(parse_machine(&current)?);
+      current.clear();
+    } else {
+      current.push(parse_line(line.as_ref())?);
+    }
+  }
+  if !current.is_empty() || result.is_empty() {
+    result.push(parse_machine(&current)?);
+  }
+  Ok(result)
+}
+
+pub fn try_generator(input: &str) -> Result<Vec<Machine>, String> {
+  parse_machines(input.lines().map(Ok))
+}
+
+pub fn generator(input: &str) -> Vec<Machine> {
+  try_generator(input).expect("Can't parse input")
+}
+
+/// Parse the input a line at a time from the reader.
+pub fn generator_from_reader(reader: impl BufRead) -> Result<Vec<Machine>, String> {
+  parse_machines(read_lines(reader))
+}
+
+pub fn part1(input: &[Machine]) -> i64 {
+  input.iter().filter_map(|m| m.solve()).map(|p| p.price()).sum()
+}
+
+fn part2_munge(machine: &Machine) -> Machine {
+  let mut result = machine.clone();
+  result.goal.x += 10000000000000;
+  result.goal.y += 10000000000000;
+  result
+}
+
+pub fn part2(input: &[Machine]) -> i64 {
+  input.iter().filter_map(|m| part2_munge(m).solve()).map(|p| p.price()).sum()
+}
+
+/// Describe the presses of each machine's prize.
+fn describe(pushes: Option<Pushes>) -> String {
+  match pushes {
+    Some(p) => format!("A {} times and B {} times for {} tokens", p.button_a, p.button_b,
+                       p.price()),
+    None => "can't be won".to_string(),
+  }
+}
+
+/// Write how many times each machine's buttons are pressed in both parts.
+pub fn explain(input: &[Machine], out: &mut dyn Write) -> io::Result<()> {
+  for (i, machine) in input.iter().enumerate() {
+    writeln!(out, "Machine {}: part 1 {}, part 2 {}", i + 1, describe(machine.solve()),
+             describe(part2_munge(machine).solve()))?;
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use proptest::prelude::*;
+  use crate::testgen;
+  use super::{generator, part1};
+
+  proptest! {
+    #[test]
+    fn test_planted_presses((input, tokens) in testgen::machines(5)) {
+      prop_assert_eq!(tokens, part1(&generator(&input)));
+    }
+  }
+}
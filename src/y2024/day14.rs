@@ -1,13 +1,17 @@
 use std::cmp::Ordering;
+use std::io::BufRead;
 use itertools::Itertools;
+use crate::params::{self, Params};
+use crate::utils::parse::{int_pair, parse_lines, split_pair};
+use crate::utils::anim::{Cell, Color, FrameBuffer, Simulation};
+use crate::utils::grid::Grid;
 
-type Position = i64;
+pub const TITLE: &str = "Restroom Redoubt";
 
-fn parse_int(s: &str) -> Result<Position, String> {
-  s.parse().map_err(|_| format!("Can't parse integer - '{s}'"))
-}
+type Position = i64;
 
 #[derive(Clone,Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
 pub struct Coordinate {
   y: Position,
   x: Position,
@@ -15,27 +19,22 @@ pub struct Coordinate {
 
 impl Coordinate {
   fn from_str(s: &str) -> Result<Self, String> {
-    let (_, values) = s.split_once('=')
-        .ok_or(format!("Can't find '=' in string '{s}'"))?;
-    let (x_str,y_str) = values.split_once(",")
-        .ok_or(format!("Can't find , in {values}"))?;
-    Ok(Coordinate{x: parse_int(x_str)?, y: parse_int(y_str)?})
+    let (_, values) = split_pair(s, "=")?;
+    let (x, y) = int_pair(values, ",")?;
+    Ok(Coordinate{x, y})
   }
 }
 
 #[derive(Clone,Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
 pub struct Robot {
   location: Coordinate,
   velocity: Coordinate,
 }
 
 impl Robot {
-  const BOARD_WIDTH: Position = 101;
-  const BOARD_HEIGHT: Position = 103;
-
   fn from_str(s: &str) -> Result<Self, String> {
-    let (loc_str, vel_str) = s.split_once(" ")
-        .ok_or("Can't split line {s}")?;
+    let (loc_str, vel_str) = split_pair(s, " ")?;
     let location = Coordinate::from_str(loc_str)?;
     let velocity = Coordinate::from_str(vel_str)?;
     Ok(Robot{location, velocity})
@@ -60,8 +59,17 @@ impl Robot {
   }
 }
 
+pub fn try_generator(input: &str) -> Result<Vec<Robot>, String> {
+  input.lines().map(Robot::from_str).try_collect()
+}
+
 pub fn generator(input: &str) -> Vec<Robot> {
-  input.lines().map(Robot::from_str).try_collect().expect("Can't parse input")
+  try_generator(input).expect("Can't parse input")
+}
+
+/// Parse the input a line at a time from the reader.
+pub fn generator_from_reader(reader: impl BufRead) -> Result<Vec<Robot>, String> {
+  parse_lines(reader, Robot::from_str)
 }
 
 /// Place the robots into quadrants and multiply the counts.
@@ -72,11 +80,20 @@ fn score(robots: &[Robot], width: Position, height: Position) -> usize {
   counts.iter().product()
 }
 
-pub fn part1(input: &[Robot]) -> usize {
+/// The width and height of the restroom.
+fn board(params: &Params) -> (Position, Position) {
+  (params.width as Position, params.height as Position)
+}
+
+pub fn part1_with(input: &[Robot], params: &Params) -> usize {
+  let (width, height) = board(params);
   let mut working = input.to_vec();
-  working.iter_mut().for_each(|r|
-      r.move_forward(100, Robot::BOARD_WIDTH, Robot::BOARD_HEIGHT));
-  score(&working, Robot::BOARD_WIDTH, Robot::BOARD_HEIGHT)
+  working.iter_mut().for_each(|r| r.move_forward(100, width, height));
+  score(&working, width, height)
+}
+
+pub fn part1(input: &[Robot]) -> usize {
+  part1_with(input, &params::current())
 }
 
 /// Is this robot in the upward facing triangle in middle of the grid?
@@ -123,30 +140,82 @@ fn display_robots(robots: &[Robot], width: Position, height: Position) {
   }
 }
 
-pub fn part2(input: &[Robot]) -> usize {
+/// The robots moving one second at a time for the visualizer. They are back
+/// where they started after the board's area in seconds, so that is the end.
+pub struct Restroom {
+  robots: Vec<Robot>,
+  seconds: usize,
+  width: Position,
+  height: Position,
+}
+
+impl Restroom {
+  pub fn new(robots: &[Robot]) -> Self {
+    let (width, height) = board(&params::current());
+    Restroom{robots: robots.to_vec(), seconds: 0, width, height}
+  }
+}
+
+impl Simulation for Restroom {
+  fn title(&self) -> String {
+    format!("Day 14: {TITLE}")
+  }
+
+  fn state(&self) -> String {
+    format!("{} seconds, safety {}, {} robots in the tree filter", self.seconds,
+            score(&self.robots, self.width, self.height),
+            tree_filter_count(&self.robots, self.width, self.height))
+  }
+
+  fn render(&self) -> FrameBuffer {
+    let mut cells = Grid::filled(self.width as usize, self.height as usize,
+                                 Cell::new('.', Color::Default));
+    for robot in &self.robots {
+      cells[(robot.location.x as usize, robot.location.y as usize)] = Cell::new('#', Color::Green);
+    }
+    FrameBuffer{cells, status: format!("Seconds: {}, safety: {}", self.seconds,
+                                       score(&self.robots, self.width, self.height))}
+  }
+
+  fn step(&mut self) -> bool {
+    if self.seconds == (self.width * self.height) as usize {
+      return false;
+    }
+    self.robots.iter_mut().for_each(|r| r.move_forward(1, self.width, self.height));
+    self.seconds += 1;
+    true
+  }
+}
+
+pub fn part2_with(input: &[Robot], params: &Params) -> usize {
+  let (width, height) = board(params);
   let mut working = input.to_vec();
   let goal_percent = 75;
   let goal = working.len() * goal_percent / 100;
   let sample = input.len() / 10;
   let mut steps = 0;
   // Find a time when most of the robots are in the tree filter region.
-  while tree_filter_count(&working, Robot::BOARD_WIDTH, Robot::BOARD_HEIGHT) < goal {
+  while tree_filter_count(&working, width, height) < goal {
     // Use a sample of the robots to find a candidate time.
-    let new_steps = find_tree(&mut working[..sample],
-                              goal_percent, Robot::BOARD_WIDTH, Robot::BOARD_HEIGHT);
+    let new_steps = find_tree(&mut working[..sample], goal_percent, width, height);
     // advance the other robots too
     for robot in working[sample..].iter_mut() {
-      robot.move_forward(new_steps, Robot::BOARD_WIDTH, Robot::BOARD_HEIGHT)
+      robot.move_forward(new_steps, width, height)
     }
     steps += new_steps;
   }
-  //display_robots(&working, Robot::BOARD_WIDTH, Robot::BOARD_HEIGHT);
+  //display_robots(&working, width, height);
   steps
 }
 
+pub fn part2(input: &[Robot]) -> usize {
+  part2_with(input, &params::current())
+}
+
 #[cfg(test)]
 mod tests {
-  use super::{generator, score};
+  use crate::params::Params;
+  use super::{generator, part1_with, score};
 
   const INPUT: &str =
 "p=0,4 v=3,-3
@@ -167,6 +236,8 @@ p=9,5 v=-3,-3";
     let mut robots = generator(INPUT).clone();
     robots.iter_mut().for_each(|r|
         r.move_forward(100, 11, 7));
-    assert_eq!(12, score(&robots, 11, 7))
+    assert_eq!(12, score(&robots, 11, 7));
+    let params = Params{width: 11, height: 7, ..Params::default()};
+    assert_eq!(12, part1_with(&generator(INPUT), &params));
   }
 }
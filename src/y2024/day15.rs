@@ -0,0 +1,301 @@
+use std::collections::VecDeque;
+use std::io::BufRead;
+use ahash::AHashSet;
+use itertools::Itertools;
+use crate::utils::grid::Grid as FloorGrid;
+use crate::utils::anim::{Cell, Color, FrameBuffer, Simulation, FLOOR, WALL};
+use crate::utils::parse::{from_reader, two_sections};
+
+pub const TITLE: &str = "Warehouse Woes";
+
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
+pub enum Side {
+  Left, Right, Both,
+}
+
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
+pub enum FloorKind {
+  Empty, Box(Side), Wall,
+}
+
+impl FloorKind {
+  /// Should this count a box for scoring?
+  fn is_box(self) -> bool {
+    matches!(self, FloorKind::Box(Side::Both) | FloorKind::Box(Side::Left))
+  }
+}
+
+type Position = u16;
+
+#[derive(Clone,Debug,Eq,Hash,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
+pub struct Coordinate {
+  y: Position,
+  x: Position,
+}
+
+impl Coordinate {
+  fn step(&self, direction: Direction) -> Coordinate {
+    match direction {
+      Direction::North => Coordinate{y: self.y - 1, x: self.x},
+      Direction::West => Coordinate{y: self.y, x: self.x - 1},
+      Direction::South => Coordinate{y: self.y + 1, x: self.x},
+      Direction::East => Coordinate{y: self.y, x: self.x + 1},
+    }
+  }
+}
+
+fn read_grid(input: &str) -> Result<(FloorGrid<FloorKind>, Coordinate), String> {
+  let chars = FloorGrid::from_str(input, Ok)?;
+  let (x, y) = chars.position(|&ch| ch == '@').ok_or("Can't find_guard")?;
+  let floor = chars.try_map(|&ch| match ch {
+    '#' => Ok(FloorKind::Wall),
+    '.' | '@' => Ok(FloorKind::Empty),
+    'O' => Ok(FloorKind::Box(Side::Both)),
+    _ => Err(format!("Invalid character '{}'", ch))})?;
+  Ok((floor, Coordinate{y: y as Position, x: x as Position}))
+}
+
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
+pub enum Direction {
+  North, West, South, East,
+}
+
+impl Direction {
+  fn from_char(ch: char) -> Result<Direction, String> {
+    match ch {
+      '<' => Ok(Direction::West),
+      '>' => Ok(Direction::East),
+      '^' => Ok(Direction::North),
+      'v' => Ok(Direction::South),
+      _ => Err(format!("Invalid direction '{ch}'")),
+    }
+  }
+}
+
+#[derive(Clone,Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
+pub struct Grid {
+  floor: FloorGrid<FloorKind>,
+  guard: Coordinate,
+}
+
+#[derive(Clone,Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
+pub struct Problem {
+  grid: Grid,
+  instructions: Vec<Direction>,
+}
+
+impl Grid {
+  /// Find the list of blocks to move. They should be moved in reverse order.
+  fn plan_move(&self, location: &Coordinate, direction: Direction) -> Option<Vec<Coordinate>> {
+    let mut result = Vec::with_capacity(20);
+    let mut pending = VecDeque::with_capacity(20);
+    let mut done = AHashSet::new();
+    pending.push_front(location.step(direction));
+    while let Some(location) = pending.pop_back() {
+      if done.insert(location.clone()) {
+        match self.floor.get(location.x, location.y) {
+          Some(FloorKind::Empty) => {}
+          Some(FloorKind::Box(side)) => {
+            match (direction, side) {
+              (Direction::East | Direction::West, _) | (_, Side::Both) => {},
+              (_, Side::Left) => {
+                let other = location.step(Direction::East);
+                if !done.contains(&other) {
+                  pending.push_back(other);
+                }
+              },
+              (_, Side::Right) => {
+                let other = location.step(Direction::West);
+                if !done.contains(&other) {
+                  pending.push_back(other);
+                }
+              },
+            }
+            pending.push_front(location.step(direction));
+            result.push(location);
+          }
+          _ => { return None }
+        }
+      }
+    }
+    Some(result)
+  }
+
+  fn perform(&mut self, instruction: Direction) {
+    if let Some(mut moving) = self.plan_move(&self.guard, instruction) {
+      while let Some(from) = moving.pop() {
+        let old_floor = *self.floor.get(from.x, from.y).unwrap();
+        let target = from.step(instruction);
+        *self.floor.get_mut(target.x, target.y).unwrap() = old_floor;
+        *self.floor.get_mut(from.x, from.y).unwrap() = FloorKind::Empty;
+      }
+      self.guard = self.guard.step(instruction);
+    }
+  }
+
+  fn perform_commands(&mut self, instructions: &[Direction]) {
+    for &instruction in instructions {
+      self.perform(instruction);
+    }
+  }
+
+  fn compute_gps(&self) -> usize {
+    self.floor.iter()
+        .filter(|(_, val)| val.is_box())
+        .map(|((x, y), _)| y * 100 + x)
+        .sum()
+  }
+
+  fn cells(&self) -> FloorGrid<Cell> {
+    let mut cells = self.floor.map(|val| match val {
+      FloorKind::Wall => WALL,
+      FloorKind::Empty => FLOOR,
+      FloorKind::Box(side) => Cell::new(match side {
+        Side::Both => 'O',
+        Side::Left => '[',
+        Side::Right => ']',
+      }, Color::Yellow),
+    });
+    cells[(self.guard.x as usize, self.guard.y as usize)] = Cell::new('@', Color::Red);
+    cells
+  }
+
+  /// The warehouse as text with the robot as '@', without the colors.
+  pub fn display(&self) -> String {
+    self.cells().display(|cell| cell.symbol)
+  }
+
+  fn double_width(&self) -> Self {
+    let mut floor = FloorGrid::filled(self.floor.width() * 2, self.floor.height(),
+                                      FloorKind::Empty);
+    for ((x, y), spot) in self.floor.iter() {
+      match spot {
+        FloorKind::Wall => {
+          floor[(2 * x, y)] = FloorKind::Wall;
+          floor[(2 * x + 1, y)] = FloorKind::Wall;
+        },
+        FloorKind::Box(_) => {
+          floor[(2 * x, y)] = FloorKind::Box(Side::Left);
+          floor[(2 * x + 1, y)] = FloorKind::Box(Side::Right);
+        }
+        _ => {}
+      }
+    }
+    let guard = Coordinate{y: self.guard.y, x: self.guard.x * 2};
+    Grid{floor, guard}
+  }
+}
+
+pub fn try_generator(input: &str) -> Result<Problem, String> {
+  let (grid_str, instructions) = two_sections(input)?;
+  let (floor, guard) = read_grid(grid_str)?;
+  let instructions = instructions.chars().filter(|ch| !ch.is_whitespace())
+      .map(Direction::from_char).try_collect()?;
+  Ok(Problem{ grid: Grid{floor, guard}, instructions})
+}
+
+pub fn generator(input: &str) -> Problem {
+  try_generator(input).expect("Can't parse input")
+}
+
+pub fn generator_from_reader(reader: impl BufRead) -> Result<Problem, String> {
+  from_reader(reader, try_generator)
+}
+
+pub fn part1(input: &Problem) -> usize {
+  let mut state = input.grid.clone();
+  state.perform_commands(&input.instructions);
+  state.compute_gps()
+}
+
+pub fn part2(input: &Problem) -> usize {
+  let mut state = input.grid.double_width();
+  state.perform_commands(&input.instructions);
+  state.compute_gps()
+}
+
+/// Moves the robot through the wide warehouse of part 2 one instruction at
+/// a time for the visualizer.
+pub struct Warehouse {
+  grid: Grid,
+  instructions: Vec<Direction>,
+  done: usize,
+}
+
+impl Warehouse {
+  pub fn new(problem: &Problem) -> Self {
+    Warehouse{grid: problem.grid.double_width(), instructions: problem.instructions.clone(),
+      done: 0}
+  }
+}
+
+impl Simulation for Warehouse {
+  fn title(&self) -> String {
+    format!("Day 15: {TITLE}")
+  }
+
+  fn state(&self) -> String {
+    let next = self.instructions.get(self.done)
+        .map_or("none".to_string(), |instruction| format!("{instruction:?}"));
+    format!("robot at {},{}, move {}/{}, next {next}", self.grid.guard.x, self.grid.guard.y,
+            self.done, self.instructions.len())
+  }
+
+  fn render(&self) -> FrameBuffer {
+    FrameBuffer{cells: self.grid.cells(),
+      status: format!("Moves: {}/{}, GPS: {}", self.done, self.instructions.len(),
+                      self.grid.compute_gps())}
+  }
+
+  fn step(&mut self) -> bool {
+    match self.instructions.get(self.done) {
+      Some(&instruction) => {
+        self.grid.perform(instruction);
+        self.done += 1;
+        true
+      }
+      None => false,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::utils::anim::Simulation;
+  use super::{generator, Warehouse};
+
+  const INPUT: &str = include_str!("../../tests/data/day15/example.txt");
+
+  const BIGGER: &str = include_str!("../../tests/data/day15/example2.txt");
+
+  #[test]
+  fn test_display() {
+    let problem = generator(INPUT);
+    let mut grid = problem.grid.clone();
+    let mut states = vec![grid.display()];
+    for moves in problem.instructions.chunks(5) {
+      grid.perform_commands(moves);
+      states.push(grid.display());
+    }
+    insta::assert_snapshot!("small_moves", states.join("\n"));
+  }
+
+  #[test]
+  fn test_warehouse() {
+    let mut warehouse = Warehouse::new(&generator(BIGGER));
+    let mut frames = vec![warehouse.render().to_text()];
+    for _ in 0..3 {
+      for _ in 0..100 {
+        warehouse.step();
+      }
+      frames.push(warehouse.render().to_text());
+    }
+    insta::assert_snapshot!("wide_warehouse", frames.join("\n"));
+  }
+}
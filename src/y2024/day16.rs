@@ -0,0 +1,750 @@
+use std::fmt::Write;
+use std::io::{self, BufRead};
+use std::str::FromStr;
+use array2d::Array2D;
+use itertools::Itertools;
+use crate::utils::anim::PATH;
+#[cfg(feature = "arena")]
+use crate::utils::arena;
+use crate::utils::graph::Dijkstra;
+use crate::utils::grid::{to_index, Grid as FloorGrid};
+use crate::utils::maze::{Maze, Point, Tile};
+use crate::utils::render::svg::{self, Style};
+use crate::utils::parse::from_reader;
+use smallvec::SmallVec;
+#[cfg(feature = "arena")]
+use bumpalo::{collections::Vec as BumpVec, Bump};
+use tracing::debug;
+
+pub const TITLE: &str = "Reindeer Maze";
+
+#[derive(Clone,Copy,Debug,Default,Eq,Hash,Ord,PartialEq,PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
+pub enum Direction {
+  #[default] North, West, South, East,
+}
+
+impl Direction {
+  const ALL: [Direction; 4] = [Direction::North, Direction::West, Direction::South,
+    Direction::East];
+
+  fn opposite(self) -> Direction {
+    match self {
+      Direction::North => Direction::South,
+      Direction::West => Direction::East,
+      Direction::South => Direction::North,
+      Direction::East => Direction::West,
+    }
+  }
+
+  fn step(self, place: Coordinate) -> Coordinate {
+    match self {
+      Direction::North => Coordinate{y: place.y - 1, x: place.x},
+      Direction::West => Coordinate{y: place.y, x: place.x - 1},
+      Direction::South => Coordinate{y: place.y + 1, x: place.x},
+      Direction::East => Coordinate{y: place.y, x: place.x + 1},
+    }
+  }
+}
+
+pub type Coordinate = Point;
+
+#[derive(Clone,Copy,Debug,Eq,Ord,PartialEq,PartialOrd)]
+struct PositionedDirection {
+  direction: Direction,
+  place: Coordinate,
+}
+
+type NeighborList = SmallVec<[PositionedDirection; 4]>;
+
+type Cost = u64;
+
+#[derive(Clone,Debug,Eq,Ord,PartialEq,PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
+pub struct CostComponents {
+  turns: u64,
+  steps: u64,
+}
+
+impl CostComponents {
+  pub fn turns(&self) -> u64 {
+    self.turns
+  }
+
+  pub fn steps(&self) -> u64 {
+    self.steps
+  }
+
+  pub fn cost(&self, costs: &Costs) -> Cost {
+    costs.walk * self.steps + costs.turn * self.turns
+  }
+}
+
+/// The price of each step and each 90 degree turn the reindeer makes.
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+pub struct Costs {
+  pub walk: Cost,
+  pub turn: Cost,
+}
+
+impl Default for Costs {
+  fn default() -> Self {
+    Costs{walk: 1, turn: 1000}
+  }
+}
+
+
+/// The maze, which may have several starts and ends.
+#[derive(Clone,Debug)]
+pub struct Grid {
+  maze: Maze,
+}
+
+impl FromStr for Grid {
+  type Err = String;
+
+  fn from_str(input: &str) -> Result<Self, String> {
+    let maze = Maze::from_str(input)?;
+    // the walks assume that they can't step off of the floor
+    let (width, height) = (maze.width(), maze.height());
+    if maze.tiles().iter().any(|((x, y), &tile)| tile.is_open() &&
+        (x == 0 || y == 0 || x + 1 == width || y + 1 == height)) {
+      return Err("The maze isn't surrounded by walls".to_string());
+    }
+    Ok(Grid{maze})
+  }
+}
+
+impl Grid {
+  /// The maze as text with the tiles on any of the best paths as 'O'.
+  pub fn display(&self, costs: &Costs) -> String {
+    let (tiles, _) = best_tiles(self, costs);
+    let mut cells = self.maze.cells();
+    for tile in tiles {
+      if self.get(tile) == Tile::Empty {
+        cells[(to_index(tile.x), to_index(tile.y))] = PATH;
+      }
+    }
+    cells.display(|cell| cell.symbol)
+  }
+
+  #[inline]
+  fn get(&self, position: Coordinate) -> Tile {
+    self.maze.tiles()[(to_index(position.x), to_index(position.y))]
+  }
+
+  fn find_neighbors(&self, place: Coordinate) -> NeighborList {
+    [Direction::North, Direction::South, Direction::East, Direction::West].iter()
+        .map(|direction| PositionedDirection{direction: *direction,
+          place: direction.step(place)})
+        .filter(|n| self.get(n.place).is_open())
+        .collect()
+  }
+
+  /// The intersection id of each location in row-major order, or None for
+  /// the walls and corridors. Every start and exit is also given an id.
+  fn intersection_ids(&self) -> impl Iterator<Item=Option<usize>> + '_ {
+    let mut next_id: usize = 0;
+    self.maze.tiles().iter()
+        .map(move |((x, y), spot)| match spot {
+          Tile::Empty if self.find_neighbors(Coordinate::new(y, x)).len() <= 2 => None,
+          Tile::Wall => None,
+          _ => {
+            next_id += 1;
+            Some(next_id - 1)
+          }
+        })
+  }
+
+  /// Contract the maze to its intersections, which are the graph's nodes.
+  /// Returns the node id of each location along with the number of nodes.
+  pub fn find_intersections(&self) -> (FloorGrid<Option<usize>>, usize) {
+    let result: Vec<Option<usize>> = self.intersection_ids().collect();
+    let node_count = result.iter().flatten().count();
+    let result = FloorGrid::new(self.maze.width(), self.maze.height(), result)
+        .expect("same size as the floor");
+    (result, node_count)
+  }
+
+  /// Add the corridors out of the location to the graph in both directions,
+  /// unless it was already visited, and push the intersections they reach.
+  fn explore(&self, current: Coordinate, node_id: &impl Fn(&Coordinate) -> usize,
+             nodes: &mut [EdgeList], visited: &mut [bool], pending: &mut impl Extend<Coordinate>) {
+    let node = node_id(&current);
+    if visited[node] {
+      return;
+    }
+    visited[node] = true;
+    for neighbor in self.find_neighbors(current) {
+      if let Some((dest, cost)) = self.walk(neighbor) {
+        let dest_node = node_id(&dest.place);
+        if !visited[dest_node] {
+          pending.extend([dest.place]);
+          nodes[node].push(Edge{start_direction: neighbor.direction,
+            destination: dest_node, destination_direction: dest.direction, cost: cost.clone()});
+          nodes[dest_node].push(Edge{start_direction: dest.direction.opposite(),
+            destination: node, destination_direction: neighbor.direction.opposite(), cost});
+        }
+      }
+    }
+  }
+
+  /// Calculate the result and cost of taking the given path.
+  fn walk(&self, start: PositionedDirection) -> Option<(PositionedDirection, CostComponents)> {
+    let mut current = start;
+    let mut cost = CostComponents{turns: 0, steps: 1};
+    loop {
+      // exit if we reach the start or end
+      if matches!(self.get(current.place), Tile::Start | Tile::End) { break }
+      let mut neighbors = self.find_neighbors(current.place);
+      // don't turn around
+      neighbors.retain(|n| n.direction != current.direction.opposite());
+      match neighbors.len() {
+        0 => { return None },
+        1 => {
+          let next = neighbors.pop().unwrap();
+          if next.direction != current.direction {
+            cost.turns += 1;
+          }
+          cost.steps += 1;
+          current = next;
+        }
+        _ => { break },
+      }
+    }
+    Some((current, cost))
+  }
+}
+
+pub fn try_generator(input: &str) -> Result<Graph, String> {
+  let grid = Grid::from_str(input)?;
+  #[cfg(feature = "arena")]
+  let graph = arena::scoped(|arena| Graph::from_grid_in(&grid, arena));
+  #[cfg(not(feature = "arena"))]
+  let graph = Graph::from_grid(&grid);
+  debug!(nodes = graph.nodes.len(), edges = graph.nodes.iter().map(|edges| edges.len()).sum::<usize>(),
+         "graph built");
+  Ok(graph)
+}
+
+pub fn generator(input: &str) -> Graph {
+  try_generator(input).expect("Can't parse input")
+}
+
+pub fn generator_from_reader(reader: impl BufRead) -> Result<Graph, String> {
+  from_reader(reader, try_generator)
+}
+
+/// A corridor between two intersections of the maze.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
+pub struct Edge {
+  start_direction: Direction,
+  destination: usize,
+  destination_direction: Direction,
+  cost: CostComponents,
+}
+
+impl Edge {
+  /// The direction the reindeer faces when it leaves the source node.
+  pub fn start_direction(&self) -> Direction {
+    self.start_direction
+  }
+
+  pub fn destination(&self) -> usize {
+    self.destination
+  }
+
+  /// The direction the reindeer faces when it arrives at the destination.
+  pub fn destination_direction(&self) -> Direction {
+    self.destination_direction
+  }
+
+  pub fn cost(&self) -> &CostComponents {
+    &self.cost
+  }
+}
+
+type EdgeList = SmallVec<[Edge; 4]>;
+
+/// The maze with each corridor contracted into a single edge between
+/// the intersections, starts, and ends.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
+pub struct Graph {
+  nodes: Vec<EdgeList>,
+  starts: Vec<usize>,
+  ends: Vec<usize>,
+}
+
+impl Graph {
+  pub fn from_grid(grid: &Grid) -> Graph {
+    let (intersections, node_count) = grid.find_intersections();
+    let node_id = |c: &Coordinate| intersections[(to_index(c.x), to_index(c.y))].unwrap();
+    let mut nodes: Vec<EdgeList> = (0..node_count).map(|_| SmallVec::new()).collect();
+    let mut pending = grid.maze.starts().to_vec();
+    let mut visited = vec![false; node_count];
+    while let Some(current) = pending.pop() {
+      grid.explore(current, &node_id, &mut nodes, &mut visited, &mut pending);
+    }
+    Graph{nodes, starts: grid.maze.starts().iter().map(node_id).collect(),
+      ends: grid.maze.ends().iter().map(node_id).collect()}
+  }
+
+  /// Build the graph like from_grid, but with the intersection ids and the
+  /// search's stack and visited flags in the arena.
+  #[cfg(feature = "arena")]
+  pub fn from_grid_in(grid: &Grid, arena: &Bump) -> Graph {
+    let ids = BumpVec::from_iter_in(grid.intersection_ids(), arena);
+    let node_count = ids.iter().flatten().count();
+    let width = grid.maze.width();
+    let node_id = |c: &Coordinate| ids[to_index(c.y) * width + to_index(c.x)].unwrap();
+    let mut nodes: Vec<EdgeList> = (0..node_count).map(|_| SmallVec::new()).collect();
+    let mut pending = BumpVec::from_iter_in(grid.maze.starts().iter().copied(), arena);
+    let visited = arena.alloc_slice_fill_copy(node_count, false);
+    while let Some(current) = pending.pop() {
+      grid.explore(current, &node_id, &mut nodes, visited, &mut pending);
+    }
+    Graph{nodes, starts: grid.maze.starts().iter().map(node_id).collect(),
+      ends: grid.maze.ends().iter().map(node_id).collect()}
+  }
+
+  pub fn node_count(&self) -> usize {
+    self.nodes.len()
+  }
+
+  pub fn edges(&self, node: usize) -> &[Edge] {
+    &self.nodes[node]
+  }
+
+  pub fn starts(&self) -> &[usize] {
+    &self.starts
+  }
+
+  pub fn ends(&self) -> &[usize] {
+    &self.ends
+  }
+
+  /// Render the graph in graphviz's dot format.
+  pub fn to_dot(&self) -> String {
+    let mut result = String::from("digraph maze {\n");
+    for id in 0..self.nodes.len() {
+      let kind = if self.starts.contains(&id) {
+        " (start)"
+      } else if self.ends.contains(&id) {
+        " (end)"
+      } else {
+        ""
+      };
+      writeln!(result, "  {id} [label=\"{id}{kind}\"];").unwrap();
+    }
+    for (id, node) in self.nodes.iter().enumerate() {
+      for edge in node {
+        writeln!(result, "  {id} -> {} [label=\"{:?} to {:?}: {} steps, {} turns\"];",
+                 edge.destination, edge.start_direction, edge.destination_direction,
+                 edge.cost.steps, edge.cost.turns).unwrap();
+      }
+    }
+    result.push_str("}\n");
+    result
+  }
+
+  #[allow(dead_code)]
+  fn display(&self) {
+    for (id, node) in self.nodes.iter().enumerate() {
+      println!("Node {id}:");
+      for neighbor in node {
+        println!("   {neighbor:?}");
+      }
+    }
+  }
+
+  /// The states reachable by leaving the state along one of its edges and
+  /// what each of them costs.
+  fn forward_moves(&self, costs: &Costs, (node, direction): State) -> MoveList {
+    self.nodes[node].iter().map(|edge| {
+      let mut cost = edge.cost.cost(costs);
+      if edge.start_direction != direction {
+        cost += costs.turn;
+      }
+      ((edge.destination, edge.destination_direction), cost)
+    }).collect()
+  }
+
+  /// The backward search's states are the direction the reindeer leaves the
+  /// node in rather than the direction it arrived facing. Find the states that
+  /// lead to the state along a single edge. Since each edge is stored
+  /// in both directions, the edges that arrive at this node are the reverse of
+  /// the edges that leave it.
+  fn backward_moves(&self, costs: &Costs, (node, direction): State) -> MoveList {
+    self.nodes[node].iter().map(|reverse| {
+      let mut cost = reverse.cost.cost(costs);
+      if reverse.start_direction.opposite() != direction {
+        cost += costs.turn;
+      }
+      ((reverse.destination, reverse.destination_direction.opposite()), cost)
+    }).collect()
+  }
+
+  fn start_states(&self) -> Vec<State> {
+    self.starts.iter().map(|&node| (node, Direction::East)).collect()
+  }
+
+  fn end_states(&self) -> Vec<State> {
+    self.ends.iter()
+        .flat_map(|&node| Direction::ALL.iter().map(move |&direction| (node, direction)))
+        .collect()
+  }
+
+  /// Put the costs that a search found into a table by node and direction.
+  fn cost_table(&self, search: &Dijkstra<State, Cost>) -> Array2D<Cost> {
+    let mut result = Array2D::filled_with(Cost::MAX, self.nodes.len(), 4);
+    for node in 0..self.nodes.len() {
+      for direction in Direction::ALL {
+        result[(node, direction as usize)] = search_cost(search, node, direction);
+      }
+    }
+    result
+  }
+
+  /// Find the minimum cost from a start to each node and direction.
+  pub fn minimum_cost(&self, costs: &Costs) -> Array2D<Cost> {
+    let mut search = Dijkstra::new(self.start_states());
+    search.run(|state| self.forward_moves(costs, state), |_| false);
+    debug!(states = search.len(), "forward Dijkstra finished");
+    self.cost_table(&search)
+  }
+
+  /// Find the minimum cost from each node and direction to an exit.
+  pub fn cost_to_exit(&self, costs: &Costs) -> Array2D<Cost> {
+    let mut search = Dijkstra::new(self.end_states());
+    search.run(|state| self.backward_moves(costs, state), |_| false);
+    debug!(states = search.len(), "backward Dijkstra finished");
+    let mut result = Array2D::filled_with(Cost::MAX, self.nodes.len(), 4);
+    for node in 0..self.nodes.len() {
+      for direction in Direction::ALL {
+        result[(node, direction as usize)] =
+            turn_to(|n, d| search_cost(&search, n, d), costs, node, direction);
+      }
+    }
+    result
+  }
+
+  /// Find the cheapest way out by searching forward from the starts and
+  /// backward from the exits until the two searches meet.
+  pub fn bidirectional_cost(&self, costs: &Costs) -> Cost {
+    let mut forward: Dijkstra<State, Cost> = Dijkstra::new(self.start_states());
+    let mut backward: Dijkstra<State, Cost> = Dijkstra::new(self.end_states());
+    let mut best = Cost::MAX;
+    while let (Some(forward_top), Some(backward_top)) = (forward.peek(), backward.peek()) {
+      if forward_top.saturating_add(backward_top) >= best {
+        break;
+      }
+      if forward_top <= backward_top {
+        forward.step(|state| self.forward_moves(costs, state),
+                     |state, cost| best = best.min(meeting_cost(&backward, costs, state, cost)));
+      } else {
+        backward.step(|state| self.backward_moves(costs, state),
+                      |state, cost| best = best.min(meeting_cost(&forward, costs, state, cost)));
+      }
+    }
+    debug!(forward = forward.len(), backward = backward.len(), best,
+           "bidirectional Dijkstra finished");
+    best
+  }
+}
+
+/// A node in the graph and the direction the reindeer is facing.
+type State = (usize, Direction);
+
+type MoveList = SmallVec<[(State, Cost); 16]>;
+
+/// The cost that the search found for the node and direction.
+fn search_cost(search: &Dijkstra<State, Cost>, node: usize, direction: Direction) -> Cost {
+  search.cost(&(node, direction)).unwrap_or(Cost::MAX)
+}
+
+/// The total cost when one search reaches a state that the other search has
+/// seen. The forward states are the direction the reindeer is facing and the
+/// backward states are the direction it leaves in, so they meet by turning.
+fn meeting_cost(other: &Dijkstra<State, Cost>, costs: &Costs, (node, direction): State,
+                cost: Cost) -> Cost {
+  cost.saturating_add(turn_to(|n, d| search_cost(other, n, d), costs, node, direction))
+}
+
+#[allow(dead_code)]
+fn display_intersections(grid: &Grid) {
+  let (intersections, _) = grid.find_intersections();
+  for (y, row) in intersections.rows().enumerate() {
+    for (x, int) in row.iter().enumerate() {
+      if let Some(i) = int {
+        print!("{i:2}");
+      } else {
+        match grid.get(Coordinate::new(y, x)) {
+          Tile::Wall => print!("##"),
+          _ => print!(".."),
+        }
+      }
+    }
+    println!();
+  }
+}
+
+/// The cheapest cost at the node once the reindeer has turned to the given direction.
+fn turn_to(cost: impl Fn(usize, Direction) -> Cost, costs: &Costs, node: usize,
+           direction: Direction) -> Cost {
+  Direction::ALL.iter()
+      .map(|&facing| {
+        let cost = cost(node, facing);
+        if facing == direction { cost } else { cost.saturating_add(costs.turn) }
+      }).min().unwrap()
+}
+
+fn min_cost(cost: &Array2D<Cost>, node: usize) -> Cost {
+  *cost.row_iter(node).unwrap().min().unwrap()
+}
+
+/// Find the cheapest cost to any of the exits.
+fn best_exit_cost(graph: &Graph, cost: &Array2D<Cost>) -> Cost {
+  graph.ends.iter().map(|&end| min_cost(cost, end)).min().unwrap()
+}
+
+pub fn do_part1(graph: &Graph, costs: &Costs) -> u64 {
+  graph.bidirectional_cost(costs)
+}
+
+pub fn part1(graph: &Graph) -> u64 {
+  do_part1(graph, &Costs::default())
+}
+
+pub fn do_part2(graph: &Graph, costs: &Costs) -> u64 {
+  let from_start = graph.minimum_cost(costs);
+  let to_exit = graph.cost_to_exit(costs);
+  let final_cost = best_exit_cost(graph, &from_start);
+  if final_cost == Cost::MAX {
+    return 0;
+  }
+  let mut node_used = vec![false; graph.nodes.len()];
+  // Each corridor is marked from the end with the lower node and direction,
+  // so that it is only counted once even if the best paths use it both ways.
+  let mut corridor_used = Array2D::filled_with(false, graph.nodes.len(), 4);
+  let mut spaces = 0;
+  for (node, edges) in graph.nodes.iter().enumerate() {
+    for edge in edges {
+      let total = turn_to(|n, d| from_start[(n, d as usize)], costs, node,
+                          edge.start_direction)
+          .saturating_add(edge.cost.cost(costs))
+          .saturating_add(to_exit[(edge.destination, edge.destination_direction as usize)]);
+      if total == final_cost {
+        node_used[node] = true;
+        node_used[edge.destination] = true;
+        let corridor = (node, edge.start_direction)
+            .min((edge.destination, edge.destination_direction.opposite()));
+        if !corridor_used[(corridor.0, corridor.1 as usize)] {
+          corridor_used[(corridor.0, corridor.1 as usize)] = true;
+          spaces += edge.cost.steps - 1;
+        }
+      }
+    }
+  }
+  spaces + node_used.iter().filter(|&&used| used).count() as u64
+}
+
+pub fn part2(graph: &Graph) -> u64 {
+  do_part2(graph, &Costs::default())
+}
+
+/// Write the best cost from a start to each node of the graph.
+pub fn explain(graph: &Graph, out: &mut dyn io::Write) -> io::Result<()> {
+  let from_start = graph.minimum_cost(&Costs::default());
+  for node in 0..graph.nodes.len() {
+    let kind = if graph.starts.contains(&node) {
+      " (start)"
+    } else if graph.ends.contains(&node) {
+      " (end)"
+    } else {
+      ""
+    };
+    match min_cost(&from_start, node) {
+      Cost::MAX => writeln!(out, "Node {node}{kind}: unreachable")?,
+      cost => writeln!(out, "Node {node}{kind}: {cost}")?,
+    }
+  }
+  Ok(())
+}
+
+/// A tile of the maze and the direction the reindeer is facing.
+type TileState = (Coordinate, Direction);
+
+/// The moves from a tile: a step forward if it is open and a turn to either
+/// side. Going backward is a step from the tile behind instead.
+fn tile_moves(grid: &Grid, costs: &Costs, (place, facing): TileState,
+              backward: bool) -> SmallVec<[(TileState, Cost); 3]> {
+  let mut result: SmallVec<[(TileState, Cost); 3]> = Direction::ALL.iter()
+      .filter(|&&turn| turn != facing && turn != facing.opposite())
+      .map(|&turn| ((place, turn), costs.turn))
+      .collect();
+  let next = if backward { facing.opposite() } else { facing }.step(place);
+  if grid.get(next).is_open() {
+    result.push(((next, facing), costs.walk));
+  }
+  result
+}
+
+/// Find the tiles on any of the best paths through the maze, along with one
+/// of those paths. This works on the tiles rather than the graph so that the
+/// paths can be drawn.
+fn best_tiles(grid: &Grid, costs: &Costs) -> (Vec<Coordinate>, Vec<Coordinate>) {
+  let mut forward = Dijkstra::new(grid.maze.starts().iter().map(|&start| (start, Direction::East)));
+  forward.run(|state| tile_moves(grid, costs, state, false), |_| false);
+  let ends = grid.maze.ends().iter()
+      .flat_map(|&end| Direction::ALL.iter().map(move |&facing| (end, facing)))
+      .collect::<Vec<_>>();
+  let Some(best) = ends.iter().filter(|state| forward.cost(state).is_some())
+      .min_by_key(|state| forward.cost(state)) else {
+    return (Vec::new(), Vec::new());
+  };
+  let best_cost = forward.cost(best).unwrap();
+  let path = forward.path_to(*best).into_iter().map(|(place, _)| place).dedup().collect();
+  let mut backward = Dijkstra::new(ends.iter().copied());
+  backward.run(|state| tile_moves(grid, costs, state, true), |_| false);
+  let tiles = grid.maze.tiles().iter()
+      .map(|((x, y), _)| Coordinate::new(y, x))
+      .filter(|&place| Direction::ALL.iter().any(|&facing| {
+        let state = (place, facing);
+        forward.cost(&state).zip(backward.cost(&state))
+            .is_some_and(|(there, back)| there + back == best_cost)
+      }))
+      .collect();
+  (tiles, path)
+}
+
+/// Draw the maze with the tiles on the best paths shaded and one of those
+/// paths as a line.
+pub fn to_svg(input: &str) -> Result<String, String> {
+  let grid = Grid::from_str(input)?;
+  let (tiles, path) = best_tiles(&grid, &Costs::default());
+  let mut shading = grid.maze.tiles().map(|&spot| match spot {
+    Tile::Wall => Style::fill("#404040"),
+    Tile::Start | Tile::End => Style::fill("#d04040"),
+    Tile::Empty => Style::empty(),
+  });
+  for tile in tiles {
+    if grid.get(tile) == Tile::Empty {
+      shading[(to_index(tile.x), to_index(tile.y))] = Style::fill("#f0d060");
+    }
+  }
+  let path = path.iter().map(|place| (to_index(place.x), to_index(place.y))).collect();
+  Ok(svg::render(&shading, Style::clone, &[svg::Path::new(path, "#3060c0")]))
+}
+
+#[cfg(test)]
+mod tests {
+  use proptest::prelude::*;
+  use crate::testgen;
+  use std::str::FromStr;
+  use super::{best_exit_cost, best_tiles, generator, do_part1, do_part2, part1, part2, to_svg,
+              Costs, Direction, Grid};
+
+  const INPUT: &str = include_str!("../../tests/data/day16/example.txt");
+
+  const BIGGER: &str = include_str!("../../tests/data/day16/example2.txt");
+
+  #[test]
+  fn test_best_tiles() {
+    for (input, tiles, steps) in [(INPUT, 45, 37), (BIGGER, 64, 49)] {
+      let (best, path) = best_tiles(&Grid::from_str(input).unwrap(), &Costs::default());
+      assert_eq!(tiles, best.len());
+      assert_eq!(steps, path.len());
+      assert!(path.iter().all(|tile| best.contains(tile)));
+    }
+    assert_eq!(1, to_svg(INPUT).unwrap().matches("<polyline").count());
+  }
+
+  #[test]
+  fn test_display() {
+    let costs = Costs::default();
+    insta::assert_snapshot!("best_paths", [INPUT, BIGGER].iter()
+        .map(|input| Grid::from_str(input).unwrap().display(&costs)).collect::<Vec<_>>()
+        .join("\n"));
+  }
+
+  #[test]
+  fn test_bidirectional() {
+    let multiple_ends = include_str!("../../tests/data/day16/multiple_ends.txt");
+    for input in [INPUT, BIGGER, multiple_ends] {
+      let graph = generator(input);
+      for costs in [Costs::default(), Costs{walk: 1, turn: 0}, Costs{walk: 3, turn: 7}] {
+        let best = best_exit_cost(&graph, &graph.minimum_cost(&costs));
+        assert_eq!(best, graph.bidirectional_cost(&costs));
+        let to_exit = graph.cost_to_exit(&costs);
+        assert_eq!(best, graph.starts().iter()
+            .map(|&start| to_exit[(start, Direction::East as usize)]).min().unwrap());
+      }
+    }
+  }
+
+  #[test]
+  fn test_find_intersections() {
+    let input = "#######\n#...#E#\n#.#.#.#\n#S....#\n#######";
+    let (intersections, node_count) = Grid::from_str(input).unwrap().find_intersections();
+    assert_eq!(3, node_count);
+    let nodes: Vec<_> = intersections.iter()
+        .filter_map(|(place, id)| id.map(|id| (place, id))).collect();
+    // the exit, the start, and the only fork, with the corridors between them contracted
+    assert_eq!(vec![((5, 1), 0), ((1, 3), 1), ((3, 3), 2)], nodes);
+    assert_eq!(node_count, generator(input).node_count());
+  }
+
+  #[test]
+  fn test_free_turns() {
+    let costs = Costs{walk: 1, turn: 0};
+    let data = generator(INPUT);
+    assert_eq!(28, do_part1(&data, &costs));
+    assert_eq!(37, do_part2(&data, &costs));
+    let data = generator(BIGGER);
+    assert_eq!(40, do_part1(&data, &costs));
+    assert_eq!(41, do_part2(&data, &costs));
+  }
+
+  #[cfg(feature = "arena")]
+  #[test]
+  fn test_from_grid_in() {
+    let arena = bumpalo::Bump::new();
+    for input in [INPUT, BIGGER] {
+      let grid = Grid::from_str(input).unwrap();
+      assert_eq!(super::Graph::from_grid(&grid).to_dot(),
+                 super::Graph::from_grid_in(&grid, &arena).to_dot());
+    }
+  }
+
+  #[test]
+  fn test_to_dot() {
+    let data = generator("#####\n#S.E#\n#####");
+    assert_eq!("digraph maze {
+  0 [label=\"0 (start)\"];
+  1 [label=\"1 (end)\"];
+  0 -> 1 [label=\"East to East: 2 steps, 0 turns\"];
+  1 -> 0 [label=\"West to West: 2 steps, 0 turns\"];
+}
+", data.to_dot());
+  }
+
+  proptest! {
+    #[test]
+    fn test_random_mazes(input in testgen::maze(15, 30)) {
+      let graph = generator(&input);
+      let costs = Costs::default();
+      let best = part1(&graph);
+      // the bidirectional search agrees with the searches from either end
+      prop_assert_eq!(best_exit_cost(&graph, &graph.minimum_cost(&costs)), best);
+      let to_exit = graph.cost_to_exit(&costs);
+      prop_assert_eq!(graph.starts().iter()
+                          .map(|&start| to_exit[(start, Direction::East as usize)])
+                          .min().unwrap(), best);
+      // every best route includes at least the start and the end
+      let tiles = part2(&graph);
+      prop_assert!(if best == u64::MAX { tiles == 0 } else { tiles >= 2 }, "{tiles}");
+    }
+  }
+}
@@ -0,0 +1,970 @@
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
+use std::io::{BufRead, Write};
+use itertools::Itertools;
+use smallvec::SmallVec;
+use crate::utils::parse::{from_reader, parse_int, split_pair, two_sections};
+
+pub const TITLE: &str = "Chronospatial Computer";
+
+pub mod symbolic;
+
+type DataValue = u64;
+
+/// Which instruction set the program uses.
+#[derive(Clone,Copy,Debug,Default,Eq,PartialEq)]
+pub enum IsaVersion {
+  /// The puzzle's 3-bit instructions with registers A, B, and C.
+  #[default]
+  Standard,
+  /// Byte-wide literals, the add and ldi instructions, and combo operands
+  /// that read up to 252 registers. The registers past C can only be
+  /// read or set from the input, since add always writes A, ldi always
+  /// writes B, and the other instructions write A, B, or C.
+  Extended,
+}
+
+impl IsaVersion {
+  /// The number of registers that a combo operand can name, which are the
+  /// values of its byte after the four literals.
+  pub fn registers(self) -> usize {
+    match self {
+      IsaVersion::Standard => 3,
+      IsaVersion::Extended => u8::MAX as usize + 1 - 4,
+    }
+  }
+}
+
+/// A register, which are named A, B, C, and so on.
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
+pub struct RegisterName(usize);
+
+impl RegisterName {
+  pub const A: RegisterName = RegisterName(0);
+  pub const B: RegisterName = RegisterName(1);
+  pub const C: RegisterName = RegisterName(2);
+
+  pub fn index(self) -> usize {
+    self.0
+  }
+
+  fn from_str(s: &str) -> Result<RegisterName, String> {
+    match s.as_bytes() {
+      [ch @ b'A'..=b'Z'] => Ok(RegisterName((ch - b'A') as usize)),
+      [ch @ b'a'..=b'z'] => Ok(RegisterName((ch - b'a') as usize)),
+      [b'R' | b'r', digits @ ..] if !digits.is_empty() => s[1..].parse()
+          .map(RegisterName).map_err(|_| format!("Unknown register '{s}'")),
+      _ => Err(format!("Unknown register '{s}'")),
+    }
+  }
+}
+
+impl Display for RegisterName {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self.0 {
+      0..26 => write!(f, "{}", (b'A' + self.0 as u8) as char),
+      _ => write!(f, "R{}", self.0),
+    }
+  }
+}
+
+type Registers = SmallVec<[DataValue; 4]>;
+
+#[derive(Clone,Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
+pub struct State {
+  registers: Registers,
+  pc: usize,
+  output: Vec<u8>,
+}
+
+impl State {
+  /// Create a state with the given register values. There are always at
+  /// least the A, B, and C registers.
+  pub fn new(registers: &[DataValue]) -> State {
+    let mut registers = Registers::from_slice(registers);
+    if registers.len() < 3 {
+      registers.resize(3, 0);
+    }
+    State{registers, pc: 0, output: Vec::new()}
+  }
+
+  /// Get the value of a register. Registers that don't exist are zero.
+  fn get(&self, name: RegisterName) -> DataValue {
+    self.registers.get(name.index()).copied().unwrap_or(0)
+  }
+}
+
+/// Write each of the registers in octal.
+fn write_registers(f: &mut Formatter<'_>, registers: &[DataValue]) -> std::fmt::Result {
+  for (i, value) in registers.iter().enumerate() {
+    write!(f, "{}{}: {value:o}", if i == 0 { "" } else { ", " }, RegisterName(i))?;
+  }
+  Ok(())
+}
+
+impl Display for State {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write_registers(f, &self.registers)?;
+    write!(f, ", PC: {:o}, Outputs: {:?}", self.pc, self.output)
+  }
+}
+
+#[derive(Clone,Copy,Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
+pub enum Operation {
+  Adv(RegisterName),
+  Xor(RegisterName),
+  Jnz,
+  Out,
+  St(RegisterName),
+  Add(RegisterName),
+  Ldi(RegisterName),
+}
+
+#[derive(Clone,Copy,Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
+pub enum Operand {
+  Literal(DataValue),
+  Register(RegisterName),
+}
+
+impl Operand {
+  fn literal_from_byte(b: u8, isa: IsaVersion) -> Operand {
+    match isa {
+      IsaVersion::Standard => Operand::Literal((b % 8) as DataValue),
+      IsaVersion::Extended => Operand::Literal(b as DataValue),
+    }
+  }
+
+  fn combo_from_byte(b: u8, isa: IsaVersion) -> Result<Operand, String> {
+    match (isa, b) {
+      (IsaVersion::Standard, _) if b % 8 == 7 => Err(format!("Bad combo number {}", b)),
+      (IsaVersion::Standard, _) => Self::combo_from_byte(b % 8, IsaVersion::Extended),
+      (IsaVersion::Extended, 0..4) => Ok(Operand::Literal(b as DataValue)),
+      (IsaVersion::Extended, _) => Ok(Operand::Register(RegisterName(b as usize - 4))),
+    }
+  }
+
+  fn evaluate(&self, state: &State) -> DataValue {
+    match self {
+      Operand::Literal(lit) => *lit,
+      Operand::Register(reg) => state.get(*reg),
+    }
+  }
+}
+
+impl Display for Operand {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Operand::Literal(lit) => write!(f, "{lit}"),
+      Operand::Register(reg) => write!(f, "{reg}"),
+    }
+  }
+}
+
+#[derive(Clone,Copy,Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
+pub struct Instruction {
+  op: Operation,
+  operand: Operand,
+}
+
+impl Instruction {
+  fn from_bytes(bytes: &[u8], isa: IsaVersion) -> Result<Instruction, String> {
+    if bytes.len() != 2 {
+      return Err(format!("Bad instruction length {}", bytes.len()));
+    }
+    let opcode = match isa {
+      IsaVersion::Standard => bytes[0] % 8,
+      IsaVersion::Extended => bytes[0],
+    };
+    match opcode {
+      0 => Ok(Instruction{op: Operation::Adv(RegisterName::A),
+        operand: Operand::combo_from_byte(bytes[1], isa)?}),
+      1 => Ok(Instruction{op: Operation::Xor(RegisterName::B),
+        operand: Operand::literal_from_byte(bytes[1], isa)}),
+      2 => Ok(Instruction{op: Operation::St(RegisterName::B),
+        operand: Operand::combo_from_byte(bytes[1], isa)?}),
+      3 => Ok(Instruction{op: Operation::Jnz,
+        operand: Operand::literal_from_byte(bytes[1], isa)}),
+      4 => Ok(Instruction{op: Operation::Xor(RegisterName::B),
+        operand: Operand::Register(RegisterName::C)}),
+      5 => Ok(Instruction{op: Operation::Out,
+        operand: Operand::combo_from_byte(bytes[1], isa)?}),
+      6 => Ok(Instruction{op: Operation::Adv(RegisterName::B),
+        operand: Operand::combo_from_byte(bytes[1], isa)?}),
+      7 => Ok(Instruction{op: Operation::Adv(RegisterName::C),
+        operand: Operand::combo_from_byte(bytes[1], isa)?}),
+      // the extended instructions have fixed destinations like the others
+      8 => Ok(Instruction{op: Operation::Add(RegisterName::A),
+        operand: Operand::combo_from_byte(bytes[1], isa)?}),
+      9 => Ok(Instruction{op: Operation::Ldi(RegisterName::B),
+        operand: Operand::literal_from_byte(bytes[1], isa)}),
+      _ => Err(format!("Bad instruction number {}", bytes[0])),
+    }
+  }
+
+  fn exuecute(&self, state: &mut State) {
+    state.pc += 1;
+    match self.op {
+      Operation::Adv(reg) => {
+        state.registers[reg.index()] = state.get(RegisterName::A)
+            .checked_shr(self.operand.evaluate(state).try_into().unwrap_or(u32::MAX))
+            .unwrap_or(0);
+      }
+      Operation::Xor(reg) => {
+        state.registers[reg.index()] = state.get(RegisterName::B) ^
+            self.operand.evaluate(state);
+      }
+      Operation::Jnz => {
+        if state.get(RegisterName::A) != 0 {
+          state.pc = self.operand.evaluate(state) as usize;
+        }
+      }
+      Operation::Out => {
+        state.output.push((self.operand.evaluate(state) % 8) as u8)
+      }
+      Operation::St(reg) => {
+        state.registers[reg.index()] = self.operand.evaluate(state) % 8;
+      }
+      Operation::Add(reg) => {
+        state.registers[reg.index()] = state.get(RegisterName::A)
+            .wrapping_add(self.operand.evaluate(state));
+      }
+      Operation::Ldi(reg) => {
+        state.registers[reg.index()] = self.operand.evaluate(state);
+      }
+    }
+  }
+}
+
+/// Print the instruction using the canonical mnemonic for its opcode.
+impl Display for Instruction {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match (self.op, self.operand) {
+      (Operation::Adv(RegisterName::A), operand) => write!(f, "adv {operand}"),
+      (Operation::Adv(RegisterName::B), operand) => write!(f, "bdv {operand}"),
+      (Operation::Adv(_), operand) => write!(f, "cdv {operand}"),
+      (Operation::Xor(_), Operand::Register(_)) => write!(f, "bxc"),
+      (Operation::Xor(_), operand) => write!(f, "bxl {operand}"),
+      (Operation::Jnz, operand) => write!(f, "jnz {operand}"),
+      (Operation::Out, operand) => write!(f, "out {operand}"),
+      (Operation::St(_), operand) => write!(f, "bst {operand}"),
+      (Operation::Add(_), operand) => write!(f, "add {operand}"),
+      (Operation::Ldi(_), operand) => write!(f, "ldi {operand}"),
+    }
+  }
+}
+
+pub type Program = Vec<Instruction>;
+
+/// Convert the program into a listing with one instruction per line.
+pub fn disassemble(program: &Program) -> String {
+  program.iter().map(|inst| format!("{inst}\n")).collect()
+}
+
+/// Decode the bytes of a program into instructions.
+fn decode(bytes: &[u8], isa: IsaVersion) -> Result<Program, String> {
+  bytes.chunks(2).map(|inst| Instruction::from_bytes(inst, isa)).collect()
+}
+
+fn assemble_combo(operand: &str, isa: IsaVersion) -> Result<u8, String> {
+  match (isa, RegisterName::from_str(operand)) {
+    (_, Ok(reg)) if reg.index() < isa.registers() => Ok(4 + reg.index() as u8),
+    (IsaVersion::Standard, Ok(reg)) if reg.index() < IsaVersion::Extended.registers() =>
+      Err(format!("Register {reg} requires the extended ISA")),
+    (_, Ok(reg)) => Err(format!("Unknown register {reg}")),
+    (_, Err(_)) => match assemble_literal(operand, isa)? {
+      lit @ 0..4 => Ok(lit),
+      lit => Err(format!("Combo literal out of range {lit}")),
+    },
+  }
+}
+
+fn assemble_literal(operand: &str, isa: IsaVersion) -> Result<u8, String> {
+  match (isa, operand.parse::<u8>()) {
+    (IsaVersion::Standard, Ok(lit)) if lit < 8 => Ok(lit),
+    (IsaVersion::Extended, Ok(lit)) => Ok(lit),
+    _ => Err(format!("Bad literal operand '{operand}'")),
+  }
+}
+
+/// Parse a listing of mnemonics, such as the output of disassemble, back into
+/// the program's bytes and instructions.
+pub fn assemble(listing: &str, isa: IsaVersion) -> Result<(Program, Vec<u8>), String> {
+  let mut bytes = Vec::new();
+  for line in listing.lines().map(str::trim).filter(|l| !l.is_empty()) {
+    let (mnemonic, operand) = line.split_once(' ')
+        .map(|(m, o)| (m, o.trim()))
+        .unwrap_or((line, ""));
+    let (opcode, operand) = match (mnemonic, isa) {
+      ("adv", _) => (0, assemble_combo(operand, isa)?),
+      ("bxl", _) => (1, assemble_literal(operand, isa)?),
+      ("bst", _) => (2, assemble_combo(operand, isa)?),
+      ("jnz", _) => (3, assemble_literal(operand, isa)?),
+      ("bxc", _) => (4, if operand.is_empty() { 0 } else { assemble_literal(operand, isa)? }),
+      ("out", _) => (5, assemble_combo(operand, isa)?),
+      ("bdv", _) => (6, assemble_combo(operand, isa)?),
+      ("cdv", _) => (7, assemble_combo(operand, isa)?),
+      ("add", IsaVersion::Extended) => (8, assemble_combo(operand, isa)?),
+      ("ldi", IsaVersion::Extended) => (9, assemble_literal(operand, isa)?),
+      _ => return Err(format!("Unknown mnemonic '{mnemonic}' for {isa:?} ISA")),
+    };
+    bytes.push(opcode);
+    bytes.push(operand);
+  }
+  Ok((decode(&bytes, isa)?, bytes))
+}
+
+fn read_register(s: &str) -> Result<DataValue, String> {
+  let (_, value) = split_pair(s, ":")?;
+  Ok(parse_int(value.trim())?)
+}
+
+/// Parse the registers and program using the given instruction set.
+pub fn parse(input: &str, isa: IsaVersion) -> Result<(State, Program, Vec<u8>), String> {
+  let (registers, program) = two_sections(input)?;
+  let values: Vec<DataValue> = registers.lines()
+      .map(read_register)
+      .try_collect()?;
+  if isa == IsaVersion::Standard && values.len() != 3 {
+    return Err(format!("Standard programs need 3 registers, not {}", values.len()));
+  }
+  let state = State::new(&values);
+  let (_, program) = split_pair(program.trim(), ": ")?;
+  let bytes: Vec<u8> = program.split(',').map(parse_int).try_collect()?;
+  let program = decode(&bytes, isa)?;
+  Ok((state, program, bytes))
+}
+
+pub fn try_generator(input: &str) -> Result<(State, Program, Vec<u8>), String> {
+  parse(input, IsaVersion::Standard)
+}
+
+pub fn generator(input: &str) -> (State, Program, Vec<u8>) {
+  try_generator(input).expect("Can't parse input")
+}
+
+pub fn generator_from_reader(reader: impl BufRead) -> Result<(State, Program, Vec<u8>), String> {
+  from_reader(reader, try_generator)
+}
+
+/// Run the program until it halts.
+fn execute(state: &mut State, program: &Program) {
+  while state.pc < program.len() {
+    program[state.pc].exuecute(state);
+  }
+}
+
+/// The number of instructions that run executes before giving up on a program.
+pub const STEP_LIMIT: usize = 1_000_000;
+
+/// Run the program until it halts or has executed limit instructions.
+/// Returns whether the program halted.
+fn execute_limited(state: &mut State, program: &Program, limit: usize) -> bool {
+  for _ in 0..limit {
+    match program.get(state.pc) {
+      Some(inst) => inst.exuecute(state),
+      None => return true,
+    }
+  }
+  state.pc >= program.len()
+}
+
+/// Run the bytes of a standard program with the given registers, returning an
+/// error if the program is invalid or doesn't halt within limit instructions.
+pub fn run_limited(program_bytes: &[u8], registers: &[DataValue],
+                   limit: usize) -> Result<Vec<u8>, String> {
+  let program = decode(program_bytes, IsaVersion::Standard)?;
+  let mut state = State::new(registers);
+  if execute_limited(&mut state, &program, limit) {
+    Ok(state.output)
+  } else {
+    Err(format!("Program didn't halt within {limit} steps"))
+  }
+}
+
+/// Run the bytes of a standard program with the given registers and return
+/// its output. A program that doesn't halt within STEP_LIMIT instructions is
+/// stopped and returns the output it produced up to that point.
+///
+/// Panics if the bytes aren't a valid program.
+pub fn run(program_bytes: &[u8], a: DataValue, b: DataValue, c: DataValue) -> Vec<u8> {
+  let program = decode(program_bytes, IsaVersion::Standard).expect("Can't decode program");
+  let mut state = State::new(&[a, b, c]);
+  execute_limited(&mut state, &program, STEP_LIMIT);
+  state.output
+}
+
+/// A record of a single executed instruction.
+#[derive(Clone,Debug)]
+pub struct TraceEntry {
+  pub pc: usize,
+  pub instruction: Instruction,
+  /// The value of the operand when the instruction ran.
+  pub operand: DataValue,
+  /// The registers after the instruction ran.
+  pub registers: Registers,
+}
+
+impl Display for TraceEntry {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{:3}: {:<6} [{:o}] => ", self.pc, self.instruction.to_string(), self.operand)?;
+    write_registers(f, &self.registers)
+  }
+}
+
+/// The instructions executed by a run of the program.
+#[derive(Clone,Debug,Default)]
+pub struct Trace {
+  pub entries: Vec<TraceEntry>,
+  /// How many times the instruction at each pc was executed.
+  pub counts: Vec<usize>,
+}
+
+impl Trace {
+  pub fn total(&self) -> usize {
+    self.entries.len()
+  }
+}
+
+impl Display for Trace {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    for entry in &self.entries {
+      writeln!(f, "{entry}")?;
+    }
+    writeln!(f, "Executed {} instructions, per pc: {:?}", self.total(), self.counts)
+  }
+}
+
+/// Run the program until it halts, recording each instruction as it executes.
+pub fn execute_traced(state: &mut State, program: &Program) -> Trace {
+  let mut trace = Trace{entries: Vec::new(), counts: vec![0; program.len()]};
+  while let Some(&instruction) = program.get(state.pc) {
+    let pc = state.pc;
+    let operand = instruction.operand.evaluate(state);
+    instruction.exuecute(state);
+    trace.counts[pc] += 1;
+    trace.entries.push(TraceEntry{pc, instruction, operand, registers: state.registers.clone()});
+  }
+  trace
+}
+
+pub fn part1((state, program, _): &(State, Program, Vec<u8>)) -> String {
+  let mut state = state.clone();
+  execute(&mut state, program);
+  state.output.iter().join(",")
+}
+
+/// Why the debugger stopped running the program.
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+pub enum StopReason {
+  Halted,
+  Breakpoint(usize),
+  Watch(RegisterName),
+}
+
+/// Runs a program while letting the user observe the intermediate states.
+pub struct Debugger {
+  program: Program,
+  state: State,
+  breakpoints: BTreeSet<usize>,
+  watches: Vec<bool>,
+}
+
+impl Debugger {
+  pub fn new(state: &State, program: &Program) -> Self {
+    Debugger{program: program.clone(), state: state.clone(), breakpoints: BTreeSet::new(),
+      watches: vec![false; state.registers.len()]}
+  }
+
+  pub fn state(&self) -> &State {
+    &self.state
+  }
+
+  pub fn pc(&self) -> usize {
+    self.state.pc
+  }
+
+  pub fn register(&self, name: RegisterName) -> DataValue {
+    self.state.get(name)
+  }
+
+  pub fn output(&self) -> &[u8] {
+    &self.state.output
+  }
+
+  pub fn is_halted(&self) -> bool {
+    self.state.pc >= self.program.len()
+  }
+
+  /// Execute a single instruction and return it, or None if the program has halted.
+  pub fn step(&mut self) -> Option<Instruction> {
+    let inst = *self.program.get(self.state.pc)?;
+    inst.exuecute(&mut self.state);
+    Some(inst)
+  }
+
+  /// Toggle the breakpoint at the given pc and return whether it is now set.
+  pub fn toggle_breakpoint(&mut self, pc: usize) -> bool {
+    if !self.breakpoints.remove(&pc) {
+      self.breakpoints.insert(pc);
+    }
+    self.breakpoints.contains(&pc)
+  }
+
+  /// Toggle whether changes to the register stop the program and return
+  /// whether it is now watched.
+  pub fn toggle_watch(&mut self, name: RegisterName) -> bool {
+    match self.watches.get_mut(name.index()) {
+      Some(watch) => {
+        *watch = !*watch;
+        *watch
+      }
+      None => false,
+    }
+  }
+
+  /// Run until the program halts, reaches a breakpoint, or changes a watched
+  /// register. At least one instruction is always run, so that continuing
+  /// from a breakpoint makes progress.
+  pub fn run(&mut self) -> StopReason {
+    loop {
+      let before = self.state.registers.clone();
+      if self.step().is_none() {
+        return StopReason::Halted;
+      }
+      if let Some(reg) = (0..self.watches.len())
+          .find(|&reg| self.watches[reg] && before[reg] != self.state.registers[reg]) {
+        return StopReason::Watch(RegisterName(reg));
+      }
+      if self.is_halted() {
+        return StopReason::Halted;
+      }
+      if self.breakpoints.contains(&self.state.pc) {
+        return StopReason::Breakpoint(self.state.pc);
+      }
+    }
+  }
+
+  fn list(&self, output: &mut impl Write) -> std::io::Result<()> {
+    for (pc, inst) in self.program.iter().enumerate() {
+      let current = if pc == self.state.pc { '>' } else { ' ' };
+      let brk = if self.breakpoints.contains(&pc) { '*' } else { ' ' };
+      writeln!(output, "{current}{brk}{pc:3}: {inst}")?;
+    }
+    Ok(())
+  }
+}
+
+const DEBUG_HELP: &str = "Commands:
+  s [n]  step n instructions (default 1)
+  c      continue until a breakpoint, watched register change, or halt
+  b pc   toggle a breakpoint
+  w reg  toggle a watch on a register
+  p      print the registers and output
+  l      list the program
+  q      quit";
+
+/// Run a simple command line debugger reading commands from input.
+pub fn debug(debugger: &mut Debugger, input: impl BufRead,
+             mut output: impl Write) -> std::io::Result<()> {
+  writeln!(output, "{DEBUG_HELP}")?;
+  for line in input.lines() {
+    let line = line?;
+    let mut words = line.split_whitespace();
+    match (words.next(), words.next()) {
+      (Some("s"), count) => {
+        match count.map_or(Ok(1), str::parse::<usize>) {
+          Ok(count) => for _ in 0..count {
+            let pc = debugger.pc();
+            match debugger.step() {
+              Some(inst) => writeln!(output, "{pc:3}: {inst}  =>  {}", debugger.state())?,
+              None => { writeln!(output, "Halted")?; break }
+            }
+          }
+          Err(_) => writeln!(output, "Bad count")?,
+        }
+      }
+      (Some("c"), _) => {
+        let reason = debugger.run();
+        writeln!(output, "Stopped: {reason:?}  =>  {}", debugger.state())?;
+      }
+      (Some("b"), Some(pc)) => match pc.parse() {
+        Ok(pc) => writeln!(output, "Breakpoint at {pc}: {}",
+                           debugger.toggle_breakpoint(pc))?,
+        Err(_) => writeln!(output, "Bad pc '{pc}'")?,
+      }
+      (Some("w"), Some(reg)) => match RegisterName::from_str(reg) {
+        Ok(reg) => writeln!(output, "Watch on {reg}: {}", debugger.toggle_watch(reg))?,
+        Err(e) => writeln!(output, "{e}")?,
+      }
+      (Some("p"), _) => writeln!(output, "{}", debugger.state())?,
+      (Some("l"), _) => debugger.list(&mut output)?,
+      (Some("q"), _) => break,
+      (None, _) => {}
+      _ => writeln!(output, "{DEBUG_HELP}")?,
+    }
+  }
+  Ok(())
+}
+
+/// The shape of a program that loops over the value in register A.
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+pub struct LoopShape {
+  /// How many bits A is shifted right by each time through the loop.
+  pub shift: u32,
+  /// How many values are output each time through the loop.
+  pub outputs: usize,
+}
+
+/// Work out how the program consumes A. The program must be a single loop
+/// that ends in "jnz 0" and shifts A by literal amounts.
+pub fn loop_shape(program: &Program) -> Result<LoopShape, String> {
+  match program.last() {
+    Some(Instruction{op: Operation::Jnz, operand: Operand::Literal(0)}) => {}
+    _ => return Err("Program doesn't end with jnz 0".to_string()),
+  }
+  let mut shift = 0;
+  let mut outputs = 0;
+  for inst in &program[..program.len() - 1] {
+    match (inst.op, inst.operand) {
+      (Operation::Adv(RegisterName::A), Operand::Literal(lit)) => shift += lit as u32,
+      (Operation::Adv(RegisterName::A), _) => return Err(format!("Variable shift of A: {inst}")),
+      (Operation::Add(RegisterName::A), _) => return Err(format!("Addition to A: {inst}")),
+      (Operation::Jnz, _) => return Err(format!("Inner jump: {inst}")),
+      (Operation::Out, _) => outputs += 1,
+      _ => {}
+    }
+  }
+  if shift == 0 || outputs == 0 {
+    return Err(format!("Program doesn't consume A (shift {shift}, outputs {outputs})"));
+  }
+  Ok(LoopShape{shift, outputs})
+}
+
+/// Run the program from the given state with register A replaced.
+pub fn run_with_a(orig_state: &State, program: &Program, a: DataValue) -> Vec<u8> {
+  let mut state = orig_state.clone();
+  state.registers[RegisterName::A.index()] = a;
+  execute(&mut state, program);
+  state.output
+}
+
+type CompiledRegisters = [DataValue; 3];
+type CompiledStep = Box<dyn Fn(&mut CompiledRegisters, &mut Vec<u8>)>;
+
+fn compiled_register(reg: RegisterName) -> Result<usize, String> {
+  match reg.index() {
+    i @ 0..3 => Ok(i),
+    _ => Err(format!("Can't compile register {reg}")),
+  }
+}
+
+/// Compile a single instruction into a closure with the operand resolved.
+fn compile_instruction(inst: &Instruction) -> Result<CompiledStep, String> {
+  let a = RegisterName::A.index();
+  let b = RegisterName::B.index();
+  let (op, operand) = (inst.op, inst.operand);
+  let value: Box<dyn Fn(&CompiledRegisters) -> DataValue> = match operand {
+    Operand::Literal(lit) => Box::new(move |_| lit),
+    Operand::Register(reg) => {
+      let src = compiled_register(reg)?;
+      Box::new(move |regs| regs[src])
+    }
+  };
+  Ok(match (op, operand) {
+    (Operation::Adv(reg), Operand::Literal(lit)) => {
+      let dest = compiled_register(reg)?;
+      Box::new(move |regs, _| regs[dest] = regs[a].checked_shr(lit as u32).unwrap_or(0))
+    }
+    (Operation::Adv(reg), _) => {
+      let dest = compiled_register(reg)?;
+      Box::new(move |regs, _| regs[dest] = regs[a]
+          .checked_shr(value(regs).try_into().unwrap_or(u32::MAX)).unwrap_or(0))
+    }
+    (Operation::Xor(reg), _) => {
+      let dest = compiled_register(reg)?;
+      Box::new(move |regs, _| regs[dest] = regs[b] ^ value(regs))
+    }
+    (Operation::Out, _) => Box::new(move |regs, output| output.push((value(regs) % 8) as u8)),
+    (Operation::St(reg), _) => {
+      let dest = compiled_register(reg)?;
+      Box::new(move |regs, _| regs[dest] = value(regs) % 8)
+    }
+    (Operation::Add(reg), _) => {
+      let dest = compiled_register(reg)?;
+      Box::new(move |regs, _| regs[dest] = regs[a].wrapping_add(value(regs)))
+    }
+    (Operation::Ldi(reg), _) => {
+      let dest = compiled_register(reg)?;
+      Box::new(move |regs, _| regs[dest] = value(regs))
+    }
+    (Operation::Jnz, _) => return Err(format!("Can't compile inner jump: {inst}")),
+  })
+}
+
+/// Compile a program that is a straight line loop ending in jnz 0 into a
+/// closure that takes A and returns the output. This avoids decoding each
+/// instruction when the search tries many values of A. Like run, a loop that
+/// doesn't halt within STEP_LIMIT instructions is stopped and returns the
+/// output it produced up to that point.
+pub fn compile(orig_state: &State, program: &Program)
+    -> Result<impl Fn(DataValue) -> Vec<u8>, String> {
+  let Some((Instruction{op: Operation::Jnz, operand: Operand::Literal(0)}, body)) =
+      program.split_last() else {
+    return Err("Program doesn't end with jnz 0".to_string());
+  };
+  if orig_state.registers.len() > 3 {
+    return Err(format!("Can't compile {} registers", orig_state.registers.len()));
+  }
+  let steps: Vec<CompiledStep> = body.iter().map(compile_instruction).try_collect()?;
+  let initial: CompiledRegisters = [orig_state.get(RegisterName::A),
+    orig_state.get(RegisterName::B), orig_state.get(RegisterName::C)];
+  let iterations = STEP_LIMIT / program.len();
+  Ok(move |a| {
+    let mut regs = initial;
+    regs[RegisterName::A.index()] = a;
+    let mut output = Vec::new();
+    for _ in 0..iterations {
+      for step in &steps {
+        step(&mut regs, &mut output);
+      }
+      if regs[RegisterName::A.index()] == 0 {
+        break;
+      }
+    }
+    output
+  })
+}
+
+/// Find the lowest value of A whose upper bits are high and whose output
+/// from the given loop iteration onward matches the goal.
+fn find_digits(run: &dyn Fn(DataValue) -> Vec<u8>, shape: LoopShape, high: DataValue,
+               iteration: usize, goal: &[u8]) -> Option<DataValue> {
+  let base = high.checked_shl(shape.shift)?;
+  if base >> shape.shift != high {
+    return None;
+  }
+  (0..(1 << shape.shift)).find_map(|digit| {
+    let a = base | digit;
+    if run(a) != goal[iteration * shape.outputs..] {
+      None
+    } else if iteration == 0 {
+      Some(a)
+    } else {
+      find_digits(run, shape, a, iteration - 1, goal)
+    }
+  })
+}
+
+/// Find the lowest value of register A that makes the program output the goal.
+/// Each time through the loop, the program consumes the low bits of A, so
+/// the last iteration's output only depends on the highest bits. The search
+/// fixes the bits of A from the highest down, one loop iteration at a time.
+pub fn find_quine(orig_state: &State, program: &Program, goal: &[u8]) -> Option<DataValue> {
+  let shape = loop_shape(program).ok()?;
+  if goal.is_empty() || !goal.len().is_multiple_of(shape.outputs) {
+    return None;
+  }
+  let iterations = goal.len() / shape.outputs;
+  match compile(orig_state, program) {
+    Ok(compiled) => find_digits(&compiled, shape, 0, iterations - 1, goal),
+    Err(_) => find_digits(&|a| run_with_a(orig_state, program, a), shape, 0, iterations - 1,
+                          goal),
+  }
+}
+
+/// The error when no value of A makes the program print itself.
+pub const NO_QUINE: &str = "No value of A makes the program print itself";
+
+pub fn part2((orig_state, program, bytes): &(State, Program, Vec<u8>))
+    -> Result<DataValue, String> {
+  find_quine(orig_state, program, bytes).ok_or_else(|| NO_QUINE.to_string())
+}
+
+/// Solve part 2 using symbolic execution rather than searching the loop iterations.
+pub fn part2_symbolic((orig_state, program, bytes): &(State, Program, Vec<u8>))
+    -> Result<DataValue, String> {
+  symbolic::solve(orig_state, program, bytes).ok_or_else(|| NO_QUINE.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{assemble, compile, debug, disassemble, execute, execute_traced, find_quine,
+              generator, loop_shape, parse, part1, part2_symbolic, run, run_limited,
+              run_with_a, symbolic, Debugger, IsaVersion, LoopShape, RegisterName, State,
+              StopReason, STEP_LIMIT};
+
+  const INPUT: &str = include_str!("../../tests/data/day17/example.txt");
+
+  const PART2_INPUT: &str = include_str!("../../tests/data/day17/example2.txt");
+
+  #[test]
+  fn test_disassemble() {
+    let (_, program, _) = generator(PART2_INPUT);
+    assert_eq!("adv 3\nout A\njnz 0\n", disassemble(&program));
+    let (_, program, _) = generator("Register A: 0\nRegister B: 0\nRegister C: 0\n\n\
+        Program: 2,4,1,5,7,5,4,3,0,3,6,6,5,5");
+    assert_eq!("bst A\nbxl 5\ncdv B\nbxc\nadv 3\nbdv C\nout B\n", disassemble(&program));
+  }
+
+  #[test]
+  fn test_assemble() {
+    let (program, bytes) = assemble("adv 1\nout a\njnz 0\n", IsaVersion::Standard).unwrap();
+    assert_eq!(vec![0, 1, 5, 4, 3, 0], bytes);
+    let state = State::new(&[729, 0, 0]);
+    assert_eq!("4,6,3,5,6,3,5,2,1,0", part1(&(state, program, bytes)));
+    let listing = "bst A\nbxl 5\ncdv B\nbxc\nadv 3\nbdv C\nout B\njnz 0\n";
+    assert_eq!(listing, disassemble(&assemble(listing, IsaVersion::Standard).unwrap().0));
+    assert!(assemble("adv 7", IsaVersion::Standard).is_err());
+    assert!(assemble("mul 2", IsaVersion::Standard).is_err());
+    assert_eq!(Err("Register D requires the extended ISA".to_string()),
+               assemble("out D", IsaVersion::Standard).map(|(_, bytes)| bytes));
+    assert_eq!(Ok(vec![5, 255]), assemble("out r251", IsaVersion::Extended).map(|(_, b)| b));
+    // the combo byte only has room for 252 registers
+    for register in ["r252", "r300"] {
+      assert_eq!(Err(format!("Unknown register R{}", &register[1..])),
+                 assemble(&format!("out {register}"), IsaVersion::Extended).map(|(_, b)| b));
+    }
+  }
+
+  #[test]
+  fn test_debugger() {
+    let (state, program, _) = generator(INPUT);
+    let mut debugger = Debugger::new(&state, &program);
+    assert!(debugger.step().is_some());
+    assert_eq!(364, debugger.register(RegisterName::A));
+    debugger.toggle_breakpoint(2);
+    assert_eq!(StopReason::Breakpoint(2), debugger.run());
+    assert_eq!(&[4], debugger.output());
+    debugger.toggle_breakpoint(2);
+    debugger.toggle_watch(RegisterName::A);
+    assert_eq!(StopReason::Watch(RegisterName::A), debugger.run());
+    assert_eq!(182, debugger.register(RegisterName::A));
+    debugger.toggle_watch(RegisterName::A);
+    assert_eq!(StopReason::Halted, debugger.run());
+    assert_eq!(&[4, 6, 3, 5, 6, 3, 5, 2, 1, 0], debugger.output());
+    assert!(debugger.step().is_none());
+
+    let mut debugger = Debugger::new(&state, &program);
+    let mut output = Vec::new();
+    debug(&mut debugger, "b 2\nc\nl\nq\n".as_bytes(), &mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("Stopped: Breakpoint(2)"));
+    assert!(output.contains(">*  2: jnz 0"));
+  }
+
+  #[test]
+  fn test_trace() {
+    let (mut state, program, _) = generator(INPUT);
+    let trace = execute_traced(&mut state, &program);
+    assert_eq!(30, trace.total());
+    assert_eq!(vec![10, 10, 10], trace.counts);
+    assert_eq!(1, trace.entries[0].operand);
+    assert_eq!([364, 0, 0], trace.entries[0].registers[..]);
+    assert_eq!("  1: out A  [554] => A: 554, B: 0, C: 0", trace.entries[1].to_string());
+  }
+
+  #[test]
+  fn test_other_shapes() {
+    let state = State::new(&[0, 0, 0]);
+    let (program, _) = assemble("bst A\nout B\nadv 2\nbst A\nbxl 7\nout B\nadv 3\n\
+        jnz 0", IsaVersion::Standard).unwrap();
+    assert_eq!(Ok(LoopShape{shift: 5, outputs: 2}), loop_shape(&program));
+    let goal = run_with_a(&state, &program, 0o1234567);
+    let a = find_quine(&state, &program, &goal).unwrap();
+    assert!(a <= 0o1234567);
+    assert_eq!(goal, run_with_a(&state, &program, a));
+    let (program, _) = assemble("adv 1\nbst A\nbxl 2\ncdv B\nbxc\nout B\njnz 0",
+                                IsaVersion::Standard).unwrap();
+    assert_eq!(Ok(LoopShape{shift: 1, outputs: 1}), loop_shape(&program));
+    let goal = run_with_a(&state, &program, 1_000_003);
+    let a = find_quine(&state, &program, &goal).unwrap();
+    assert_eq!(goal, run_with_a(&state, &program, a));
+    let (program, _) = assemble("adv B\nout A\njnz 0", IsaVersion::Standard).unwrap();
+    assert!(loop_shape(&program).is_err());
+  }
+
+  #[test]
+  fn test_symbolic() {
+    let data = generator(PART2_INPUT);
+    assert_eq!(Ok(117440), part2_symbolic(&data));
+    for input in [INPUT, PART2_INPUT] {
+      let (state, program, bytes) = generator(input);
+      assert_eq!(find_quine(&state, &program, &bytes),
+                 symbolic::solve(&state, &program, &bytes));
+      let goal = run_with_a(&state, &program, 0o7654321);
+      assert_eq!(find_quine(&state, &program, &goal), symbolic::solve(&state, &program, &goal));
+    }
+    let (program, _) = assemble("bst A\nbxl 3\ncdv B\nbxl 5\nbxc\nout B\nadv 3\njnz 0",
+                                IsaVersion::Standard).unwrap();
+    let goal = vec![2, 4, 1, 3, 7, 5, 1, 5, 4, 0];
+    let (state, _, _) = generator(PART2_INPUT);
+    assert_eq!(find_quine(&state, &program, &goal), symbolic::solve(&state, &program, &goal));
+  }
+
+  const EXTENDED_INPUT: &str =
+"Register A: 10
+Register B: 0
+Register C: 0
+Register D: 7
+
+Program: 8,7,5,4,9,200,5,5";
+
+  #[test]
+  fn test_extended() {
+    assert!(parse(EXTENDED_INPUT, IsaVersion::Standard).is_err());
+    let (mut state, program, bytes) = parse(EXTENDED_INPUT, IsaVersion::Extended).unwrap();
+    assert_eq!("add D\nout A\nldi 200\nout B\n", disassemble(&program));
+    execute(&mut state, &program);
+    assert_eq!(vec![1, 0], state.output);
+    assert_eq!(17, state.get(RegisterName::A));
+    assert_eq!(200, state.get(RegisterName::B));
+    assert_eq!(7, state.get(RegisterName(3)));
+    assert_eq!(0, state.get(RegisterName(5)));
+    let (_, assembled) = assemble(&disassemble(&program), IsaVersion::Extended).unwrap();
+    assert_eq!(bytes, assembled);
+    assert!(assemble("add D", IsaVersion::Standard).is_err());
+    assert_eq!("out R30\n", disassemble(&assemble("out r30", IsaVersion::Extended).unwrap().0));
+  }
+
+  #[test]
+  fn test_run() {
+    assert_eq!(vec![4, 6, 3, 5, 6, 3, 5, 2, 1, 0], run(&[0, 1, 5, 4, 3, 0], 729, 0, 0));
+    assert_eq!(vec![0, 3, 5, 4, 3, 0], run(&[0, 3, 5, 4, 3, 0], 117440, 0, 0));
+    assert_eq!(vec![1], run(&[1, 7, 5, 5], 0, 6, 0));
+    // out B; jnz 0 never halts since A is never changed
+    assert_eq!(STEP_LIMIT / 2, run(&[5, 5, 3, 0], 1, 2, 0).len());
+    assert!(run_limited(&[5, 5, 3, 0], &[1, 2, 0], 100).is_err());
+    assert_eq!(Ok(vec![2]), run_limited(&[5, 5, 3, 0], &[0, 2, 0], 100));
+    assert!(run_limited(&[5, 7], &[0, 0, 0], 100).is_err());
+  }
+
+  #[test]
+  fn test_compile() {
+    for input in [INPUT, PART2_INPUT] {
+      let (state, program, _) = generator(input);
+      let compiled = compile(&state, &program).unwrap();
+      for a in [1, 729, 2024, 117440, 0o7654321] {
+        assert_eq!(run_with_a(&state, &program, a), compiled(a));
+      }
+    }
+    let (program, _) = assemble("bst A\nbxl 5\ncdv B\nbxc\nadv 3\nbdv C\nout B\njnz 0",
+                                IsaVersion::Standard).unwrap();
+    let state = State::new(&[0, 0, 0]);
+    let compiled = compile(&state, &program).unwrap();
+    for a in [1, 12345, 0o7654321, u64::MAX] {
+      assert_eq!(run_with_a(&state, &program, a), compiled(a));
+    }
+    // A is never cleared, so the loop stops at the step limit
+    let (program, _) = assemble("bst A\nout A\njnz 0", IsaVersion::Standard).unwrap();
+    assert_eq!(STEP_LIMIT / 3, compile(&state, &program).unwrap()(5).len());
+    let (program, _) = assemble("adv 1\njnz 0\nout A\njnz 0", IsaVersion::Standard).unwrap();
+    assert!(compile(&state, &program).is_err());
+    let (program, _) = assemble("adv 1\nout A", IsaVersion::Standard).unwrap();
+    assert!(compile(&state, &program).is_err());
+  }
+}
@@ -0,0 +1,174 @@
+//! Symbolic execution of the day 17 programs.
+//!
+//! Register A starts as a bit-vector where each bit is either known or
+//! unknown. Running the program with the partially known registers turns the
+//! out instructions into constraints against the goal. The solver assigns A
+//! three bits at a time from the bottom and backtracks whenever a known bit
+//! of an output disagrees with the goal.
+
+use super::{DataValue, Instruction, Operand, Operation, Program, RegisterName, State};
+
+/// A value where only some of the bits are known.
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+struct SymValue {
+  /// The mask of the bits that are known.
+  known: u64,
+  /// The values of the known bits. The unknown bits are always zero.
+  bits: u64,
+}
+
+impl SymValue {
+  fn literal(value: DataValue) -> Self {
+    SymValue{known: !0, bits: value}
+  }
+
+  fn value(&self) -> Option<DataValue> {
+    if self.known == !0 { Some(self.bits) } else { None }
+  }
+
+  fn shift_right(&self, amount: DataValue) -> Self {
+    if amount >= 64 {
+      SymValue::literal(0)
+    } else {
+      // the bits shifted in from the top are known zeros
+      SymValue{known: (self.known >> amount) | !(!0 >> amount), bits: self.bits >> amount}
+    }
+  }
+
+  fn unknown() -> Self {
+    SymValue{known: 0, bits: 0}
+  }
+
+  fn xor(&self, other: &SymValue) -> Self {
+    let known = self.known & other.known;
+    SymValue{known, bits: (self.bits ^ other.bits) & known}
+  }
+
+  fn low_bits(&self) -> Self {
+    SymValue{known: self.known | !7, bits: self.bits & 7}
+  }
+
+  fn is_nonzero(&self) -> Option<bool> {
+    if self.bits != 0 {
+      Some(true)
+    } else if self.known == !0 {
+      Some(false)
+    } else {
+      None
+    }
+  }
+}
+
+/// The result of symbolically running the program.
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+enum Outcome {
+  /// A known bit of the output disagreed with the goal.
+  Conflict,
+  /// The program needed an unknown bit to continue.
+  Stuck,
+  /// The program halted after producing the goal.
+  Done,
+}
+
+struct SymState {
+  registers: Vec<SymValue>,
+  pc: usize,
+  outputs: usize,
+}
+
+impl SymState {
+  fn evaluate(&self, operand: &Operand) -> SymValue {
+    match operand {
+      Operand::Literal(lit) => SymValue::literal(*lit),
+      Operand::Register(reg) => self.get(*reg),
+    }
+  }
+
+  fn get(&self, reg: RegisterName) -> SymValue {
+    self.registers.get(reg.index()).copied().unwrap_or(SymValue::literal(0))
+  }
+
+  /// Run a single instruction, returning an outcome if the run is over.
+  fn execute(&mut self, inst: &Instruction, goal: &[u8]) -> Option<Outcome> {
+    self.pc += 1;
+    let operand = self.evaluate(&inst.operand);
+    match inst.op {
+      Operation::Adv(reg) => {
+        let Some(amount) = operand.value() else { return Some(Outcome::Stuck) };
+        self.registers[reg.index()] = self.get(RegisterName::A).shift_right(amount);
+      }
+      Operation::Xor(reg) => {
+        self.registers[reg.index()] = self.get(RegisterName::B).xor(&operand);
+      }
+      Operation::Jnz => {
+        match self.get(RegisterName::A).is_nonzero() {
+          Some(true) => self.pc = operand.value().unwrap() as usize,
+          Some(false) => {}
+          None => return Some(Outcome::Stuck),
+        }
+      }
+      Operation::Out => {
+        let output = operand.low_bits();
+        match goal.get(self.outputs) {
+          Some(&expected) if (output.bits ^ expected as u64) & output.known == 0 =>
+            self.outputs += 1,
+          _ => return Some(Outcome::Conflict),
+        }
+      }
+      Operation::St(reg) => {
+        self.registers[reg.index()] = operand.low_bits();
+      }
+      Operation::Add(reg) => {
+        self.registers[reg.index()] = match (self.get(RegisterName::A).value(), operand.value()) {
+          (Some(left), Some(right)) => SymValue::literal(left.wrapping_add(right)),
+          _ => SymValue::unknown(),
+        };
+      }
+      Operation::Ldi(reg) => {
+        self.registers[reg.index()] = operand;
+      }
+    }
+    None
+  }
+}
+
+/// Run the program with a partially known value for A.
+fn run(orig_state: &State, program: &Program, a: SymValue, goal: &[u8]) -> Outcome {
+  let mut state = SymState{
+    registers: orig_state.registers.iter().copied().map(SymValue::literal).collect(),
+    pc: 0, outputs: 0};
+  state.registers[RegisterName::A.index()] = a;
+  while let Some(inst) = program.get(state.pc) {
+    if let Some(outcome) = state.execute(inst, goal) {
+      return outcome;
+    }
+  }
+  if state.outputs == goal.len() { Outcome::Done } else { Outcome::Conflict }
+}
+
+/// Assign the next group of three bits of A, returning the smallest solution.
+fn assign(orig_state: &State, program: &Program, goal: &[u8], a: DataValue,
+          group: u32) -> Option<DataValue> {
+  // Try making all of the remaining bits zero.
+  let mut best = (run(orig_state, program, SymValue::literal(a), goal) == Outcome::Done)
+      .then_some(a);
+  if 3 * group + 3 > DataValue::BITS {
+    return best;
+  }
+  let known = !(!0 << (3 * group + 3));
+  for digit in 0..8 {
+    let next = a | (digit << (3 * group));
+    let candidate = SymValue{known, bits: next};
+    if run(orig_state, program, candidate, goal) != Outcome::Conflict {
+      if let Some(result) = assign(orig_state, program, goal, next, group + 1) {
+        best = Some(best.map_or(result, |b| b.min(result)));
+      }
+    }
+  }
+  best
+}
+
+/// Find the smallest value of A that makes the program output the goal.
+pub fn solve(orig_state: &State, program: &Program, goal: &[u8]) -> Option<DataValue> {
+  assign(orig_state, program, goal, 0, 0)
+}
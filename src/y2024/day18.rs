@@ -0,0 +1,591 @@
+use std::fmt::{Display, Formatter};
+use std::io::{BufRead, Write};
+use std::ops::Range;
+use array2d::Array2D;
+use crate::params::{self, Params};
+use crate::utils::dsu::DisjointSet;
+use crate::utils::graph::astar;
+use crate::utils::grid::{to_index, to_position, Grid, Neighborhood, Position};
+use crate::utils::maze::{Maze, Point};
+use crate::utils::parse::{int_pair, parse_int, read_lines};
+use crate::utils::anim::{self, Cell, Color, FrameBuffer, Simulation, FLOOR, PATH, WALL};
+use itertools::Itertools;
+use tracing::debug;
+
+pub const TITLE: &str = "RAM Run";
+
+pub type Coordinate = Point;
+
+fn parse_line(s: &str) -> Result<Coordinate, String> {
+  let (x, y) = int_pair(s, ",")?;
+  Ok(Coordinate{x, y})
+}
+
+/// The falling bytes along with the size of the memory space and how many
+/// bytes fall before part 1 looks for a path.
+#[derive(Clone,Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
+pub struct Memory {
+  pub bytes: Vec<Coordinate>,
+  pub size: Position,
+  pub fallen: usize,
+}
+
+impl Memory {
+  pub fn bounds(&self) -> Range<Position> {
+    0..self.size
+  }
+}
+
+/// Parse an optional header line such as "size=7 bytes=12" that overrides the
+/// size of the memory space and the number of bytes for part 1.
+fn parse_header(line: &str, memory: &mut Memory) -> Result<(), String> {
+  for setting in line.split_whitespace() {
+    match setting.split_once('=') {
+      Some(("size", value)) => memory.size = parse_int(value)?,
+      Some(("bytes", value)) => memory.fallen = parse_int(value)?,
+      _ => return Err(format!("Unknown setting '{setting}'")),
+    }
+  }
+  Ok(())
+}
+
+/// Parse the optional header and then the bytes a line at a time, with the
+/// memory size and byte count from the parameters unless there is a header.
+fn parse_from<S: AsRef<str>>(lines: impl Iterator<Item=Result<S, String>>, params: &Params)
+    -> Result<Memory, String> {
+  let mut memory = Memory{bytes: Vec::new(), size: to_position(params.size), fallen: params.bytes};
+  let mut lines = lines.peekable();
+  if let Some(header) =
+      lines.next_if(|line| line.as_ref().is_ok_and(|line| line.as_ref().contains('='))) {
+    parse_header(header?.as_ref(), &mut memory)?;
+    if memory.size <= 0 {
+      return Err(format!("Invalid memory size {}", memory.size));
+    }
+  }
+  memory.bytes = lines.map(|line| parse_line(line?.as_ref())).try_collect()?;
+  let bounds = memory.bounds();
+  if let Some(bad) = memory.bytes.iter()
+      .find(|c| !bounds.contains(&c.x) || !bounds.contains(&c.y)) {
+    return Err(format!("Byte {},{} is outside of the memory space", bad.x, bad.y));
+  }
+  Ok(memory)
+}
+
+/// Parse the input with the memory size and byte count from the parameters,
+/// unless the input has a header.
+pub fn parse_with(input: &str, params: &Params) -> Result<Memory, String> {
+  parse_from(input.lines().map(Ok), params)
+}
+
+pub fn parse(input: &str) -> Result<Memory, String> {
+  parse_with(input, &params::current())
+}
+
+pub fn try_generator(input: &str) -> Result<Memory, String> {
+  parse(input)
+}
+
+pub fn generator(input: &str) -> Memory {
+  try_generator(input).expect("Can't parse input")
+}
+
+/// Parse the input a line at a time from the reader.
+pub fn generator_from_reader(reader: impl BufRead) -> Result<Memory, String> {
+  parse_from(read_lines(reader), &params::current())
+}
+
+/// The memory space with the fallen bytes as walls, where the start is the
+/// top left corner and the exit is the bottom right corner.
+fn make_maze(blocks: &[Coordinate], bounds: Range<Position>) -> Maze {
+  let size = to_index(bounds.end);
+  let mut maze = Maze::open(size, size, Point::new(0, 0), Point::new(size - 1, size - 1));
+  for &blk in blocks {
+    maze.set_wall(blk);
+  }
+  maze
+}
+
+/// Draw the fallen bytes as walls with the route through them.
+fn cells(maze: &Maze, route: &[Coordinate]) -> Grid<Cell> {
+  let mut cells = maze.tiles().map(|tile| if tile.is_open() { FLOOR } else { WALL });
+  for step in route {
+    cells[(to_index(step.x), to_index(step.y))] = PATH;
+  }
+  cells
+}
+
+#[allow(dead_code)]
+fn display(maze: &Maze, route: &[Coordinate]) {
+  print!("{}", anim::colored(&cells(maze, route), |cell| *cell));
+}
+
+/// Find the shortest route from the top left to the bottom right corner,
+/// including both ends. The route is empty if the exit can't be reached.
+pub fn run_part1(input: &[Coordinate], bounds: Range<Position>) -> Vec<Coordinate> {
+  let maze = make_maze(input, bounds);
+  maze.shortest_path(maze.start(), maze.end())
+}
+
+/// Find the shortest route using A* with the Manhattan distance to the exit
+/// as the heuristic, which avoids exploring the whole space on big grids.
+pub fn run_part1_astar(input: &[Coordinate], bounds: Range<Position>) -> Vec<Coordinate> {
+  let maze = make_maze(input, bounds);
+  let exit = maze.end();
+  if !maze.is_open(maze.start()) {
+    return Vec::new();
+  }
+  astar([maze.start()],
+        |coord| maze.neighbors(coord).into_iter().map(|n| (n, 1usize)),
+        |coord| coord.manhattan(&exit),
+        |&coord| coord == exit)
+      .map(|(_, route)| route)
+      .unwrap_or_default()
+}
+
+#[allow(dead_code)]
+fn print_distances(distances: &Array2D<usize>) {
+  for row in distances.rows_iter() {
+    for dist in row {
+      if *dist == usize::MAX {
+        print!("{:4}", "");
+      } else {
+        print!("{:4}", *dist);
+      }
+    }
+    println!();
+  }
+}
+
+pub fn part1(memory: &Memory) -> usize {
+  let fallen = &memory.bytes[..memory.fallen.min(memory.bytes.len())];
+  run_part1(fallen, memory.bounds()).len().checked_sub(1).unwrap_or(usize::MAX)
+}
+
+/// The first byte that cuts off the exit.
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+pub struct Blocker {
+  /// The zero-based position of the byte in the input.
+  pub index: usize,
+  pub coord: Coordinate,
+}
+
+impl Display for Blocker {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{},{}", self.coord.x, self.coord.y)
+  }
+}
+
+pub fn run_part2(input: &[Coordinate], bounds: Range<Position>) -> Option<Blocker> {
+  let mut block_time = Array2D::filled_with(None, to_index(bounds.end), to_index(bounds.end));
+  let mut unionfind = DisjointSet::new(2 + input.len());
+  for (round, current) in input.iter().enumerate() {
+    block_time[(to_index(current.y), to_index(current.x))] = Some(round + 2);
+    // Is it connected to the left/bottom?
+    if current.x == 0 || current.y == bounds.end - 1 {
+      unionfind.union(0, round + 2);
+    }
+    // Is it connected to the right/top? The corners at the start and exit
+    // touch both, so a byte there cuts off the exit by itself.
+    if current.y == 0 || current.x == bounds.end - 1 {
+      unionfind.union(1, round + 2);
+    }
+    // connect us to each of the neighbors
+    for (x, y) in Neighborhood::All.around(current.x, current.y) {
+      if bounds.contains(&x) && bounds.contains(&y) {
+        if let Some(other) = block_time[(y as usize, x as usize)] {
+          unionfind.union(round + 2, other);
+        }
+      }
+    }
+    if unionfind.same(0, 1) {
+      debug!(index = round, x = current.x, y = current.y, "exit cut off");
+      return Some(Blocker{index: round, coord: *current});
+    }
+  }
+  None
+}
+
+/// Can the exit be reached from the start after the given bytes have fallen?
+fn is_reachable(input: &[Coordinate], bounds: Range<Position>) -> bool {
+  !run_part1(input, bounds).is_empty()
+}
+
+/// An alternative to run_part2 that binary searches for the first byte that
+/// blocks the exit, checking reachability with a BFS at each step.
+pub fn run_part2_search(input: &[Coordinate], bounds: Range<Position>) -> Option<Blocker> {
+  let fallen = (0..=input.len())
+      .collect_vec()
+      .partition_point(|&count| is_reachable(&input[..count], bounds.clone()));
+  let index = fallen.checked_sub(1)?;
+  input.get(index).map(|&coord| Blocker{index, coord})
+}
+
+/// Answers how long the shortest route is after the first k bytes have
+/// fallen for any k. Since the bytes only ever block cells, the route stays
+/// the shortest until a byte lands on it, so the search is only rerun then.
+pub struct ShortestPaths {
+  /// The length of the route after k bytes, or None if the exit is blocked.
+  lengths: Vec<Option<usize>>,
+}
+
+impl ShortestPaths {
+  pub fn new(bytes: &[Coordinate], bounds: Range<Position>) -> Self {
+    let mut on_route = Array2D::filled_with(false, to_index(bounds.end), to_index(bounds.end));
+    let mut route: Vec<Coordinate> = Vec::new();
+    let mut lengths = Vec::with_capacity(bytes.len() + 1);
+    for fallen in 0..=bytes.len() {
+      let cut = fallen == 0 || on_route[(bytes[fallen - 1].y as usize,
+                                         bytes[fallen - 1].x as usize)];
+      if cut {
+        for step in &route {
+          on_route[(to_index(step.y), to_index(step.x))] = false;
+        }
+        route = run_part1(&bytes[..fallen], bounds.clone());
+        for step in &route {
+          on_route[(to_index(step.y), to_index(step.x))] = true;
+        }
+      }
+      if route.is_empty() {
+        // once the exit is blocked it stays blocked
+        lengths.resize(bytes.len() + 1, None);
+        break;
+      }
+      lengths.push(Some(route.len() - 1));
+    }
+    ShortestPaths{lengths}
+  }
+
+  /// The length of the shortest route after the first fallen bytes, or None
+  /// if the exit can't be reached. Counts past the end use all of the bytes.
+  pub fn after(&self, fallen: usize) -> Option<usize> {
+    self.lengths[fallen.min(self.lengths.len() - 1)]
+  }
+}
+
+/// Drops the bytes one at a time for the visualizer. The shortest route is
+/// redrawn each time a byte lands on it, until the exit is cut off.
+pub struct FallingBytes {
+  bytes: Vec<Coordinate>,
+  bounds: Range<Position>,
+  maze: Maze,
+  route: Vec<Coordinate>,
+  fallen: usize,
+}
+
+impl FallingBytes {
+  pub fn new(bytes: &[Coordinate], bounds: Range<Position>) -> Self {
+    let maze = make_maze(&[], bounds.clone());
+    let route = maze.shortest_path(maze.start(), maze.end());
+    FallingBytes{bytes: bytes.to_vec(), bounds, maze, route, fallen: 0}
+  }
+}
+
+impl Simulation for FallingBytes {
+  fn title(&self) -> String {
+    format!("Day 18: {TITLE}")
+  }
+
+  fn state(&self) -> String {
+    let last = match self.fallen.checked_sub(1).map(|i| self.bytes[i]) {
+      Some(last) => format!("last at {},{}", last.x, last.y),
+      None => "none yet".to_string(),
+    };
+    format!("{} of {} bytes fallen, {last}, route of {} steps", self.fallen, self.bytes.len(),
+            self.route.len().saturating_sub(1))
+  }
+
+  /// Draw the fallen bytes as '#' and the route as 'O'.
+  fn render(&self) -> FrameBuffer {
+    let mut cells = cells(&self.maze, &self.route);
+    if let Some(last) = self.fallen.checked_sub(1).map(|i| self.bytes[i]) {
+      cells[(to_index(last.x), to_index(last.y))] = Cell::new('#', Color::Red);
+    }
+    let status = match (self.fallen, self.route.len()) {
+      (0, length) => format!("Bytes: 0, route: {}", length.saturating_sub(1)),
+      (fallen, 0) => format!("Bytes: {fallen}, route blocked by {},{}",
+                              self.bytes[fallen - 1].x, self.bytes[fallen - 1].y),
+      (fallen, length) => format!("Bytes: {fallen}, route: {}", length - 1),
+    };
+    FrameBuffer{cells, status}
+  }
+
+  fn step(&mut self) -> bool {
+    if self.route.is_empty() || self.fallen == self.bytes.len() {
+      return false;
+    }
+    let current = self.bytes[self.fallen];
+    self.fallen += 1;
+    self.maze.set_wall(current);
+    if self.route.contains(&current) {
+      self.route = run_part1(&self.bytes[..self.fallen], self.bounds.clone());
+    }
+    true
+  }
+}
+
+/// The frames of the falling bytes as text.
+pub struct Animation {
+  falling: FallingBytes,
+  started: bool,
+}
+
+impl Animation {
+  pub fn new(bytes: &[Coordinate], bounds: Range<Position>) -> Self {
+    Animation{falling: FallingBytes::new(bytes, bounds), started: false}
+  }
+}
+
+impl Iterator for Animation {
+  type Item = String;
+
+  fn next(&mut self) -> Option<String> {
+    if self.started && !self.falling.step() {
+      return None;
+    }
+    self.started = true;
+    Some(self.falling.render().to_text())
+  }
+}
+
+/// Play the animation on a terminal at the frame rate.
+pub fn animate(memory: &Memory, mut output: impl Write, fps: u32) -> std::io::Result<()> {
+  anim::play(&mut FallingBytes::new(&memory.bytes, memory.bounds()), &mut output, fps)
+}
+
+pub fn part2(memory: &Memory) -> String {
+  match run_part2(&memory.bytes, memory.bounds()) {
+    Some(blocker) => blocker.to_string(),
+    None => "None".to_string(),
+  }
+}
+
+/// Part 1 with the A* search, for --compare.
+pub fn part1_astar(memory: &Memory) -> usize {
+  let fallen = &memory.bytes[..memory.fallen.min(memory.bytes.len())];
+  run_part1_astar(fallen, memory.bounds()).len().checked_sub(1).unwrap_or(usize::MAX)
+}
+
+/// Part 2 with the binary search instead of the union-find.
+pub fn part2_search(memory: &Memory) -> String {
+  match run_part2_search(&memory.bytes, memory.bounds()) {
+    Some(blocker) => blocker.to_string(),
+    None => "None".to_string(),
+  }
+}
+
+/// The original Dijkstra version of run_part1, which is kept to cross-check
+/// the BFS in the tests and for benches/internals.rs.
+#[cfg(any(test, feature = "bench-internals"))]
+pub mod internals {
+  use std::ops::Range;
+  use crate::utils::graph::Dijkstra;
+  use super::{make_maze, Coordinate, Position};
+
+  pub fn run_part1_dijkstra(input: &[Coordinate], bounds: Range<Position>) -> Vec<Coordinate> {
+    let maze = make_maze(input, bounds);
+    let exit = maze.end();
+    if !maze.is_open(maze.start()) {
+      return Vec::new();
+    }
+    let mut search = Dijkstra::new([maze.start()]);
+    search.run(|coord| maze.neighbors(coord).into_iter().map(|n| (n, 1usize)),
+               |&coord| coord == exit);
+    search.path_to(exit)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use itertools::Itertools;
+  use proptest::prelude::*;
+  use crate::testgen::{self, XorShift};
+  use super::internals::run_part1_dijkstra;
+  use super::{generator, parse, part1, part1_astar, part2, run_part1, run_part1_astar,
+              run_part2, run_part2_search, Animation, Coordinate, Position, ShortestPaths};
+
+  const INPUT: &str =
+"5,4
+4,2
+4,5
+3,0
+2,1
+6,3
+2,4
+1,5
+0,6
+3,3
+2,6
+5,1
+1,2
+5,5
+2,5
+6,5
+1,4
+0,4
+6,4
+1,1
+6,1
+1,0
+0,5
+1,6
+2,0";
+
+  #[test]
+  fn test_part1() {
+    let data = generator(INPUT);
+    let route = run_part1(&data.bytes[..12], 0..7);
+    assert_eq!(23, route.len());
+    assert_eq!(Coordinate{x: 0, y: 0}, route[0]);
+    assert_eq!(Coordinate{x: 6, y: 6}, route[22]);
+    for (prev, next) in route.iter().tuple_windows() {
+      assert_eq!(1, (prev.x - next.x).abs() + (prev.y - next.y).abs());
+      assert!(!data.bytes[..12].contains(next));
+    }
+    // once the blocking byte has fallen there is no route
+    let blocker = data.bytes.iter().position(|c| *c == Coordinate{x: 6, y: 1}).unwrap();
+    assert!(!run_part1(&data.bytes[..blocker], 0..7).is_empty());
+    assert!(run_part1(&data.bytes[..=blocker], 0..7).is_empty());
+  }
+
+  #[test]
+  fn test_part2() {
+    let data = generator(INPUT);
+    let blocker = run_part2(&data.bytes, 0..7).unwrap();
+    assert_eq!("6,1", blocker.to_string());
+    assert_eq!(20, blocker.index);
+    assert_eq!(data.bytes[20], blocker.coord);
+    assert_eq!(None, run_part2(&data.bytes[..20], 0..7));
+  }
+
+  #[test]
+  fn test_header() {
+    let data = generator(&format!("size=7 bytes=12\n{INPUT}"));
+    assert_eq!(7, data.size);
+    assert_eq!(12, data.fallen);
+    assert_eq!(25, data.bytes.len());
+    assert_eq!(22, part1(&data));
+    assert_eq!(22, part1_astar(&data));
+    assert_eq!("6,1", part2(&data));
+    let data = generator(INPUT);
+    assert_eq!((71, 1024), (data.size, data.fallen));
+    assert!(parse(&format!("size=6\n{INPUT}")).is_err());
+    assert!(parse(&format!("depth=6\n{INPUT}")).is_err());
+    assert!(parse(&format!("search=astar\n{INPUT}")).is_err());
+  }
+
+  /// Generate the cells of a size x size grid, except the start and exit, in
+  /// a random order.
+  fn shuffled_bytes(size: Position, seed: u64) -> Vec<Coordinate> {
+    let mut rng = XorShift::new(seed);
+    let mut result = (0..size).cartesian_product(0..size)
+        .map(|(y, x)| Coordinate{x, y})
+        .filter(|c| *c != Coordinate{x: 0, y: 0} && *c != Coordinate{x: size - 1, y: size - 1})
+        .collect_vec();
+    for i in (1..result.len()).rev() {
+      result.swap(i, rng.below(i + 1));
+    }
+    result
+  }
+
+  #[test]
+  fn test_part2_search() {
+    let data = generator(INPUT);
+    assert_eq!(run_part2(&data.bytes, 0..7), run_part2_search(&data.bytes, 0..7));
+    assert_eq!(None, run_part2_search(&data.bytes[..12], 0..7));
+    for size in [2, 3, 7, 15, 30] {
+      for seed in 1..20 {
+        let bytes = shuffled_bytes(size, seed);
+        assert_eq!(run_part2(&bytes, 0..size), run_part2_search(&bytes, 0..size),
+                   "size {size}, seed {seed}");
+        let half = &bytes[..bytes.len() / 2];
+        assert_eq!(run_part2(half, 0..size), run_part2_search(half, 0..size),
+                   "half size {size}, seed {seed}");
+      }
+    }
+  }
+
+  #[test]
+  fn test_animation() {
+    let data = generator(INPUT);
+    let frames = Animation::new(&data.bytes, 0..7).collect_vec();
+    // the exit is cut off by the 21st byte
+    assert_eq!(22, frames.len());
+    assert_eq!("Bytes: 0, route: 12", frames[0].lines().next().unwrap());
+    assert_eq!(13, frames[0].chars().filter(|&ch| ch == 'O').count());
+    assert_eq!("Bytes: 12, route: 22", frames[12].lines().next().unwrap());
+    assert_eq!("Bytes: 21, route blocked by 6,1\n\
+                ...#...\n\
+                .##..##\n\
+                .#..#..\n\
+                ...#..#\n\
+                ###..##\n\
+                .##.###\n\
+                #.#....\n", frames[21]);
+  }
+
+  #[test]
+  fn test_shortest_paths() {
+    let data = generator(INPUT);
+    let paths = ShortestPaths::new(&data.bytes, 0..7);
+    assert_eq!(Some(12), paths.after(0));
+    assert_eq!(Some(22), paths.after(12));
+    assert_eq!(None, paths.after(21));
+    assert_eq!(None, paths.after(1000));
+    for size in [3, 10, 20] {
+      let bytes = shuffled_bytes(size, size as u64);
+      let paths = ShortestPaths::new(&bytes, 0..size);
+      for fallen in 0..=bytes.len() {
+        let route = run_part1(&bytes[..fallen], 0..size);
+        assert_eq!(route.len().checked_sub(1), paths.after(fallen), "size {size}, {fallen}");
+      }
+    }
+  }
+
+  #[test]
+  fn test_dijkstra() {
+    let data = generator(INPUT);
+    assert_eq!(23, run_part1_dijkstra(&data.bytes[..12], 0..7).len());
+    for size in [5, 20, 40] {
+      let bytes = shuffled_bytes(size, 2 * size as u64);
+      for fallen in (0..bytes.len()).step_by(7) {
+        assert_eq!(run_part1_dijkstra(&bytes[..fallen], 0..size).len(),
+                   run_part1(&bytes[..fallen], 0..size).len(), "size {size}, {fallen}");
+      }
+    }
+  }
+
+  #[test]
+  fn test_astar() {
+    let data = generator(INPUT);
+    assert_eq!(23, run_part1_astar(&data.bytes[..12], 0..7).len());
+    assert!(run_part1_astar(&data.bytes, 0..7).is_empty());
+    for size in [5, 20, 40] {
+      let bytes = shuffled_bytes(size, 3 * size as u64);
+      for fallen in (0..bytes.len()).step_by(5) {
+        assert_eq!(run_part1(&bytes[..fallen], 0..size).len(),
+                   run_part1_astar(&bytes[..fallen], 0..size).len(), "size {size}, {fallen}");
+      }
+    }
+  }
+
+  proptest! {
+    #[test]
+    fn test_random_bytes(input in testgen::falling_bytes(8, 40)) {
+      let memory = generator(&input);
+      for fallen in [0, memory.bytes.len() / 2, memory.bytes.len()] {
+        let bytes = &memory.bytes[..fallen];
+        let length = run_part1(bytes, memory.bounds()).len();
+        prop_assert_eq!(length, run_part1_astar(bytes, memory.bounds()).len());
+        prop_assert_eq!(length, run_part1_dijkstra(bytes, memory.bounds()).len());
+      }
+      let blocker = run_part2(&memory.bytes, memory.bounds());
+      prop_assert_eq!(blocker, run_part2_search(&memory.bytes, memory.bounds()));
+      // the exit is reachable right up until the blocker falls
+      if let Some(blocker) = blocker {
+        prop_assert!(!run_part1(&memory.bytes[..blocker.index], memory.bounds()).is_empty());
+        prop_assert!(run_part1(&memory.bytes[..=blocker.index], memory.bounds()).is_empty());
+      }
+    }
+  }
+}
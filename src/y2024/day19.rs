@@ -0,0 +1,534 @@
+use std::io::{BufRead, Write};
+use std::str;
+use aho_corasick::AhoCorasick;
+use itertools::Itertools;
+use rayon::prelude::*;
+use smallvec::SmallVec;
+use trie_rs::Trie;
+use trie_rs::inc_search::Answer;
+use crate::utils::parse::{from_reader, two_sections};
+
+pub const TITLE: &str = "Linen Layout";
+
+/// Matches designs against a set of towels.
+pub struct TowelMatcher {
+  towels: Vec<String>,
+  words: Trie<u8>,
+}
+
+impl TowelMatcher {
+  pub fn new<S: AsRef<str>>(towels: impl IntoIterator<Item=S>) -> Self {
+    // the trie can't hold empty towels, which wouldn't cover anything anyway
+    let towels = towels.into_iter().map(|t| t.as_ref().to_owned())
+        .filter(|t| !t.is_empty()).unique().collect_vec();
+    let words = Trie::from_iter(towels.iter());
+    TowelMatcher{towels, words}
+  }
+
+  pub fn towels(&self) -> &[String] {
+    &self.towels
+  }
+
+  /// Add more towels, rebuilding the trie if any of them are new.
+  pub fn add_towels<S: AsRef<str>>(&mut self, towels: impl IntoIterator<Item=S>) {
+    let before = self.towels.len();
+    for towel in towels {
+      if !towel.as_ref().is_empty() && !self.towels.iter().any(|t| t == towel.as_ref()) {
+        self.towels.push(towel.as_ref().to_owned());
+      }
+    }
+    if self.towels.len() != before {
+      self.words = Trie::from_iter(self.towels.iter());
+    }
+  }
+
+  /// Can the design be made out of the towels?
+  pub fn can_make(&self, design: &str) -> bool {
+    match_line(&self.words, design.as_bytes())
+  }
+
+  /// How many different arrangements of towels make the design?
+  pub fn count_ways(&self, design: &str) -> Count {
+    count_patterns(&self.words, design.as_bytes())
+  }
+}
+
+/// Only the towels are saved, since the trie is quick to rebuild from them.
+#[cfg(feature = "serde")]
+impl serde::Serialize for TowelMatcher {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    self.towels.serialize(serializer)
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for TowelMatcher {
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    Vec::<String>::deserialize(deserializer).map(TowelMatcher::new)
+  }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
+pub struct Input {
+  matcher: TowelMatcher,
+  lines: Vec<String>,
+}
+
+impl Input {
+  pub fn matcher(&self) -> &TowelMatcher {
+    &self.matcher
+  }
+
+  pub fn designs(&self) -> &[String] {
+    &self.lines
+  }
+}
+
+pub fn try_generator(input: &str) -> Result<Input, String> {
+  let (words, patterns) = two_sections(input)?;
+  let matcher = TowelMatcher::new(words.split(',').map(|w| w.trim()));
+  let lines = patterns.lines().map(|line| line.to_owned()).collect();
+  Ok(Input{matcher, lines})
+}
+
+pub fn generator(input: &str) -> Input {
+  try_generator(input).expect("Can't parse input")
+}
+
+pub fn generator_from_reader(reader: impl BufRead) -> Result<Input, String> {
+  from_reader(reader, try_generator)
+}
+
+fn match_line(words: &Trie<u8>, line: &[u8]) -> bool {
+  let mut done = vec![false; line.len()];
+  let mut search = words.inc_search();
+  let mut backtrace = vec![0];
+  while let Some(posn) = backtrace.pop() {
+    if posn >= line.len() {
+      return true;
+    }
+    if done[posn] {
+      continue;
+    }
+    done[posn] = true;
+    search.reset();
+    for (i, ch) in line[posn..].iter().enumerate() {
+      let res = search.query(ch);
+      match res {
+        None => { break; }
+        Some(Answer::Prefix) => { },
+        Some(Answer::Match) => {
+          if posn + i + 1 == line.len() {
+            return true;
+          } else {
+            search.reset();
+          }
+        },
+        Some(Answer::PrefixAndMatch) => {
+          backtrace.push(posn + i + 1);
+        }
+      }
+    }
+  }
+  false
+}
+
+/// Find the lengths of the towels that match the start of the line.
+fn towel_lengths(words: &Trie<u8>, line: &[u8]) -> SmallVec<[usize; 8]> {
+  let mut search = words.inc_search();
+  let mut result = SmallVec::new();
+  for (i, ch) in line.iter().enumerate() {
+    match search.query(ch) {
+      None => break,
+      Some(Answer::Prefix) => {},
+      Some(Answer::Match) => {
+        result.push(i + 1);
+        break;
+      }
+      Some(Answer::PrefixAndMatch) => result.push(i + 1),
+    }
+  }
+  result
+}
+
+impl TowelMatcher {
+  /// Find one way to make the design out of towels, returning the towels in
+  /// order, or None if the design is impossible.
+  pub fn decompose<'a>(&self, line: &'a str) -> Option<Vec<&'a str>> {
+    let bytes = line.as_bytes();
+    // where the towel ending at each position started
+    let mut start: Vec<Option<usize>> = vec![None; bytes.len() + 1];
+    let mut pending = vec![0];
+    while let Some(posn) = pending.pop() {
+      if posn == bytes.len() {
+        let mut result = Vec::new();
+        let mut end = posn;
+        while end > 0 {
+          let begin = start[end].unwrap();
+          result.push(&line[begin..end]);
+          end = begin;
+        }
+        result.reverse();
+        return Some(result);
+      }
+      for len in towel_lengths(&self.words, &bytes[posn..]) {
+        if start[posn + len].is_none() {
+          start[posn + len] = Some(posn);
+          pending.push(posn + len);
+        }
+      }
+    }
+    None
+  }
+}
+
+pub fn part1(input: &Input) -> usize {
+  input.lines.iter().filter(|line| input.matcher.can_make(line)).count()
+}
+
+/// Check the designs on all of the threads.
+pub fn part1_parallel(input: &Input) -> usize {
+  input.lines.par_iter().filter(|line| input.matcher.can_make(line)).count()
+}
+
+/// The number of arrangements, which can be huge when there are many short towels.
+pub type Count = u128;
+
+/// Count the arrangements of the line with a DP from right to left, where
+/// ways[posn] is the number of arrangements of the suffix starting at posn.
+fn count_patterns(words: &Trie<u8>, line: &[u8]) -> Count {
+  let mut ways: Vec<Count> = vec![0; line.len() + 1];
+  ways[line.len()] = 1;
+  for posn in (0..line.len()).rev() {
+    ways[posn] = towel_lengths(words, &line[posn..]).iter()
+        .map(|len| ways[posn + len])
+        .sum();
+  }
+  ways[0]
+}
+
+pub fn part2(input: &Input) -> Count {
+  input.lines.iter().map(|line| input.matcher.count_ways(line)).sum()
+}
+
+/// An alternative to the trie that finds every towel in the design with an
+/// Aho-Corasick automaton and counts the arrangements with a DP.
+pub struct AhoMatcher {
+  automaton: AhoCorasick,
+}
+
+impl AhoMatcher {
+  pub fn new(towels: &[String]) -> Self {
+    AhoMatcher{automaton: AhoCorasick::new(towels).expect("Can't build automaton")}
+  }
+
+  pub fn count_ways(&self, line: &str) -> Count {
+    let mut ways = vec![0; line.len() + 1];
+    ways[0] = 1;
+    // the matches come out ordered by their end, so each start is complete
+    for found in self.automaton.find_overlapping_iter(line) {
+      ways[found.end()] += ways[found.start()];
+    }
+    ways[line.len()]
+  }
+
+  /// Add the number of arrangements of the line that use each towel to uses.
+  /// An arrangement that uses a towel twice is counted twice.
+  fn add_towel_uses(&self, line: &str, uses: &mut [Count]) {
+    let found = self.automaton.find_overlapping_iter(line).collect_vec();
+    // the ways to make the prefix ending at each position
+    let mut before = vec![0; line.len() + 1];
+    before[0] = 1;
+    for m in &found {
+      before[m.end()] += before[m.start()];
+    }
+    // the ways to make the suffix starting at each position
+    let mut after = vec![0; line.len() + 1];
+    after[line.len()] = 1;
+    for m in found.iter().sorted_by_key(|m| std::cmp::Reverse(m.start())) {
+      after[m.start()] += after[m.end()];
+    }
+    for m in &found {
+      uses[m.pattern().as_usize()] += before[m.start()] * after[m.end()];
+    }
+  }
+}
+
+/// How many arrangements across all of the designs use each towel.
+pub struct TowelStats {
+  pub uses: Vec<(String, Count)>,
+}
+
+impl TowelStats {
+  /// The towels that aren't in any arrangement.
+  pub fn unused(&self) -> Vec<&str> {
+    self.uses.iter().filter(|(_, count)| *count == 0).map(|(towel, _)| towel.as_str()).collect()
+  }
+}
+
+pub fn towel_stats(input: &Input) -> TowelStats {
+  let matcher = AhoMatcher::new(&input.matcher.towels);
+  let mut uses = vec![0; input.matcher.towels.len()];
+  for line in &input.lines {
+    matcher.add_towel_uses(line, &mut uses);
+  }
+  TowelStats{uses: input.matcher.towels.iter().cloned().zip(uses).collect()}
+}
+
+pub fn part2_aho(input: &Input) -> Count {
+  let matcher = AhoMatcher::new(&input.matcher.towels);
+  input.lines.iter().map(|line| matcher.count_ways(line)).sum()
+}
+
+/// The result of matching one design from a stream along with the totals so far.
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub struct StreamResult {
+  pub design: String,
+  pub ways: Count,
+  /// The number of possible designs so far.
+  pub possible: usize,
+  /// The number of arrangements of all of the designs so far.
+  pub total: Count,
+}
+
+/// Matches designs as they are read, so that the designs don't all need to be
+/// held in memory.
+pub struct DesignStream<'a, R> {
+  matcher: &'a TowelMatcher,
+  lines: std::io::Lines<R>,
+  possible: usize,
+  total: Count,
+}
+
+impl<'a, R: BufRead> DesignStream<'a, R> {
+  pub fn new(matcher: &'a TowelMatcher, input: R) -> Self {
+    DesignStream{matcher, lines: input.lines(), possible: 0, total: 0}
+  }
+}
+
+impl<R: BufRead> Iterator for DesignStream<'_, R> {
+  type Item = std::io::Result<StreamResult>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let design = match self.lines.next()? {
+      Ok(line) => line.trim().to_string(),
+      Err(err) => return Some(Err(err)),
+    };
+    let ways = self.matcher.count_ways(&design);
+    if ways > 0 {
+      self.possible += 1;
+    }
+    self.total += ways;
+    Some(Ok(StreamResult{design, ways, possible: self.possible, total: self.total}))
+  }
+}
+
+/// Read the designs from input, writing the arrangements of each design and
+/// the running totals to output. Returns the final totals.
+pub fn stream_designs(matcher: &TowelMatcher, input: impl BufRead,
+                      mut output: impl Write) -> std::io::Result<(usize, Count)> {
+  let mut totals = (0, 0);
+  for result in DesignStream::new(matcher, input) {
+    let result = result?;
+    writeln!(output, "{}: {} (possible: {}, total: {})", result.design, result.ways,
+             result.possible, result.total)?;
+    totals = (result.possible, result.total);
+  }
+  Ok(totals)
+}
+
+/// Find the k designs with the most arrangements, from most to least.
+pub fn top_designs(input: &Input, k: usize) -> Vec<(&str, Count)> {
+  input.lines.iter()
+      .map(|line| (line.as_str(), input.matcher.count_ways(line)))
+      .sorted_by_key(|(_, count)| std::cmp::Reverse(*count))
+      .take(k)
+      .collect()
+}
+
+/// Count the arrangements on all of the threads, with a cache for each design.
+pub fn part2_parallel(input: &Input) -> Count {
+  input.lines.par_iter().map(|line| input.matcher.count_ways(line)).sum()
+}
+
+/// The hot loops of the day, for benches/internals.rs.
+#[cfg(feature = "bench-internals")]
+pub mod internals {
+  use super::{Count, Input};
+
+  /// Count the arrangements of every design with count_patterns.
+  pub fn count_patterns(input: &Input) -> Count {
+    input.lines.iter()
+        .map(|line| super::count_patterns(&input.matcher.words, line.as_bytes()))
+        .sum()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use itertools::Itertools;
+  use proptest::prelude::*;
+  use crate::testgen;
+  use super::{count_patterns, generator, part1, part1_parallel, part2, part2_aho, part2_parallel,
+              stream_designs, top_designs, towel_stats, AhoMatcher, Count, DesignStream,
+              StreamResult, TowelMatcher};
+
+  const INPUT: &str = include_str!("../../tests/data/day19/example.txt");
+
+  #[test]
+  fn test_decompose() {
+    let data = generator(INPUT);
+    assert_eq!(Some(vec!["br", "wr", "r"]), data.matcher.decompose("brwrr"));
+    for line in &data.lines {
+      match data.matcher.decompose(line) {
+        Some(towels) => {
+          assert_eq!(*line, towels.concat());
+          assert!(towels.iter().all(|towel| data.matcher.words.exact_match(towel)));
+        }
+        None => assert!(["ubwu", "bbrgwb"].contains(&line.as_str())),
+      }
+    }
+    assert_eq!(Some(vec![]), data.matcher.decompose(""));
+  }
+
+  #[test]
+  fn test_matcher() {
+    let mut matcher = TowelMatcher::new(["r", "wr", "b", "g", "bwu", "rb", "gb", "br", "r"]);
+    assert_eq!(8, matcher.towels().len());
+    assert!(matcher.can_make("brwrr"));
+    assert!(!matcher.can_make("ubwu"));
+    assert_eq!(6, matcher.count_ways("rrbgbr"));
+    assert_eq!(0, matcher.count_ways("ubwu"));
+    matcher.add_towels(["u", "b"]);
+    assert_eq!(9, matcher.towels().len());
+    assert!(matcher.can_make("ubwu"));
+    assert_eq!(1, matcher.count_ways("ubwu"));
+    assert_eq!(Some(vec!["u", "bwu"]), matcher.decompose("ubwu"));
+  }
+
+  #[test]
+  fn test_big_counts() {
+    let data = generator(&format!("r, rr\n\n{}\nrrr\n{}", "r".repeat(150), "r".repeat(100)));
+    // the arrangements of n stripes follow the Fibonacci sequence
+    let mut fibonacci: Vec<Count> = vec![1, 1];
+    for i in 2..=150 {
+      fibonacci.push(fibonacci[i - 1] + fibonacci[i - 2]);
+    }
+    assert!(fibonacci[150] > u64::MAX as Count);
+    assert_eq!(fibonacci[150], data.matcher.count_ways(&"r".repeat(150)));
+    assert_eq!(fibonacci[150] + fibonacci[100] + 3, part2(&data));
+    assert_eq!(part2(&data), part2_aho(&data));
+    let top = top_designs(&data, 2);
+    assert_eq!(vec![("r".repeat(150), fibonacci[150]), ("r".repeat(100), fibonacci[100])],
+               top.iter().map(|(line, count)| (line.to_string(), *count)).collect::<Vec<_>>());
+    let data = generator(INPUT);
+    assert_eq!(vec![("rrbgbr", 6), ("gbbr", 4)], top_designs(&data, 2));
+  }
+
+  #[test]
+  fn test_stream() {
+    let data = generator(INPUT);
+    let (_, designs) = INPUT.split_once("\n\n").unwrap();
+    let results: Vec<StreamResult> = DesignStream::new(data.matcher(), designs.as_bytes())
+        .try_collect().unwrap();
+    assert_eq!(8, results.len());
+    assert_eq!(StreamResult{design: "ubwu".to_string(), ways: 0, possible: 4, total: 13},
+               results[4]);
+    let mut output = Vec::new();
+    assert_eq!((6, 16), stream_designs(data.matcher(), designs.as_bytes(), &mut output).unwrap());
+    let output = String::from_utf8(output).unwrap();
+    assert_eq!(Some("brwrr: 2 (possible: 1, total: 2)"), output.lines().next());
+    assert_eq!(Some("bbrgwb: 0 (possible: 6, total: 16)"), output.lines().last());
+  }
+
+  #[test]
+  fn test_long_design() {
+    // every position is both a towel and the prefix of a longer one
+    let matcher = TowelMatcher::new(["r", "rb"]);
+    let design = "r".repeat(100_000);
+    assert_eq!(1, matcher.count_ways(&design));
+    assert_eq!(1, matcher.count_ways(&format!("{design}b")));
+    assert_eq!(0, matcher.count_ways(&format!("{design}bb")));
+    assert!(matcher.can_make(&design));
+    assert_eq!(100_000, matcher.decompose(&design).unwrap().len());
+  }
+
+  #[test]
+  fn test_parallel() {
+    let data = generator(INPUT);
+    assert_eq!(6, part1_parallel(&data));
+    assert_eq!(16, part2_parallel(&data));
+  }
+
+  #[test]
+  fn test_aho() {
+    let data = generator(INPUT);
+    assert_eq!(16, part2_aho(&data));
+    let matcher = AhoMatcher::new(&data.matcher.towels);
+    for line in data.lines.iter().map(|line| line.as_str())
+        .chain(["rrrrrrrrrrrrrrrrrrrrrrrrrrrrrr", "gbrwrbwugbrbgbr", "brbrbrbrbrbrbrx"]) {
+      assert_eq!(count_patterns(&data.matcher.words, line.as_bytes()),
+                 matcher.count_ways(line), "{line}");
+    }
+  }
+
+  #[test]
+  fn test_empty_design() {
+    // an empty design is made by the empty arrangement in every backend
+    let data = generator("r, wr, b\n\nbrwr\n\nrx");
+    assert_eq!(vec!["brwr", "", "rx"], data.lines);
+    assert!(data.matcher.can_make(""));
+    assert_eq!(1, data.matcher.count_ways(""));
+    assert_eq!(1, AhoMatcher::new(&data.matcher.towels).count_ways(""));
+    assert_eq!(2, part1(&data));
+    assert_eq!(2, part1_parallel(&data));
+    assert_eq!(2, part2(&data));
+    assert_eq!(2, part2_aho(&data));
+    assert_eq!(2, part2_parallel(&data));
+  }
+
+  /// Find every arrangement of the line by brute force.
+  fn arrangements<'a>(towels: &'a [String], line: &str) -> Vec<Vec<&'a str>> {
+    if line.is_empty() {
+      return vec![vec![]];
+    }
+    towels.iter().filter(|towel| line.starts_with(towel.as_str()))
+        .flat_map(|towel| arrangements(towels, &line[towel.len()..]).into_iter()
+            .map(move |mut rest| {
+              rest.insert(0, towel.as_str());
+              rest
+            }))
+        .collect()
+  }
+
+  #[test]
+  fn test_towel_stats() {
+    let data = generator(INPUT);
+    let stats = towel_stats(&data);
+    for (towel, count) in &stats.uses {
+      let expected: Count = data.lines.iter()
+          .flat_map(|line| arrangements(&data.matcher.towels, line))
+          .map(|arrangement| arrangement.iter().filter(|t| **t == towel).count() as Count)
+          .sum();
+      assert_eq!(expected, *count, "{towel}");
+    }
+    assert_eq!(("r".to_string(), 24), stats.uses[0]);
+    assert!(stats.unused().is_empty());
+    let data = generator("r, b, bw, x\n\nrbr\nbbr");
+    assert_eq!(vec!["bw", "x"], towel_stats(&data).unused());
+  }
+
+  proptest! {
+    #[test]
+    fn test_random_towels(input in testgen::towels(8, 10)) {
+      let data = generator(&input);
+      let possible = part1(&data);
+      let ways = part2(&data);
+      // each possible design can be made at least one way
+      prop_assert!(possible as Count <= ways);
+      prop_assert_eq!(possible, part1_parallel(&data));
+      prop_assert_eq!(ways, part2_aho(&data));
+      prop_assert_eq!(ways, part2_parallel(&data));
+    }
+  }
+}
@@ -1,19 +1,28 @@
+use std::io::BufRead;
 use std::ops::RangeInclusive;
 use itertools::Itertools;
 use smallvec::SmallVec;
+use crate::utils::parse::{parse_int, parse_lines};
 
-fn parse_int(s: &str) -> Result<i32, String> {
-  s.parse().map_err(|_| format!("Can't parse integer - '{s}'"))
-}
+pub const TITLE: &str = "Red-Nosed Reports";
 
 type Row = SmallVec<[i32; 20]>;
 
 fn parse_line(s: &str) -> Result<Row, String> {
-  s.split_whitespace().map(parse_int).try_collect()
+  Ok(s.split_whitespace().map(parse_int).try_collect()?)
+}
+
+pub fn try_generator(input: &str) -> Result<Vec<Row>, String> {
+  input.lines().map(parse_line).try_collect()
 }
 
 pub fn generator(input: &str) -> Vec<Row> {
-  input.lines().map(parse_line).try_collect().expect("Can't parse input")
+  try_generator(input).expect("Can't parse input")
+}
+
+/// Parse the input a line at a time from the reader.
+pub fn generator_from_reader(reader: impl BufRead) -> Result<Vec<Row>, String> {
+  parse_lines(reader, parse_line)
 }
 
 const VALID: RangeInclusive<i32> = 1..=3;
@@ -53,28 +62,3 @@ pub fn part1(input: &[Row]) -> usize {
 pub fn part2(input: &[Row]) -> usize {
   input.iter().filter(|v| is_ok(v)).count()
 }
-
-#[cfg(test)]
-mod tests {
-  use super::{generator, part1, part2};
-
-  const INPUT: &str =
-"7 6 4 2 1
-1 2 7 8 9
-9 7 6 2 1
-1 3 2 4 5
-8 6 4 4 1
-1 3 6 7 9";
-
-  #[test]
-  fn test_part1() {
-    let data = generator(INPUT);
-    assert_eq!(2, part1(&data));
-  }
-
-  #[test]
-  fn test_part2() {
-    let data = generator(INPUT);
-    assert_eq!(4, part2(&data));
-  }
-}
@@ -0,0 +1,473 @@
+use std::io::BufRead;
+use std::str::FromStr;
+use array2d::Array2D;
+use itertools::Itertools;
+use rayon::prelude::*;
+use tracing::debug;
+use crate::params::{self, Params};
+use crate::progress::Counter;
+use crate::utils::grid::to_index;
+use crate::utils::maze::{Maze, Point, Tile};
+use crate::utils::render::svg::{self, Style};
+use crate::utils::parse::from_reader;
+
+pub const TITLE: &str = "Race Condition";
+
+/// The racetrack, which has a single start and end.
+pub type Grid = Maze;
+pub type Coordinate = Point;
+
+/// Find the distance from the start to each cell with a BFS. Walls and
+/// unreachable cells are usize::MAX.
+fn find_distances(input: &Grid) -> Array2D<usize> {
+  input.distances_from(input.start())
+}
+
+pub fn try_generator(input: &str) -> Result<Grid, String> {
+  Maze::from_str(input)
+}
+
+pub fn generator(input: &str) -> Grid {
+  try_generator(input).expect("Can't parse input")
+}
+
+pub fn generator_from_reader(reader: impl BufRead) -> Result<Grid, String> {
+  from_reader(reader, try_generator)
+}
+
+fn cheat_distance(distances: &Array2D<usize>, p1: Coordinate, p2: Coordinate) -> usize {
+  // we need to discount the distance between the two points
+  let walk = p1.manhattan(&p2);
+  match (distances[(to_index(p1.y), to_index(p1.x))], distances[(to_index(p2.y), to_index(p2.x))]) {
+    (usize::MAX, _) | (_, usize::MAX) => 0,
+    (left, right) => left.abs_diff(right).max(walk) - walk,
+  }
+}
+
+/// The minimum number of picoseconds a cheat must save to count.
+pub const DEFAULT_LIMIT: usize = 100;
+/// The longest cheat in part 2.
+pub const DEFAULT_JUMP: usize = 20;
+
+/// Count the cheats through a single wall that save at least limit picoseconds.
+pub fn do_part1(input: &Grid, limit: usize) -> usize {
+  let distances = find_distances(input);
+  let mut count = 0;
+  for ((x, y), flr) in input.tiles().iter() {
+    if *flr == Tile::Wall && x != 0 && y != 0 && y != input.height() - 1 &&
+        x != input.width() - 1 {
+      if cheat_distance(&distances, Coordinate::new(y - 1, x),
+                        Coordinate::new(y + 1, x)) >= limit {
+        count += 1;
+      }
+      if cheat_distance(&distances, Coordinate::new(y, x - 1),
+                        Coordinate::new(y, x + 1)) >= limit {
+        count += 1;
+      }
+    }
+  }
+  count
+}
+
+pub fn part1_with(input: &Grid, params: &Params) -> usize {
+  do_part1(input, params.cheat_limit)
+}
+
+pub fn part1(input: &Grid) -> usize {
+  part1_with(input, &params::current())
+}
+
+/// Call action with the two ends and the savings of each cheat of up to jump
+/// picoseconds that saves at least limit picoseconds.
+fn for_each_cheat(input: &Grid, distances: &Array2D<usize>, limit: usize, jump: usize,
+                  mut action: impl FnMut(Coordinate, Coordinate, usize)) {
+  for y in 0..distances.num_rows() {
+    for_each_row_cheat(input, distances, y, limit, jump, &mut action);
+  }
+}
+
+/// Call action for each cheat whose first end is in row y.
+fn for_each_row_cheat(input: &Grid, distances: &Array2D<usize>, y: usize, limit: usize,
+                      jump: usize, mut action: impl FnMut(Coordinate, Coordinate, usize)) {
+  let max = distances[(to_index(input.end().y), to_index(input.end().x))];
+  for (x, dist) in distances.row_iter(y).unwrap().enumerate() {
+    // ignore walls
+    if max >= *dist {
+      for y2 in y..(y + jump + 1).min(distances.num_rows()) {
+        for x2 in (jump.max(x + y2 - y) - jump)..
+            (x + jump + y + 1 - y2).min(distances.num_columns()) {
+          if y2 != y || x2 < x {
+            let cheat = cheat_distance(distances, Coordinate::new(y, x),
+                                       Coordinate::new(y2, x2));
+            if  cheat >= limit {
+              action(Coordinate::new(y, x), Coordinate::new(y2, x2), cheat);
+            }
+          }
+        }
+      }
+    }
+  }
+}
+
+/// Count the cheats of up to jump picoseconds that save at least limit
+/// picoseconds.
+pub fn do_part2(input: &Grid, limit: usize, jump: usize) -> usize {
+  let distances = find_distances(input);
+  let mut count = 0;
+  for_each_cheat(input, &distances, limit, jump, |_, _, _| count += 1);
+  count
+}
+
+/// Count the cheats like do_part2, but only consider the track cells in the
+/// order that the race visits them. Along a single track, moving one step
+/// changes the Manhattan distance by at most one, so when the other end is too
+/// far away we can skip ahead by the excess.
+pub fn do_part2_track(input: &Grid, limit: usize, jump: usize) -> usize {
+  let distances = find_distances(input);
+  let max = distances[(to_index(input.end().y), to_index(input.end().x))];
+  let track = distances.rows_iter().enumerate()
+      .flat_map(|(y, row)| row.enumerate()
+          .filter(|(_, dist)| **dist <= max)
+          .map(move |(x, dist)| (*dist, Coordinate::new(y, x))))
+      .sorted_by_key(|(dist, _)| *dist)
+      .collect_vec();
+  let walk = |left: &Coordinate, right: &Coordinate| left.manhattan(right);
+  let single_track = track.iter().enumerate().all(|(i, (dist, _))| *dist == i) &&
+      track.iter().tuple_windows().all(|((_, left), (_, right))| walk(left, right) == 1);
+  debug!(length = track.len(), single_track, "track found");
+  if !single_track {
+    return do_part2(input, limit, jump);
+  }
+  let counter = Counter::new("day20 part2", track.len());
+  let mut count = 0;
+  for (i, (_, start)) in track.iter().enumerate() {
+    counter.tick();
+    // the cheat must save at least limit, so skip the closer cells
+    let mut j = i + limit.max(1);
+    while let Some((_, end)) = track.get(j) {
+      let length = walk(start, end);
+      if length > jump {
+        j += length - jump;
+      } else {
+        if j - i >= limit + length {
+          count += 1;
+        }
+        j += 1;
+      }
+    }
+  }
+  count
+}
+
+/// Count the cheats like do_part2, but on maps with branches and dead ends
+/// where the distance from the start doesn't tell how far the end is. Each
+/// cheat is timed as the distance from the start to where it begins, its
+/// length, and the distance from where it ends to the end.
+pub fn do_part2_branched(input: &Grid, limit: usize, jump: usize) -> usize {
+  let from_start = find_distances(input);
+  let to_end = input.distances_from(input.end());
+  let best = from_start[(to_index(input.end().y), to_index(input.end().x))];
+  if best == usize::MAX {
+    return 0;
+  }
+  let (rows, columns) = (from_start.num_rows(), from_start.num_columns());
+  let mut count = 0;
+  for ((y, x), before) in from_start.enumerate_row_major() {
+    if *before >= best {
+      continue;
+    }
+    for y2 in y.saturating_sub(jump)..(y + jump + 1).min(rows) {
+      let spare = jump - y.abs_diff(y2);
+      for x2 in x.saturating_sub(spare)..(x + spare + 1).min(columns) {
+        let after = to_end[(y2, x2)];
+        let length = y.abs_diff(y2) + x.abs_diff(x2);
+        if after != usize::MAX && before + length + after + limit <= best {
+          count += 1;
+        }
+      }
+    }
+  }
+  count
+}
+
+/// Count the cheats like do_part2, but with each row of starts on a thread.
+pub fn do_part2_parallel(input: &Grid, limit: usize, jump: usize) -> usize {
+  let distances = find_distances(input);
+  (0..distances.num_rows()).into_par_iter().map(|y| {
+    let mut count = 0;
+    for_each_row_cheat(input, &distances, y, limit, jump, |_, _, _| count += 1);
+    count
+  }).sum()
+}
+
+/// A cheat that leaves the track at start and rejoins it at end.
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+pub struct Cheat {
+  pub start: Coordinate,
+  pub end: Coordinate,
+  /// How many picoseconds the cheat saves.
+  pub saved: usize,
+}
+
+/// Find each cheat of up to jump picoseconds that saves at least limit
+/// picoseconds. The cheats through a single wall in part 1 are the ones with
+/// a jump of 2.
+pub fn find_cheats(input: &Grid, limit: usize, jump: usize) -> Vec<Cheat> {
+  let distances = find_distances(input);
+  let mut result = Vec::new();
+  for_each_cheat(input, &distances, limit, jump, |first, second, saved| {
+    // the cheat goes from the end closer to the start
+    let (start, end) = if distances[(to_index(first.y), to_index(first.x))] <
+        distances[(to_index(second.y), to_index(second.x))] {
+      (first, second)
+    } else {
+      (second, first)
+    };
+    result.push(Cheat{start, end, saved});
+  });
+  result
+}
+
+pub fn part2_with(input: &Grid, params: &Params) -> usize {
+  //slow_test(input, 100, 20)
+  do_part2_track(input, params.cheat_limit, params.cheat_length)
+}
+
+pub fn part2(input: &Grid) -> usize {
+  part2_with(input, &params::current())
+}
+
+/// Draw the racetrack with the cheats on top of it. The nth cheat starts at
+/// the nth lower case letter and ends at the matching upper case letter. The
+/// walls that it passes through, going vertically first, are drawn as 'o'.
+pub fn render_cheats(input: &Grid, cheats: &[Cheat]) -> String {
+  let mut chars = input.to_chars();
+  for (i, cheat) in cheats.iter().enumerate() {
+    let (y_step, x_step) = ((cheat.end.y - cheat.start.y).signum(),
+                            (cheat.end.x - cheat.start.x).signum());
+    let mut current = cheat.start;
+    while current != cheat.end {
+      if current.y != cheat.end.y {
+        current.y += y_step;
+      } else {
+        current.x += x_step;
+      }
+      let cell = &mut chars[to_index(current.y)][to_index(current.x)];
+      if *cell == '#' {
+        *cell = 'o';
+      }
+    }
+    let label = (b'a' + (i % 26) as u8) as char;
+    chars[to_index(cheat.start.y)][to_index(cheat.start.x)] = label;
+    chars[to_index(cheat.end.y)][to_index(cheat.end.x)] = label.to_ascii_uppercase();
+  }
+  chars.iter().map(|row| row.iter().collect::<String>() + "\n").collect()
+}
+
+/// Draw the n cheats of up to jump picoseconds that save the most time.
+pub fn render_best_cheats(input: &Grid, n: usize, jump: usize) -> String {
+  let cheats = find_cheats(input, 1, jump).into_iter()
+      .sorted_by_key(|cheat| std::cmp::Reverse(cheat.saved))
+      .take(n)
+      .collect_vec();
+  render_cheats(input, &cheats)
+}
+
+/// Draw the racetrack as a line from the start to the end with a line
+/// across the walls for each cheat that saves at least limit picoseconds.
+pub fn to_svg(input: &Grid, limit: usize, jump: usize) -> String {
+  let distances = find_distances(input);
+  let mut track = input.tiles().iter()
+      .filter(|((x, y), _)| distances[(*y, *x)] != usize::MAX)
+      .map(|(position, _)| position)
+      .collect_vec();
+  track.sort_by_key(|&(x, y)| distances[(y, x)]);
+  let mut paths = vec![svg::Path::new(track, "#3060c0")];
+  for_each_cheat(input, &distances, limit, jump, |left, right, _| {
+    paths.push(svg::Path::new(vec![(to_index(left.x), to_index(left.y)),
+                                   (to_index(right.x), to_index(right.y))], "#d04040"));
+  });
+  svg::render(input.tiles(), |spot| match spot {
+    Tile::Wall => Style::fill("#404040"),
+    _ => Style::empty(),
+  }, &paths)
+}
+
+#[cfg(test)]
+mod tests {
+  use proptest::prelude::*;
+  use crate::testgen;
+  use super::{generator, do_part1, do_part2, do_part2_parallel, do_part2_track, find_cheats,
+              do_part2_branched, find_distances, render_best_cheats, render_cheats, to_svg, Cheat, Coordinate};
+
+  const INPUT: &str =
+"###############
+#...#...#.....#
+#.#.#.#.#.###.#
+#S#...#.#.#...#
+#######.#.#.###
+#######.#.#...#
+#######.#.###.#
+###..E#...#...#
+###.#######.###
+#...###...#...#
+#.#####.#.###.#
+#.#...#.#.#...#
+#.#.#.#.#.#.###
+#...#...#...###
+###############";
+
+  #[test]
+  fn test_svg() {
+    let data = generator(INPUT);
+    // the track and the one cheat that saves 64 picoseconds
+    assert_eq!(2, to_svg(&data, 64, 2).matches("<polyline").count());
+    assert_eq!(1 + do_part1(&data, 20), to_svg(&data, 20, 2).matches("<polyline").count());
+  }
+
+  #[test]
+  fn test_part1() {
+    let data = generator(INPUT);
+    assert_eq!(3, do_part1(&data, 38));
+  }
+
+  #[test]
+  fn test_part2() {
+    let data = generator(INPUT);
+    assert_eq!(41, do_part2(&data, 70, 20));
+    assert_eq!(41, do_part2_parallel(&data, 70, 20));
+    assert_eq!(do_part2(&data, 2, 20), do_part2_parallel(&data, 2, 20));
+  }
+
+  #[test]
+  fn test_track_order() {
+    let data = generator(INPUT);
+    assert_eq!(41, do_part2_track(&data, 70, 20));
+    for jump in [2, 3, 10, 20, 40] {
+      for limit in [1, 2, 20, 50, 76, 84] {
+        assert_eq!(do_part2(&data, limit, jump), do_part2_track(&data, limit, jump),
+                   "limit {limit}, jump {jump}");
+      }
+    }
+  }
+
+  #[test]
+  fn test_parameters() {
+    let data = generator(INPUT);
+    // the example's counts of cheats by the time that they save
+    assert_eq!(14 + 14 + 2 + 4 + 2 + 3 + 1 + 1 + 1 + 1 + 1, do_part1(&data, 2));
+    assert_eq!(5, do_part1(&data, 20));
+    assert_eq!(0, do_part1(&data, 65));
+    assert_eq!(3, do_part2(&data, 76, 20));
+    assert_eq!(do_part1(&data, 10), do_part2(&data, 10, 2));
+  }
+
+  #[test]
+  fn test_find_cheats() {
+    let data = generator(INPUT);
+    assert_eq!(vec![Cheat{start: Coordinate::new(7, 7), end: Coordinate::new(7, 5), saved: 64}],
+               find_cheats(&data, 64, 2));
+    let cheats = find_cheats(&data, 76, 20);
+    assert_eq!(3, cheats.len());
+    assert!(cheats.iter().all(|c| c.start == Coordinate::new(3, 1) && c.saved == 76));
+    assert!(cheats.iter().any(|c| c.end == Coordinate::new(7, 3)));
+    assert_eq!(do_part2(&data, 50, 20), find_cheats(&data, 50, 20).len());
+  }
+
+  const BRANCHED: &str =
+"#########
+#S..#...#
+#.#.#.#.#
+#.#...#E#
+#.#####.#
+#.......#
+#########";
+
+  #[test]
+  fn test_distances() {
+    use itertools::Itertools;
+    let data = generator(BRANCHED);
+    let distances = find_distances(&data);
+    // both ways around the loop to the end are the same length
+    assert_eq!(12, distances[(3, 7)]);
+    let rows = distances.rows_iter()
+        .map(|row| row.map(|d| if *d == usize::MAX { "  #".to_string() } else { format!("{d:3}") })
+            .join(""))
+        .join("\n");
+    assert_eq!(
+"  #  #  #  #  #  #  #  #  #
+  #  0  1  2  #  8  9 10  #
+  #  1  #  3  #  7  # 11  #
+  #  2  #  4  5  6  # 12  #
+  #  3  #  #  #  #  # 11  #
+  #  4  5  6  7  8  9 10  #
+  #  #  #  #  #  #  #  #  #", rows);
+  }
+
+  #[test]
+  fn test_branched() {
+    let data = generator(INPUT);
+    for (limit, jump) in [(1, 2), (50, 20), (70, 20), (76, 20), (10, 6)] {
+      assert_eq!(do_part2(&data, limit, jump), do_part2_branched(&data, limit, jump));
+    }
+    // the dead end curls back under the start, so the distances from the
+    // start make cheats into it look like shortcuts
+    let data = generator(
+"##########
+#E#.S....#
+#.#.####.#
+#...#....#
+##########");
+    assert_eq!(5, do_part2(&data, 2, 2));
+    assert_eq!(2, do_part2_branched(&data, 2, 2));
+    // only (3,1) -> (1,1) saves 4
+    assert_eq!(1, do_part2_branched(&data, 4, 2));
+    assert_eq!(0, do_part2_branched(&data, 10, 20));
+  }
+
+  #[test]
+  fn test_open_room() {
+    // an open room has many paths to each cell, which used to be revisited often
+    let size = 200;
+    let mut rows = vec!["#".repeat(size)];
+    for y in 1..size - 1 {
+      let fill = match y {
+        1 => format!("S{}", ".".repeat(size - 3)),
+        _ if y == size - 2 => format!("{}E", ".".repeat(size - 3)),
+        _ => ".".repeat(size - 2),
+      };
+      rows.push(format!("#{fill}#"));
+    }
+    rows.push("#".repeat(size));
+    let data = generator(&rows.join("\n"));
+    let distances = find_distances(&data);
+    for (y, x) in [(1, 1), (5, 100), (198, 198), (198, 1), (100, 37)] {
+      assert_eq!(y - 1 + x - 1, distances[(y, x)]);
+    }
+  }
+
+  #[test]
+  fn test_render() {
+    let data = generator(INPUT);
+    let cheats = find_cheats(&data, 64, 2);
+    let picture = render_cheats(&data, &cheats);
+    assert_eq!(Some("###..Aoa..#...#"), picture.lines().nth(7));
+    assert_eq!(render_cheats(&data, &[]), INPUT.to_string() + "\n");
+    let picture = render_best_cheats(&data, 1, 20);
+    assert_eq!(Some("#a#...#.#.#...#"), picture.lines().nth(3));
+    assert_eq!(1, picture.matches('A').count());
+  }
+
+  proptest! {
+    #[test]
+    fn test_random_tracks(input in testgen::track(15), limit in 1..20usize,
+                          jump in 2..8usize) {
+      let data = generator(&input);
+      let count = do_part2(&data, limit, jump);
+      prop_assert_eq!(count, do_part2_parallel(&data, limit, jump));
+      prop_assert_eq!(count, do_part2_track(&data, limit, jump));
+      prop_assert_eq!(count, do_part2_branched(&data, limit, jump));
+      prop_assert_eq!(do_part1(&data, limit), do_part2(&data, limit, 2));
+    }
+  }
+}
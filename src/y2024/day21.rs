@@ -0,0 +1,284 @@
+use std::fmt::{Display, Formatter};
+use std::io::BufRead;
+use std::iter;
+use std::str::FromStr;
+use itertools::Itertools;
+use smallvec::SmallVec;
+use crate::keypad::{plan_paths, ArrowKey, Coordinate, KeyPad, TenKey};
+use crate::utils::parse::parse_lines;
+
+pub const TITLE: &str = "Keypad Conundrum";
+
+type Sequence = SmallVec<[TenKey; 10]>;
+
+fn parse_line(s: &str) -> Result<Sequence, String> {
+  s.chars().map(TenKey::from_char).try_collect()
+}
+
+pub fn try_generator(input: &str) -> Result<Vec<Sequence>, String> {
+  input.lines().map(parse_line).try_collect()
+}
+
+pub fn generator(input: &str) -> Vec<Sequence> {
+  try_generator(input).expect("Can't parse input")
+}
+
+/// Parse the input a line at a time from the reader.
+pub fn generator_from_reader(reader: impl BufRead) -> Result<Vec<Sequence>, String> {
+  parse_lines(reader, parse_line)
+}
+
+fn find_numeric(val: &Sequence) -> usize {
+  val.iter().filter_map(|x| x.digit())
+      .fold(0, |acc, x| acc * 10 + x)
+}
+
+/// The arrow keys that someone presses, which are displayed as "^v<>A".
+#[derive(Clone,Debug,Default,Eq,PartialEq)]
+pub struct Presses(pub Vec<ArrowKey>);
+
+impl Display for Presses {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    for key in &self.0 {
+      write!(f, "{}", key.to_char())?;
+    }
+    Ok(())
+  }
+}
+
+impl FromStr for Presses {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Ok(Presses(s.chars().map(ArrowKey::from_char).try_collect()?))
+  }
+}
+
+/// Move the arm over a pad for an arrow key. Activate returns the key under
+/// the arm and the others move the arm, which must stay over the pad.
+fn drive_arm<T: KeyPad>(arm: &mut Coordinate, key: ArrowKey) -> Result<Option<T>, String> {
+  if key == ArrowKey::Activate {
+    return Ok(T::from_position(*arm));
+  }
+  *arm = key.step(*arm);
+  match T::from_position(*arm) {
+    Some(_) => Ok(None),
+    None => Err(format!("Arm moved off the pad to {:?}", arm)),
+  }
+}
+
+/// Simulate the human typing the presses through a chain of robots using
+/// arrow pads and return the code typed on the ten key.
+pub fn simulate(presses: &Presses, directional_robot_count: usize) -> Result<Sequence, String> {
+  let mut arrows = vec![ArrowKey::Activate.position(); directional_robot_count];
+  let mut ten_key = TenKey::Activate.position();
+  let mut result = Sequence::new();
+  'presses: for (step, &press) in presses.0.iter().enumerate() {
+    let mut key = press;
+    for (robot, arm) in arrows.iter_mut().enumerate() {
+      match drive_arm::<ArrowKey>(arm, key)
+          .map_err(|e| format!("Robot {} at step {}: {}", robot, step, e))? {
+        Some(next) => key = next,
+        None => continue 'presses,
+      }
+    }
+    if let Some(digit) = drive_arm::<TenKey>(&mut ten_key, key)
+        .map_err(|e| format!("Ten key robot at step {}: {}", step, e))? {
+      result.push(digit);
+    }
+  }
+  Ok(result)
+}
+
+/// A table of the number of human presses needed to move between each pair
+/// of arrow keys and press the second one.
+type CostTable = [[usize; 5]; 5];
+
+/// The cost tables for each number of robots between the arrow pad and the
+/// human, so that each code is a handful of lookups.
+struct ArrowCosts {
+  levels: Vec<CostTable>,
+}
+
+impl ArrowCosts {
+  /// Build the tables for chains of up to depth robots. The human presses
+  /// each key directly, so the first table is all ones.
+  fn new(depth: usize) -> Self {
+    let mut levels = vec![[[1; 5]; 5]];
+    for _ in 0..depth {
+      let previous = levels.last().unwrap();
+      let mut table = [[0; 5]; 5];
+      for from in ArrowKey::ALL {
+        for to in ArrowKey::ALL {
+          table[from as usize][to as usize] = plan_paths(from, to).iter()
+              .map(|path| Self::path_cost(previous, path))
+              .min().unwrap_or(0);
+        }
+      }
+      levels.push(table);
+    }
+    ArrowCosts { levels }
+  }
+
+  /// The cost of typing the path, which starts from the Activate key.
+  fn path_cost(table: &CostTable, path: &[ArrowKey]) -> usize {
+    iter::once(ArrowKey::Activate).chain(path.iter().copied())
+        .tuple_windows()
+        .map(|(from, to)| table[from as usize][to as usize])
+        .sum()
+  }
+
+  /// Find the cheapest way to type the path with depth robots between it
+  /// and the human and return what the human presses.
+  fn expand_path(&self, path: &[ArrowKey], depth: usize) -> Vec<ArrowKey> {
+    if depth == 0 {
+      return path.to_vec();
+    }
+    iter::once(ArrowKey::Activate).chain(path.iter().copied())
+        .tuple_windows()
+        .flat_map(|(from, to)| {
+          let best = plan_paths(from, to).into_iter()
+              .min_by_key(|option| Self::path_cost(&self.levels[depth - 1], option))
+              .unwrap_or_default();
+          self.expand_path(&best, depth - 1)
+        })
+        .collect()
+  }
+
+  /// Find one of the cheapest sequences of presses that types the code
+  /// through the deepest chain.
+  fn code_sequence(&self, code: &Sequence) -> Vec<ArrowKey> {
+    let depth = self.levels.len() - 1;
+    iter::once(TenKey::Activate).chain(code.iter().copied())
+        .tuple_windows()
+        .flat_map(|(from, to)| {
+          let best = plan_paths(from, to).into_iter()
+              .min_by_key(|path| Self::path_cost(&self.levels[depth], path))
+              .unwrap_or_default();
+          self.expand_path(&best, depth)
+        })
+        .collect()
+  }
+
+  /// The cost of typing the code on the ten key through the deepest chain.
+  fn code_cost(&self, code: &Sequence) -> usize {
+    let table = self.levels.last().unwrap();
+    iter::once(TenKey::Activate).chain(code.iter().copied())
+        .tuple_windows()
+        .map(|(from, to)| plan_paths(from, to).iter()
+            .map(|path| Self::path_cost(table, path))
+            .min().unwrap_or(0))
+        .sum()
+  }
+}
+
+/// Find one of the shortest sequences the human types for a code through
+/// the two robots of part 1.
+pub fn human_sequence(code: &Sequence) -> Presses {
+  Presses(ArrowCosts::new(2).code_sequence(code))
+}
+
+/// Find the length of the shortest sequence the human types for a code
+/// through the two robots of part 1.
+pub fn human_length(code: &Sequence) -> usize {
+  human_sequence(code).0.len()
+}
+
+/// Sum the complexities of the codes when there are the given number of
+/// robots using arrow pads between the human and the ten key robot.
+pub fn solve(codes: &[Sequence], directional_robot_count: usize) -> usize {
+  let costs = ArrowCosts::new(directional_robot_count);
+  codes.iter()
+      .map(|code| costs.code_cost(code) * find_numeric(code))
+      .sum()
+}
+
+pub fn part1(input: &[Sequence]) -> usize {
+  solve(input, 2)
+}
+
+pub fn part2(input: &[Sequence]) -> usize {
+  solve(input, 25)
+}
+
+#[cfg(test)]
+mod tests {
+  use itertools::Itertools;
+  use std::str::FromStr;
+  use super::{generator, human_length, human_sequence, part1, part2, simulate, solve,
+              ArrowCosts, Presses};
+  use crate::keypad::ArrowKey;
+
+  const INPUT: &str = include_str!("../../tests/data/day21/example.txt");
+
+  #[test]
+  fn test_part2() {
+    let data = generator(INPUT);
+    assert_eq!(154115708116294, part2(&data));
+  }
+
+  #[test]
+  fn test_human_sequence() {
+    let data = generator(INPUT);
+    let lengths = data.iter().map(|code| human_sequence(code).to_string().len()).collect_vec();
+    assert_eq!(vec![68, 60, 68, 64, 64], lengths);
+    let sequence = human_sequence(&data[0]).to_string();
+    assert!(sequence.chars().all(|ch| "^v<>A".contains(ch)));
+    // each A tells the middle robot to press a key, which it does 28 times
+    assert_eq!(28, sequence.matches("A").count());
+  }
+
+  #[test]
+  fn test_simulate() {
+    let data = generator(INPUT);
+    for code in &data {
+      assert_eq!(*code, simulate(&human_sequence(code), 2).unwrap());
+    }
+    let presses = Presses::from_str("<A^A>^^AvvvA").unwrap();
+    assert_eq!(data[0], simulate(&presses, 0).unwrap());
+    let presses = Presses::from_str("v<<A>>^A<A>AvA<^AA>A<vAAA>^A").unwrap();
+    assert_eq!(data[0], simulate(&presses, 1).unwrap());
+    // going left twice from A on the ten key is the gap
+    assert!(simulate(&Presses::from_str("<<A").unwrap(), 0).is_err());
+    assert!(simulate(&Presses::from_str("v<<AA").unwrap(), 1).is_err());
+    // so is going left from ^ on the arrow pad
+    assert!(simulate(&Presses::from_str("<<").unwrap(), 1).is_err());
+    assert!(simulate(&Presses::from_str("v<<A").unwrap(), 1).is_ok());
+  }
+
+  #[test]
+  fn test_costs() {
+    let data = generator(INPUT);
+    let costs = ArrowCosts::new(2);
+    for code in &data {
+      assert_eq!(human_length(code), costs.code_cost(code));
+    }
+    assert_eq!(12, ArrowCosts::new(0).code_cost(&data[0]));
+    let (a, left) = (ArrowKey::Activate as usize, ArrowKey::Left as usize);
+    // pressing A again is always a single press
+    assert_eq!(1, costs.levels[2][a][a]);
+    // A -> < has to go down first to avoid the gap with "v<<A"
+    assert_eq!(4, costs.levels[1][a][left]);
+    assert_eq!(10, costs.levels[2][a][left]);
+    // the sequences match the costs at deeper levels too
+    let costs = ArrowCosts::new(6);
+    for code in &data {
+      let sequence = Presses(costs.code_sequence(code));
+      assert_eq!(costs.code_cost(code), sequence.0.len());
+      assert_eq!(*code, simulate(&sequence, 6).unwrap());
+    }
+  }
+
+  #[test]
+  fn test_solve() {
+    let data = generator(INPUT);
+    let complexity = |len: usize| len * 29;
+    assert_eq!(complexity(12), solve(&data[..1], 0));
+    assert_eq!(complexity(28), solve(&data[..1], 1));
+    assert_eq!(complexity(68), solve(&data[..1], 2));
+    assert_eq!(part1(&data), solve(&data, 2));
+    assert_eq!(part2(&data), solve(&data, 25));
+    // the lengths keep growing by a factor of about 2.5 per robot
+    assert!(solve(&data, 30) > 90 * solve(&data, 25));
+  }
+}
@@ -0,0 +1,128 @@
+use std::io::BufRead;
+use std::iter::Peekable;
+use std::str::Chars;
+use crate::utils::parse::from_reader;
+use crate::utils::simd;
+
+pub const TITLE: &str = "Mull It Over";
+
+#[derive(Clone,Copy,Debug,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
+pub enum Command {
+  Mul(i32, i32),
+  Do,
+  Dont,
+}
+
+fn parse_int(stream: &mut Peekable<Chars>) -> Option<i32> {
+  let mut result = 0;
+  for i in 0..3 {
+    if let Some(peek) = stream.peek() {
+      if peek.is_ascii_digit() {
+        result = result * 10 + stream.next().unwrap().to_digit(10).unwrap() as i32;
+      }
+    } else if i == 0 {
+      return None;
+    } else {
+      break;
+    }
+  }
+  Some(result)
+}
+
+fn consume_literal(stream: &mut Peekable<Chars>, lit: &str) -> bool {
+  for ch in lit.chars() {
+    if let Some(next) = stream.peek() {
+      if *next != ch {
+        return false;
+      }
+      stream.next();
+    }
+  }
+  true
+}
+
+/// Parse the command that starts with the byte, which is either 'm' or 'd',
+/// from the rest of the text after it.
+fn command_at(first: u8, stream: &mut Peekable<Chars>) -> Option<Command> {
+  match first {
+    // match mul(999,999)
+    b'm' => {
+      if !consume_literal(stream, "ul(") { return None }
+      let left = parse_int(stream)?;
+      if !consume_literal(stream, ",") { return None }
+      let right = parse_int(stream)?;
+      if !consume_literal(stream, ")") { return None }
+      Some(Command::Mul(left, right))
+    }
+    // match do() and don't()
+    _ => {
+      if !consume_literal(stream, "o") { return None }
+      match stream.peek() {
+        Some('(') => consume_literal(stream, "()").then_some(Command::Do),
+        Some('n') => consume_literal(stream, "n't()").then_some(Command::Dont),
+        _ => None,
+      }
+    }
+  }
+}
+
+/// Any text is valid input, since the corrupted parts are skipped. The
+/// commands can only start at an 'm' or a 'd', so the scanner jumps from
+/// one of those to the next. None of the bytes that a command matches after
+/// its first are an 'm' or a 'd', so it doesn't skip any commands.
+pub fn try_generator(input: &str) -> Result<Vec<Command>, String> {
+  let bytes = input.as_bytes();
+  let mut result = Vec::new();
+  let mut start = 0;
+  while let Some(offset) = simd::find_any(&bytes[start..], b"md") {
+    let at = start + offset;
+    if let Some(command) = command_at(bytes[at], &mut input[at + 1..].chars().peekable()) {
+      result.push(command);
+    }
+    start = at + 1;
+  }
+  Ok(result)
+}
+
+pub fn generator(input: &str) -> Vec<Command> {
+  try_generator(input).expect("Can't parse input")
+}
+
+pub fn generator_from_reader(reader: impl BufRead) -> Result<Vec<Command>, String> {
+  from_reader(reader, try_generator)
+}
+
+pub fn part1(input: &[Command]) -> i32 {
+  input.iter().map(|c| match c {
+    Command::Mul(x, y) => x * y,
+    _ => 0,
+  }).sum()
+}
+
+pub fn part2(input: &[Command]) -> i32 {
+  let mut result = 0;
+  let mut enabled = true;
+  for cmd in input {
+    match cmd {
+      Command::Mul(left, right) => {
+        if enabled {
+          result += left * right;
+        }}
+      Command::Do => { enabled = true; }
+      Command::Dont => { enabled = false; }
+    }
+  }
+  result
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{Command, generator};
+
+  #[test]
+  fn test_generator() {
+    let output = generator("ddo(),don't(mul(3,4)");
+    assert_eq!(vec![Command::Do, Command::Mul(3,4)], output);
+  }
+}
@@ -0,0 +1,103 @@
+use std::io::BufRead;
+use itertools::Itertools;
+use crate::utils::parse::from_reader;
+use crate::utils::simd;
+
+pub const TITLE: &str = "Ceres Search";
+
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
+pub struct Board {
+  vals: Vec<Vec<u8>>,
+  width: usize,
+  height: usize,
+}
+
+impl Board {
+  fn get(&self, x: i32, y: i32) -> u8 {
+    self.vals[y as usize][x as usize]
+  }
+}
+
+pub fn try_generator(input: &str) -> Result<Board, String> {
+  let vals: Vec<Vec<u8>> = input.lines()
+      .map(|l: &str| l.chars().map(|ch| ch as u8).collect())
+      .collect();
+  let height = vals.len();
+  let width = vals.first().ok_or("Empty board")?.len();
+  if let Some((y, row)) = vals.iter().find_position(|row| row.len() != width) {
+    return Err(format!("Row {y} has length {} instead of {width}", row.len()));
+  }
+  Ok(Board{vals, width, height})
+}
+
+pub fn generator(input: &str) -> Board {
+  try_generator(input).expect("Can't parse input")
+}
+
+pub fn generator_from_reader(reader: impl BufRead) -> Result<Board, String> {
+  from_reader(reader, try_generator)
+}
+
+/// Count the words along the rows, the columns, and the diagonals in both
+/// directions. Each kind of ray lines up the rows that its letters are on,
+/// shifted so that a word is the pattern at the same position of each one.
+pub fn part1(input: &Board) -> usize {
+  let rows = &input.vals;
+  let width = input.width;
+  if width < 4 {
+    return 0;
+  }
+  let mut result = 0;
+  for pattern in [*b"XMAS", *b"SAMX"] {
+    for row in rows {
+      result += simd::count_aligned([&row[..width - 3], &row[1..width - 2],
+                                     &row[2..width - 1], &row[3..]], pattern);
+    }
+    for rows in rows.windows(4) {
+      result += simd::count_aligned([&rows[0][..], &rows[1], &rows[2], &rows[3]], pattern);
+      result += simd::count_aligned([&rows[0][..width - 3], &rows[1][1..width - 2],
+                                     &rows[2][2..width - 1], &rows[3][3..]], pattern);
+      result += simd::count_aligned([&rows[0][3..], &rows[1][2..width - 1],
+                                     &rows[2][1..width - 2], &rows[3][..width - 3]], pattern);
+    }
+  }
+  result
+}
+
+fn has_x_mas(board: &Board, x: i32, y: i32, pattern: &[u8]) -> bool {
+  let up_left = board.get(x - 1, y - 1);
+  let down_left = board.get(x - 1, y + 1);
+  let up_right = board.get(x + 1, y - 1);
+  let down_right = board.get(x + 1, y + 1);
+  if up_left == pattern[0] {
+    if down_right != pattern[2] {
+      return false;
+    }
+  } else if up_left == pattern[2] {
+    if down_right != pattern[0] {
+      return false
+    }
+  } else {
+    return false
+  }
+  if down_left == pattern[0] {
+    up_right == pattern[2]
+  } else if down_left == pattern[2] {
+    up_right == pattern[0]
+  } else {
+    false
+  }
+}
+
+pub fn part2(input: &Board) -> usize {
+  let pattern = "MAS".as_bytes();
+  let mut result = 0;
+  for x in 1..(input.width-1) as i32 {
+    for y in 1..(input.height-1) as i32 {
+      if input.get(x, y) == pattern[1] && has_x_mas(input, x, y, pattern) {
+        result += 1;
+      }
+    }
+  }
+  result
+}
@@ -1,11 +1,11 @@
+use std::io::{self, BufRead, Write};
 use itertools::Itertools;
 use smallvec::{SmallVec, ToSmallVec};
+use crate::utils::parse::{int_pair, parse_int, read_lines};
 
-pub type PageId = u16;
+pub const TITLE: &str = "Print Queue";
 
-fn parse_int(s: &str) -> Result<PageId, String> {
-  s.parse().map_err(|_| format!("Can't parse integer - '{s}'"))
-}
+pub type PageId = u16;
 
 /// A single rule stating which page comes before another.
 #[derive(Debug,Eq,Ord,PartialOrd,PartialEq)]
@@ -16,15 +16,14 @@ struct Rule {
 
 impl Rule {
   fn from_str(line: &str) -> Result<Rule, String> {
-    let (previous_str, following_str) = line.split_once("|")
-        .ok_or("missing divider")?;
-    Ok(Rule{previous: parse_int(previous_str)?,
-      following: parse_int(following_str)?})
+    let (previous, following) = int_pair(line, "|")?;
+    Ok(Rule{previous, following})
   }
 }
 
 /// Grouping the rules by their previous page.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
 pub struct RuleGroup {
   previous: PageId,
   following_list: PageList,
@@ -33,27 +32,32 @@ pub struct RuleGroup {
 pub type PageList = SmallVec<[PageId; 32]>;
 
 fn parse_printing(line: &str) -> Result<PageList, String> {
-  line.split(",").map(parse_int).try_collect()
+  Ok(line.split(",").map(parse_int).try_collect()?)
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
 pub struct Input {
   rules: Vec<RuleGroup>,
   printings: Vec<PageList>,
   max_id: PageId,
 }
 
-pub fn generator(input: &str) -> Input {
+/// Parse the rules, a blank line, and the printings a line at a time.
+fn parse_input<S: AsRef<str>>(lines: impl Iterator<Item=Result<S, String>>)
+    -> Result<Input, String> {
   let mut reading_rules = true;
   let mut simple_rules = Vec::new();
   let mut printings = Vec::new();
-  for line in input.lines() {
+  for line in lines {
+    let line = line?;
+    let line = line.as_ref();
     if line.is_empty() {
       reading_rules = false;
     } else if reading_rules {
-      simple_rules.push(Rule::from_str(line).expect("Can't parse rule"));
+      simple_rules.push(Rule::from_str(line)?);
     } else {
-      printings.push(parse_printing(line).expect("Can't parse printing"));
+      printings.push(parse_printing(line)?);
     }
   }
   // Sort the rules and group them together to form the rule groups.
@@ -67,7 +71,20 @@ pub fn generator(input: &str) -> Input {
     max_id = max_id.max(previous);
     rules.push(RuleGroup{previous, following_list})
   }
-  Input{rules, printings, max_id}
+  Ok(Input{rules, printings, max_id})
+}
+
+pub fn try_generator(input: &str) -> Result<Input, String> {
+  parse_input(input.lines().map(Ok))
+}
+
+pub fn generator(input: &str) -> Input {
+  try_generator(input).expect("Can't parse input")
+}
+
+/// Parse the input a line at a time from the reader.
+pub fn generator_from_reader(reader: impl BufRead) -> Result<Input, String> {
+  parse_input(read_lines(reader))
 }
 
 /// Look up which RuleGroup applies.
@@ -163,50 +180,17 @@ pub fn part2(input: &Input) -> u64 {
       .map(|pr| find_middle(&pr) as u64).sum()
 }
 
-#[cfg(test)]
-mod tests {
-  use super::{generator, part1, part2};
-
-  const INPUT: &str =
-"47|53
-97|13
-97|61
-97|47
-75|29
-61|13
-75|53
-29|13
-97|29
-53|29
-61|53
-97|53
-61|29
-47|13
-75|47
-97|75
-47|61
-75|61
-47|29
-75|13
-53|13
-
-75,47,61,53,29
-97,61,53,29,13
-75,29,13
-75,97,47,61,53
-61,13,29
-97,13,75,29,47
-";
-
-  #[test]
-  fn test_part1() {
-    let data = generator(INPUT);
-    assert_eq!(143, part1(&data));
-  }
-
-  #[test]
-  fn test_part2() {
-    let data = generator(INPUT);
-    assert_eq!(123, part2(&data));
+/// Write whether each printing was in order or how it was fixed, along with
+/// the middle page that it adds.
+pub fn explain(input: &Input, out: &mut dyn Write) -> io::Result<()> {
+  let mut pad = vec![None; input.max_id as usize + 1];
+  for (i, printing) in input.printings.iter().enumerate() {
+    match fix_printing(&input.rules, printing, &mut pad) {
+      Some(fix) => writeln!(out, "Printing {}: {} was fixed to {}, part 2 adds {}", i + 1,
+                            printing.iter().join(","), fix.iter().join(","), find_middle(&fix))?,
+      None => writeln!(out, "Printing {}: {} is in order, part 1 adds {}", i + 1,
+                       printing.iter().join(","), find_middle(printing))?,
+    }
   }
+  Ok(())
 }
@@ -1,8 +1,15 @@
-use array2d::Array2D;
-use itertools::Itertools;
+use std::io::BufRead;
 use smallvec::SmallVec;
+use tracing::debug;
+use crate::progress::Counter;
+use crate::utils::grid::Grid as FloorGrid;
+use crate::utils::anim::{Cell, Color, FrameBuffer, Simulation};
+use crate::utils::parse::from_reader;
+
+pub const TITLE: &str = "Guard Gallivant";
 
 #[derive(Clone,Copy,Debug,Eq,Hash,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
 pub enum Direction{
   North,
   East,
@@ -10,7 +17,19 @@ pub enum Direction{
   West,
 }
 
+impl Direction {
+  fn symbol(self) -> char {
+    match self {
+      Direction::North => '^',
+      Direction::East => '>',
+      Direction::South => 'v',
+      Direction::West => '<',
+    }
+  }
+}
+
 #[derive(Clone,Copy,Debug,Eq,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
 pub enum Floor {
   Empty,
   Full,
@@ -36,6 +55,7 @@ impl Floor {
 }
 
 #[derive(Clone,Debug,Eq,Hash,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
 pub struct Coordinate {
   x: i32,
   y: i32,
@@ -53,6 +73,7 @@ impl Coordinate {
 }
 
 #[derive(Clone,Debug,Eq,Hash,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
 struct Guard {
   position: Coordinate,
   facing: Direction,
@@ -70,46 +91,51 @@ impl Guard {
 }
 
 #[derive(Clone,Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
 pub struct Grid {
-  floor: Array2D<Floor>,
+  floor: FloorGrid<Floor>,
   guard: Guard,
   bounds: Coordinate,
 }
 
 impl Grid {
 
-  fn find_guard(floor: &Array2D<Floor>) -> Option<Guard> {
-    floor.rows_iter().enumerate()
-        .find_map(|(y, line)|
-            line.enumerate()
-                .find_map(|(x, flr)| match flr {
-                  Floor::Guard(facing) =>
-                    Some(Guard{position: Coordinate{x: x as i32, y: y as i32},
-                    facing: *facing}),
-                  _ => None,
-                }))
+  fn find_guard(floor: &FloorGrid<Floor>) -> Option<Guard> {
+    floor.iter()
+        .find_map(|((x, y), flr)| match flr {
+          Floor::Guard(facing) =>
+            Some(Guard{position: Coordinate{x: x as i32, y: y as i32},
+            facing: *facing}),
+          _ => None,
+        })
   }
 
   fn get(&self, position: &Coordinate) -> Option<&Floor> {
-    self.floor.get(position.y as usize, position.x as usize)
+    self.floor.get(position.x, position.y)
   }
 
   fn get_mut(&mut self, position: &Coordinate) -> Option<&mut Floor> {
-    self.floor.get_mut(position.y as usize, position.x as usize)
+    self.floor.get_mut(position.x, position.y)
   }
 
   pub fn from_string(input: &str) -> Result<Grid, String> {
-    let array: Vec<Vec<Floor>> = input.lines()
-        .map(|line| line.chars().map(Floor::from_char).try_collect()).try_collect()?;
-    let floor: Array2D<Floor> = Array2D::from_rows(&array).map_err(|e| format!("{e}"))?;
-    let bounds = Coordinate { x: floor.column_len() as i32, y: floor.row_len() as i32};
+    let floor = FloorGrid::from_str(input, Floor::from_char)?;
+    let bounds = Coordinate { x: floor.width() as i32, y: floor.height() as i32};
     let guard = Self::find_guard(&floor).ok_or("No guard found")?;
     Ok(Grid { floor, guard, bounds })
   }
 }
 
+pub fn try_generator(input: &str) -> Result<Grid, String> {
+  Grid::from_string(input)
+}
+
 pub fn generator(input: &str) -> Grid {
-  Grid::from_string(input).expect("Can't parse input")
+  try_generator(input).expect("Can't parse input")
+}
+
+pub fn generator_from_reader(reader: impl BufRead) -> Result<Grid, String> {
+  from_reader(reader, try_generator)
 }
 
 #[derive(Clone,Debug,Default)]
@@ -118,7 +144,7 @@ struct SquareState {
 }
 
 struct WalkState {
-  state: Array2D<SquareState>,
+  state: FloorGrid<SquareState>,
   current: Guard,
   square_count: usize,
 }
@@ -127,12 +153,12 @@ impl WalkState {
 
   #[inline]
   fn get_mut(&mut self, position: &Coordinate) -> &mut SquareState {
-    self.state.get_mut(position.y as usize,position.x as usize).unwrap()
+    self.state.get_mut(position.x, position.y).unwrap()
   }
 
   fn from_grid(grid: &Grid) -> Self {
-    let state = Array2D::filled_with(SquareState::default(),
-                                     grid.bounds.y as usize, grid.bounds.x as usize);
+    let state = FloorGrid::filled(grid.bounds.x as usize, grid.bounds.y as usize,
+                                  SquareState::default());
     let current = grid.guard.clone();
     WalkState{state, current, square_count: 1}
   }
@@ -203,40 +229,81 @@ pub fn part2(input: &Grid) -> usize {
   let mut playground = input.clone();
   let mut state = WalkState::from_grid(&playground);
   assert!(!state.walk_is_loop(&playground), "shouldn't loop");
+  // each step of the walk is on a stack and gets popped once
+  let steps = state.state.iter().map(|(_, square)| square.stack.len()).sum();
+  let counter = Counter::new("day6 part2", steps);
   let mut result = 0;
   while let Some(new_block) = state.pop() {
-    if state.place_block(&mut playground, &new_block) {
+    let is_loop = state.place_block(&mut playground, &new_block);
+    debug!(x = new_block.x, y = new_block.y, is_loop, "obstruction tested");
+    if is_loop {
       result += 1;
     }
+    counter.tick();
   }
   result
 }
 
-#[cfg(test)]
-mod tests {
-  use super::{generator, part1, part2};
-
-  const INPUT: &str =
-"....#.....
-.........#
-..........
-..#.......
-.......#..
-..........
-.#..^.....
-........#.
-#.........
-......#...";
-
-  #[test]
-  fn test_part1() {
-    let data = generator(INPUT);
-    assert_eq!(41, part1(&data));
-  }
-
-  #[test]
-  fn test_part2() {
-    let data = generator(INPUT);
-    assert_eq!(6, part2(&data));
+/// Walks the guard one step at a time for the visualizer, marking the
+/// squares that it has visited.
+pub struct Patrol {
+  grid: Grid,
+  guard: Option<Guard>,
+  visited: FloorGrid<bool>,
+  count: usize,
+}
+
+impl Patrol {
+  pub fn new(grid: &Grid) -> Self {
+    let mut visited = FloorGrid::filled(grid.bounds.x as usize, grid.bounds.y as usize, false);
+    visited[(grid.guard.position.x as usize, grid.guard.position.y as usize)] = true;
+    Patrol{grid: grid.clone(), guard: Some(grid.guard.clone()), visited, count: 1}
+  }
+}
+
+impl Simulation for Patrol {
+  fn title(&self) -> String {
+    format!("Day 6: {TITLE}")
+  }
+
+  fn state(&self) -> String {
+    match &self.guard {
+      Some(guard) => format!("guard at {},{} facing {:?}, visited {}", guard.position.x,
+                             guard.position.y, guard.facing, self.count),
+      None => format!("guard left the lab, visited {}", self.count),
+    }
+  }
+
+  fn render(&self) -> FrameBuffer {
+    let mut cells = self.grid.floor.map(|floor| match floor {
+      Floor::Full => Cell::new('#', Color::Gray),
+      _ => Cell::new('.', Color::Default),
+    });
+    for ((x, y), _) in self.visited.iter().filter(|(_, &visited)| visited) {
+      cells[(x, y)] = Cell::new('X', Color::Yellow);
+    }
+    if let Some(guard) = &self.guard {
+      cells[(guard.position.x as usize, guard.position.y as usize)] =
+          Cell::new(guard.facing.symbol(), Color::Red);
+    }
+    FrameBuffer{cells, status: format!("Visited: {}", self.count)}
+  }
+
+  fn step(&mut self) -> bool {
+    let Some(guard) = &mut self.guard else { return false };
+    let forward = guard.position.step(guard.facing);
+    match self.grid.get(&forward) {
+      Some(floor) if floor.is_occupied() => guard.turn_right(),
+      Some(_) => {
+        let seen = &mut self.visited[(forward.x as usize, forward.y as usize)];
+        if !*seen {
+          *seen = true;
+          self.count += 1;
+        }
+        guard.position = forward;
+      }
+      None => self.guard = None,
+    }
+    true
   }
 }
@@ -1,29 +1,38 @@
+use std::io::BufRead;
 use itertools::Itertools;
 use smallvec::SmallVec;
+use crate::utils::parse::{parse_int, parse_lines, split_pair};
 
-pub type Number = i64;
+pub const TITLE: &str = "Bridge Repair";
 
-fn parse_int(s: &str) -> Result<Number, String> {
-  s.parse().map_err(|_| format!("Can't parse integer - '{s}'"))
-}
+pub type Number = i64;
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
 pub struct Row {
   target: Number,
   inputs: SmallVec<[Number;16]>,
 }
 
 fn parse_line(line: &str) -> Result<Row, String> {
-  let (target_str, inputs_str) = line.split_once(':')
-      .ok_or(format!("Can't find separator: '{line}'"))?;
+  let (target_str, inputs_str) = split_pair(line, ":")?;
   let target = parse_int(target_str)?;
   let inputs = inputs_str.split_whitespace()
       .map(parse_int).try_collect()?;
   Ok(Row { target, inputs })
 }
 
+pub fn try_generator(input: &str) -> Result<Vec<Row>, String> {
+  input.lines().map(parse_line).try_collect()
+}
+
 pub fn generator(input: &str) -> Vec<Row> {
-  input.lines().map(parse_line).try_collect().expect("Can't parse input")
+  try_generator(input).expect("Can't parse input")
+}
+
+/// Parse the input a line at a time from the reader.
+pub fn generator_from_reader(reader: impl BufRead) -> Result<Vec<Row>, String> {
+  parse_lines(reader, parse_line)
 }
 
 fn subtract(right: Number, result: Number) -> Option<Number> {
@@ -87,6 +96,24 @@ fn solvable<const HAS_CONCAT:bool>(row: &Row) -> bool {
   }
 }
 
+/// Try the operators from left to right instead of working back from the
+/// target. The total only grows, so a branch stops once it passes the target.
+fn forward<const HAS_CONCAT:bool>(inputs: &[Number], target: Number, total: Number) -> bool {
+  match inputs.split_first() {
+    None => total == target,
+    Some(_) if total > target => false,
+    Some((&next, rest)) =>
+      forward::<HAS_CONCAT>(rest, target, total + next) ||
+          forward::<HAS_CONCAT>(rest, target, total * next) ||
+          (HAS_CONCAT && forward::<HAS_CONCAT>(rest, target, total * pow10(next) + next)),
+  }
+}
+
+fn solvable_forward<const HAS_CONCAT:bool>(row: &Row) -> bool {
+  row.inputs.split_first()
+      .is_some_and(|(&first, rest)| forward::<HAS_CONCAT>(rest, row.target, first))
+}
+
 pub fn part1(input: &[Row]) -> Number {
   input.iter().filter(|&r| solvable::<false>(r)).map(|row| row.target).sum()
 }
@@ -95,30 +122,12 @@ pub fn part2(input: &[Row]) -> Number {
   input.iter().filter(|&r| solvable::<true>(r)).map(|row| row.target).sum()
 }
 
-#[cfg(test)]
-mod tests {
-  use super::{generator, part1, part2};
-
-  const INPUT: &str =
-"190: 10 19
-3267: 81 40 27
-83: 17 5
-156: 15 6
-7290: 6 8 6 15
-161011: 16 10 13
-192: 17 8 14
-21037: 9 7 18 13
-292: 11 6 16 20";
-
-  #[test]
-  fn test_part1() {
-    let data = generator(INPUT);
-    assert_eq!(3749, part1(&data));
-  }
+/// The forward version of part1, which --compare checks against it.
+pub fn part1_forward(input: &[Row]) -> Number {
+  input.iter().filter(|&r| solvable_forward::<false>(r)).map(|row| row.target).sum()
+}
 
-  #[test]
-  fn test_part2() {
-    let data = generator(INPUT);
-    assert_eq!(11387, part2(&data));
-  }
+/// The forward version of part2.
+pub fn part2_forward(input: &[Row]) -> Number {
+  input.iter().filter(|&r| solvable_forward::<true>(r)).map(|row| row.target).sum()
 }
@@ -1,10 +1,15 @@
 use itertools::Itertools;
 use smallvec::SmallVec;
 use std::collections::HashSet;
+use std::io::BufRead;
+use crate::utils::parse::from_reader;
+
+pub const TITLE: &str = "Resonant Collinearity";
 
 type Position = i32;
 
 #[derive(Clone,Copy,Debug,Eq,Hash,Ord,PartialEq,PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
 pub struct Coordinate {
   x: Position,
   y: Position,
@@ -17,6 +22,7 @@ struct AntennaLocation {
 }
 
 #[derive(Clone,Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
 pub struct Antenna {
   #[allow(dead_code)]
   frequency: char,
@@ -24,6 +30,7 @@ pub struct Antenna {
 }
 
 #[derive(Clone,Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
 pub struct Grid {
   antenna: Vec<Antenna>,
   rows: Position,
@@ -68,7 +75,8 @@ impl Grid {
 
 }
 
-pub fn generator(input: &str) -> Grid {
+/// Every character other than '.' is an antenna, so any text is valid.
+pub fn try_generator(input: &str) -> Result<Grid, String> {
   let mut raw = Vec::new();
   let mut rows = 0;
   let mut columns = 0;
@@ -89,7 +97,15 @@ pub fn generator(input: &str) -> Grid {
   for (frequency, chunk) in &raw.into_iter().chunk_by(|elt| elt.frequency) {
     antenna.push(Antenna{frequency, locations: chunk.map(|a| a.location).collect()})
   }
-  Grid{antenna, rows, columns}
+  Ok(Grid{antenna, rows, columns})
+}
+
+pub fn generator(input: &str) -> Grid {
+  try_generator(input).expect("Can't parse input")
+}
+
+pub fn generator_from_reader(reader: impl BufRead) -> Result<Grid, String> {
+  from_reader(reader, try_generator)
 }
 
 pub fn part1(input: &Grid) -> usize {
@@ -111,35 +127,3 @@ pub fn part2(input: &Grid) -> usize {
   }
   antinodes.len()
 }
-
-#[cfg(test)]
-mod tests {
-  use super::{generator, part1, part2};
-
-  const INPUT: &str =
-"............
-........0...
-.....0......
-.......0....
-....0.......
-......A.....
-............
-............
-........A...
-.........A..
-............
-............";
-
-
-  #[test]
-  fn test_part1() {
-    let data = generator(INPUT);
-    assert_eq!(14, part1(&data));
-  }
-
-  #[test]
-  fn test_part2() {
-    let data = generator(INPUT);
-    assert_eq!(34, part2(&data));
-  }
-}
@@ -1,9 +1,14 @@
+use std::io::BufRead;
 use std::ops::Range;
+use crate::utils::parse::from_reader;
+
+pub const TITLE: &str = "Disk Fragmenter";
 
 type Position = u32;
 type FileId = u32;
 
 #[derive(Clone,Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize,serde::Serialize))]
 pub struct FileRange {
   range: Range<Position>,
   id: FileId,
@@ -15,20 +20,29 @@ impl FileRange {
   }
 }
 
-pub fn generator(input: &str) -> Vec<FileRange> {
-  let mut next_address = 0;
+pub fn try_generator(input: &str) -> Result<Vec<FileRange>, String> {
+  let mut next_address: Position = 0;
   let mut result = Vec::new();
   let mut is_file = true;
   for ch in input.trim().chars() {
-    let size = ch.to_digit(10).unwrap();
+    let size = ch.to_digit(10).ok_or(format!("Invalid size '{ch}'"))?;
+    let end = next_address.checked_add(size).ok_or("Disk map is too large")?;
     if is_file {
       let id = result.len() as Position;
-      result.push(FileRange{range: next_address..(next_address + size), id });
+      result.push(FileRange{range: next_address..end, id });
     }
-    next_address += size;
+    next_address = end;
     is_file = !is_file;
   }
-  result
+  Ok(result)
+}
+
+pub fn generator(input: &str) -> Vec<FileRange> {
+  try_generator(input).expect("Can't parse input")
+}
+
+pub fn generator_from_reader(reader: impl BufRead) -> Result<Vec<FileRange>, String> {
+  from_reader(reader, try_generator)
 }
 
 fn compacted_size(files: &[FileRange]) -> Position {
@@ -169,6 +183,18 @@ fn checksum(files: &[FileRange]) -> u64 {
   files.iter().map(|f| f.checksum()).sum()
 }
 
+/// Draw the disk a block at a time like the puzzle does, with the last
+/// digit of the file id in each used block and '.' for the free ones.
+pub fn disk_layout(files: &[FileRange]) -> String {
+  let size = files.iter().map(|f| f.range.end).max().unwrap_or(0);
+  let mut result = vec!['.'; size as usize];
+  for f in files {
+    let digit = char::from_digit(f.id % 10, 10).unwrap();
+    result[f.range.start as usize..f.range.end as usize].fill(digit);
+  }
+  result.into_iter().collect()
+}
+
 pub fn part1(input: &[FileRange]) -> u64 {
   checksum(&compact(input))
 }
@@ -177,21 +203,86 @@ pub fn part2(input: &[FileRange]) -> u64 {
   checksum(&file_compact(input))
 }
 
+/// The hot loops of the day, for benches/internals.rs.
+#[cfg(feature = "bench-internals")]
+pub mod internals {
+  use super::{FileCompactor, FileRange};
+
+  /// Take every file from a compactor with next_file and count them.
+  pub fn next_files(files: &[FileRange]) -> usize {
+    let mut compactor = FileCompactor::from_files(files);
+    std::iter::from_fn(|| compactor.next_file()).count()
+  }
+}
+
 #[cfg(test)]
 mod tests {
-  use super::{generator, part1, part2};
+  use proptest::prelude::*;
+  use crate::testgen;
+  use super::{compact, disk_layout, file_compact, generator, part1, part2};
 
-  const INPUT: &str = "2333133121414131402";
+  /// Lay the disk out block by block with the file id in each used block.
+  fn blocks(input: &str) -> Vec<Option<u64>> {
+    input.chars().enumerate()
+        .flat_map(|(i, ch)| {
+          let id = (i % 2 == 0).then_some(i as u64 / 2);
+          std::iter::repeat_n(id, ch.to_digit(10).unwrap() as usize)
+        }).collect()
+  }
 
-  #[test]
-  fn test_part1() {
-    let data = generator(INPUT);
-    assert_eq!(1928, part1(&data));
+  fn block_checksum(disk: &[Option<u64>]) -> u64 {
+    disk.iter().enumerate().map(|(posn, id)| posn as u64 * id.unwrap_or(0)).sum()
+  }
+
+  /// Move the last block to the first free space until there are no gaps.
+  fn slow_part1(input: &str) -> u64 {
+    let mut disk = blocks(input);
+    let mut left = 0;
+    while let Some(&last) = disk.last() {
+      while left < disk.len() && disk[left].is_some() {
+        left += 1;
+      }
+      if left >= disk.len() {
+        break;
+      }
+      disk.pop();
+      if last.is_some() {
+        disk[left] = last;
+      }
+    }
+    block_checksum(&disk)
+  }
+
+  /// Move each whole file, from the highest id down, to the first free space
+  /// to its left that can hold it.
+  fn slow_part2(input: &str) -> u64 {
+    let mut disk = blocks(input);
+    let files = input.len().div_ceil(2) as u64;
+    for id in (0..files).rev() {
+      let start = disk.iter().position(|&b| b == Some(id)).unwrap();
+      let size = disk.iter().filter(|&&b| b == Some(id)).count();
+      let free = (0..start).find(|&posn| disk[posn..posn + size].iter().all(Option::is_none));
+      if let Some(free) = free {
+        disk[free..free + size].fill(Some(id));
+        disk[start..start + size].fill(None);
+      }
+    }
+    block_checksum(&disk)
   }
 
   #[test]
-  fn test_part2() {
-    let data = generator(INPUT);
-    assert_eq!(2858, part2(&data));
+  fn test_disk_layout() {
+    let files = generator("2333133121414131402");
+    insta::assert_snapshot!("disk_layout", [disk_layout(&files), disk_layout(&compact(&files)),
+                                          disk_layout(&file_compact(&files))].join("\n"));
+  }
+
+  proptest! {
+    #[test]
+    fn test_compaction(input in testgen::disk_map(30)) {
+      let files = generator(&input);
+      prop_assert_eq!(slow_part1(&input), part1(&files));
+      prop_assert_eq!(slow_part2(&input), part2(&files));
+    }
   }
 }
@@ -0,0 +1,72 @@
+//! Check each day against the example inputs in tests/data. The expected
+//! answers are in tests/data/examples.yml, so adding another example only
+//! needs a new file and a manifest entry.
+use std::fs;
+use std::path::Path;
+use omalley_aoc2024 as aoc_lib;
+use aoc_lib::check::{check_examples, read_manifest};
+
+#[test]
+fn test_examples() {
+  let data = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/data");
+  let manifest = read_manifest(&data).expect("Can't read the manifest");
+  let mut failures = Vec::new();
+  let mut checked = 0;
+  for day in manifest.keys() {
+    let solver = aoc_lib::y2024::SOLVERS.iter().find(|solver| solver.name() == day)
+        .unwrap_or_else(|| panic!("Unknown day {day} in the manifest"));
+    for check in check_examples(*solver, &data, &manifest).unwrap_or_else(|e| panic!("{e}")) {
+      checked += 1;
+      if !check.passed() {
+        failures.push(format!("{day}/{} part{}: expected {}, got {}", check.input, check.part,
+                              check.expected.unwrap_or_default(), check.actual));
+      }
+    }
+  }
+  assert!(checked > 0, "No examples were checked");
+  assert!(failures.is_empty(), "Examples failed:\n{}", failures.join("\n"));
+}
+
+#[test]
+fn test_examples_from_reader() {
+  // --stream parses the inputs from a reader, which must give the same answers
+  let data = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/data");
+  let manifest = read_manifest(&data).expect("Can't read the manifest");
+  for (day, examples) in &manifest {
+    let solver = aoc_lib::y2024::SOLVERS.iter().find(|solver| solver.name() == day)
+        .unwrap_or_else(|| panic!("Unknown day {day} in the manifest"));
+    for example in examples {
+      let input = fs::read(data.join(day).join(&example.file)).unwrap();
+      let parsed = solver.try_generator_from_reader(&mut input.as_slice())
+          .unwrap_or_else(|e| panic!("{day}/{}: {e}", example.file));
+      if let Some(expected) = &example.part1 {
+        assert_eq!(Ok(expected.clone()), solver.part1(&parsed).map(|a| a.to_string()),
+                   "{day}/{} part1", example.file);
+      }
+      if let Some(expected) = &example.part2 {
+        assert_eq!(Ok(expected.clone()), solver.part2(&parsed).map(|a| a.to_string()),
+                   "{day}/{} part2", example.file);
+      }
+    }
+  }
+}
+
+#[test]
+fn test_fixtures_listed() {
+  // catch example files that were added without their answers
+  let data = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/data");
+  let manifest = read_manifest(&data).expect("Can't read the manifest");
+  for entry in fs::read_dir(&data).unwrap() {
+    let entry = entry.unwrap();
+    if !entry.file_type().unwrap().is_dir() {
+      continue;
+    }
+    let day = entry.file_name().to_string_lossy().to_string();
+    let listed = manifest.get(&day).map(Vec::as_slice).unwrap_or_default();
+    for file in fs::read_dir(entry.path()).unwrap() {
+      let file = file.unwrap().file_name().to_string_lossy().to_string();
+      assert!(listed.iter().any(|example| example.file == file),
+              "{day}/{file} isn't in examples.yml");
+    }
+  }
+}
@@ -0,0 +1,47 @@
+//! The browser's entry points into the solutions. Build it with
+//! `wasm-pack build --target web --out-dir www/pkg` and serve the www
+//! directory to get the playground.
+use omalley_aoc2024::{y2024::NAMES, DEFAULT_YEAR};
+use wasm_bindgen::prelude::*;
+
+/// The names of the implemented days, such as "day1".
+#[wasm_bindgen]
+pub fn days() -> Vec<String> {
+  NAMES.iter().map(|name| name.to_string()).collect()
+}
+
+/// The registry's description of each day as a JSON array, with the day's
+/// number, title, and answer types.
+#[wasm_bindgen]
+pub fn registry() -> String {
+  serde_json::to_string(&omalley_aoc2024::registry()).expect("Registry can be serialized")
+}
+
+/// Solve one part of a day from the input text. Since a panic kills the
+/// module, problems with the input, such as a day 17 program that can't
+/// print itself, come back as an "Error: " answer.
+#[wasm_bindgen]
+pub fn solve(day: u32, part: u32, input: &str) -> String {
+  omalley_aoc2024::solve(DEFAULT_YEAR, day as usize, part as usize, input)
+      .map_or_else(|e| format!("Error: {e}"), |answer| answer.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+  use omalley_aoc2024::y2024::day17;
+  use super::{days, registry, solve};
+
+  #[test]
+  fn test_solve() {
+    let input = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3";
+    assert_eq!("11", solve(1, 1, input));
+    assert_eq!("31", solve(1, 2, input));
+    assert_eq!("Error: Part 3 doesn't exist", solve(1, 3, input));
+    assert_eq!("Error: Day 30 of 2024 isn't implemented", solve(30, 1, input));
+    assert!(solve(9, 1, "12x").starts_with("Error: "));
+    let quine = "Register A: 5\nRegister B: 0\nRegister C: 0\n\nProgram: 0,1,5,4,3,0";
+    assert_eq!(format!("Error: {}", day17::NO_QUINE), solve(17, 2, quine));
+    assert_eq!("day1", days()[0]);
+    assert!(registry().contains("\"title\":\"Historian Hysteria\""));
+  }
+}